@@ -10,6 +10,7 @@ debug-command-need-1arg = Debug command "{$command}" needs 1 argument.
 debug-command-failed = Debug command "{$command}" failed.
 debug-command-genchara = Character "{$chara}" is generated.
 debug-command-genitem = Item "{$item}" is generated.
+debug-command-reload = Objects, rules and texts are reloaded.
 
 # Messages about tile information
 
@@ -47,7 +48,7 @@ killed = {$chara} was killed.
 killed-by-melee-attack = {$chara} was killed.
 killed-by-ranged-attack = {$chara} was killed.
 killed-by-explosion = {$chara} was killed.
-killed-by-poison-damage = {$chara} was killed by poison.
+killed-by-status-damage = {$chara} was killed by status effects.
 killed-by-starve-damage = {$chara} starved to death.
 killed-by-encumbrance-damage = {$chara} was killed by their own weight.
 
@@ -88,11 +89,12 @@ use_item-deed-succeed = You built new home!
 # Messages when a character is affected
 
 heal-hp = {$chara} was healed ({$value}).
-fall-asleep = {$chara} fell asleep.
-poisoned = {$chara} was poisoned.
-scanned = {$chara} was scanned.
+status_effect-asleep = {$chara} fell asleep.
+status_effect-poisoned = {$chara} was poisoned.
+status_effect-scanned = {$chara} was scanned.
 not-scanned = {$chara} isn't scanned.
-asleep = {$chara} is asleep.
+cannot-act = {$chara} cannot act ({$status}).
+action-restricted = {$chara} cannot do that now.
 poison-damage = {$chara} was damaged by poison ({$damage}).
 
 # Messages about shops
@@ -1 +1,2 @@
 item-charges = charges
+save-autosave = autosave
@@ -3,6 +3,11 @@ dialog-exit_to_regionmap = Do you want to exit to region map?
 dialog-gameover = You die...
 dialog-move_floor = Do you want to move from this floor?
 dialog-enter_site = Do you want to enter {$site_name}?
+dialog-data_mismatch = Paks or rules have been changed since this game was saved.
+dialog-data_mismatch-missing = Missing: {$list}
+dialog-data_mismatch-added = Added: {$list}
+dialog-data_mismatch-changed = Changed: {$list}
+dialog-data_mismatch-confirm = Do you want to load this game?
 newgame-choose_class = Choose your class
 newgame-choose_trait = Choose your traits
 newgame-input_player_name = Please input your name
@@ -34,6 +34,7 @@ label_text-status-carry = Carry
 label_text-status-faction = Faction
 label_text-status-travel_speed = Travel Speed
 label_text-play_time = Play Time
+label_text-save_info-level = Level {$level}
 label_text-creation-use-facility = Facility to use
 label_text-creation-required-facility = Required facility
 label_text-creation-no-required-facility = No required facility
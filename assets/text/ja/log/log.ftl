@@ -10,6 +10,7 @@ debug-command-need-1arg = Debug command "{$command}" needs 1 argument.
 debug-command-failed = Debug command "{$command}" failed.
 debug-command-genchara = Character "{$chara}" is generated.
 debug-command-genitem = Item "{$item}" is generated.
+debug-command-reload = Objects, rules and texts are reloaded.
 
 # Messages about tile information
 
@@ -47,7 +48,7 @@ killed = {$chara}は倒された。
 killed-by-melee-attack = {$chara}は倒された。
 killed-by-ranged-attack = {$chara}は倒された。
 killed-by-explosion = {$chara}は爆死した。
-killed-by-poison-damage = {$chara}は毒により死んだ。
+killed-by-status-damage = {$chara}は状態異常により死んだ。
 killed-by-starve-damage = {$chara}は餓死した。
 killed-by-encumbrance-damage = {$chara}は重さに耐えられず死んだ。
 
@@ -88,11 +89,12 @@ use_item-deed-succeed = 新たな家を建設した！
 # Messages when a character is affected
 
 heal-hp = {$chara}は回復した({$value})。
-fall-asleep = {$chara}は眠りに落ちた。
-poisoned = {$chara}は毒を受けた。
-scanned = {$chara}のスキャンが完了した。
+status_effect-asleep = {$chara}は眠りに落ちた。
+status_effect-poisoned = {$chara}は毒を受けた。
+status_effect-scanned = {$chara}のスキャンが完了した。
 not-scanned = {$chara}はまだスキャンされていない。
-asleep = {$chara}は眠っている。
+cannot-act = {$chara}は行動できない({$status})。
+action-restricted = {$chara}は今それを行えない。
 poison-damage = {$chara}は毒のダメージを受けた({$damage})。
 
 # Messages about shops
@@ -1 +1,2 @@
 item-charges = チャージ回数
+save-autosave = オートセーブ
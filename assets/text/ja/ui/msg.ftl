@@ -3,6 +3,11 @@ dialog-exit_to_regionmap = リージョンマップに戻りますか?
 dialog-gameover = やられてしまった…。
 dialog-move_floor = この階から移動しますか?
 dialog-enter_site = {$site_name}に入りますか?
+dialog-data_mismatch = セーブ時からパックまたはルールが変更されています。
+dialog-data_mismatch-missing = 見つからない: {$list}
+dialog-data_mismatch-added = 追加: {$list}
+dialog-data_mismatch-changed = 変更: {$list}
+dialog-data_mismatch-confirm = このゲームをロードしますか?
 newgame-choose_class = クラスを選択して下さい
 newgame-choose_trait = 特性を選択して下さい
 newgame-input_player_name = プレイヤー名を入力して下さい
@@ -34,6 +34,7 @@ label_text-status-carry = 運搬力
 label_text-status-faction = 所属
 label_text-status-travel_speed = 移動速度
 label_text-play_time = プレイ時間
+label_text-save_info-level = レベル {$level}
 label_text-creation-use-facility = 必要な設備
 label_text-creation-required-facility = 必要な設備
 label_text-creation-no-required-facility = 設備不要
@@ -1,3 +1,5 @@
+use schemars::JsonSchema;
+
 // Basic parameters for this game
 
 /// Size of one tile
@@ -47,6 +49,11 @@ pub const MISC_TXT_DIR: &str = "misc";
 pub const READABLE_TXT_DIR: &str = "readable";
 pub const SAVE_DIR_NAME: &str = "save";
 pub const SAVE_EXTENSION: &str = "rrsve";
+pub const SAVE_ARCHIVE_EXTENSION: &str = "rrsave";
+
+/// Save format version. Increment this when the layout of save data is changed,
+/// and add a migration step in saveload::migration.
+pub const SAVE_FORMAT_VERSION: u32 = 2;
 
 /// Id table
 pub const ID_TABLE_SECTION_TAG: &str = "§";
@@ -68,7 +75,9 @@ pub const ARRAY_STR_ID_LEN: usize = 15;
 pub type ArrayStringId = arrayvec::ArrayString<ARRAY_STR_ID_LEN>;
 
 /// Bonus / penalty representation used in this game
-#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Debug, Serialize, Deserialize)]
+#[derive(
+    Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Debug, Serialize, Deserialize, JsonSchema,
+)]
 #[repr(i8)]
 pub enum BonusLevel {
     Awful = -4,
@@ -0,0 +1,252 @@
+//! Fingerprints of paks and rules used to play a game
+
+use once_cell::sync::Lazy;
+use std::fs;
+use std::hash::Hasher;
+use std::path::Path;
+use std::sync::RwLock;
+
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Debug, Serialize, Deserialize)]
+pub enum DataKind {
+    Pak,
+    Rules,
+}
+
+/// Identifies the content of a pak file or a rule directory
+#[derive(Clone, PartialEq, Eq, Debug, Serialize, Deserialize)]
+pub struct DataFingerprint {
+    pub kind: DataKind,
+    pub name: String,
+    #[serde(default)]
+    pub version: Option<String>,
+    /// Hash of the content
+    pub hash: String,
+}
+
+/// Differences between the data recorded in a save and the current data
+#[derive(Clone, Default, PartialEq, Eq, Debug)]
+pub struct FingerprintDiff {
+    /// Recorded in the save, but not found in the current data
+    pub missing: Vec<DataFingerprint>,
+    /// Not recorded in the save
+    pub added: Vec<DataFingerprint>,
+    /// Found in both, but the content is different
+    pub changed: Vec<DataFingerprint>,
+}
+
+impl FingerprintDiff {
+    pub fn is_empty(&self) -> bool {
+        self.missing.is_empty() && self.added.is_empty() && self.changed.is_empty()
+    }
+}
+
+static CURRENT: Lazy<RwLock<Vec<DataFingerprint>>> = Lazy::new(|| RwLock::new(Vec::new()));
+
+/// Set fingerprints of the current data, which are recorded in saves
+pub fn set_current(fingerprints: Vec<DataFingerprint>) {
+    *CURRENT.write().unwrap() = fingerprints;
+}
+
+pub fn current() -> Vec<DataFingerprint> {
+    CURRENT.read().unwrap().clone()
+}
+
+/// Compare fingerprints in a save with the current data
+pub fn compare(saved: &[DataFingerprint], current: &[DataFingerprint]) -> FingerprintDiff {
+    let find = |list: &[DataFingerprint], a: &DataFingerprint| {
+        list.iter()
+            .find(|b| a.kind == b.kind && a.name == b.name)
+            .cloned()
+    };
+
+    let mut diff = FingerprintDiff::default();
+    for a in saved {
+        match find(current, a) {
+            Some(b) if a.hash != b.hash || a.version != b.version => diff.changed.push(b),
+            Some(_) => (),
+            None => diff.missing.push(a.clone()),
+        }
+    }
+    for b in current {
+        if find(saved, b).is_none() {
+            diff.added.push(b.clone());
+        }
+    }
+    diff
+}
+
+/// Get fingerprints of pak files in the given directories.
+/// Paks are named by the name and version in their manifests.
+pub fn pak_fingerprints<P: AsRef<Path>>(pak_dirs: &[P]) -> Vec<DataFingerprint> {
+    let mut fingerprints = Vec::new();
+    for pak_dir in pak_dirs {
+        let pak_dir = pak_dir.as_ref();
+        let mut files = Vec::new();
+        collect_files(pak_dir, "pak", &mut files);
+        for path in files {
+            let data = match fs::read(&path) {
+                Ok(data) => data,
+                Err(e) => {
+                    warn!("cannot read \"{}\": {}", path.display(), e);
+                    continue;
+                }
+            };
+            // Paks without manifest are identified by the path
+            let (name, version) = match crate::pakutil::read_pak_manifest(&path) {
+                Ok(Some(manifest)) => {
+                    let version = Some(manifest.version).filter(|v| !v.is_empty());
+                    (manifest.name, version)
+                }
+                Ok(None) => (relative_name(pak_dir, &path), None),
+                Err(e) => {
+                    warn!("cannot read manifest of \"{}\": {:?}", path.display(), e);
+                    (relative_name(pak_dir, &path), None)
+                }
+            };
+            fingerprints.push(DataFingerprint {
+                kind: DataKind::Pak,
+                name,
+                version,
+                hash: hash_to_string(&[&data]),
+            });
+        }
+    }
+    fingerprints
+}
+
+/// Get the fingerprint of a rule directory. Returns None if the directory does not exist.
+/// The name is "<parent directory name>/<directory name>", e.g. "assets/rules".
+pub fn rules_fingerprint<P: AsRef<Path>>(rules_dir: P) -> Option<DataFingerprint> {
+    let rules_dir = rules_dir.as_ref();
+    if !rules_dir.is_dir() {
+        return None;
+    }
+    let mut files = Vec::new();
+    collect_files(rules_dir, "ron", &mut files);
+
+    let mut contents = Vec::new();
+    for path in &files {
+        let mut content = relative_name(rules_dir, path).into_bytes();
+        match fs::read(path) {
+            Ok(data) => content.extend_from_slice(&data),
+            Err(e) => warn!("cannot read \"{}\": {}", path.display(), e),
+        }
+        contents.push(content);
+    }
+    let contents: Vec<&[u8]> = contents.iter().map(|c| c.as_slice()).collect();
+
+    let name = [
+        rules_dir.parent().and_then(|p| p.file_name()),
+        rules_dir.file_name(),
+    ]
+    .iter()
+    .flatten()
+    .map(|s| s.to_string_lossy())
+    .collect::<Vec<_>>()
+    .join("/");
+
+    Some(DataFingerprint {
+        kind: DataKind::Rules,
+        name,
+        version: None,
+        hash: hash_to_string(&contents),
+    })
+}
+
+/// Collect files which have the given extension recursively, sorted by path
+fn collect_files(dir: &Path, extension: &str, files: &mut Vec<std::path::PathBuf>) {
+    let entries = match fs::read_dir(dir) {
+        Ok(entries) => entries,
+        Err(_) => return,
+    };
+    let mut paths: Vec<_> = entries.filter_map(|e| e.ok()).map(|e| e.path()).collect();
+    paths.sort();
+    for path in paths {
+        if path.is_dir() {
+            collect_files(&path, extension, files);
+        } else if path.extension().is_some_and(|e| e == extension) {
+            files.push(path);
+        }
+    }
+}
+
+fn relative_name(base: &Path, path: &Path) -> String {
+    let path = path.strip_prefix(base).unwrap_or(path);
+    path.components()
+        .map(|c| c.as_os_str().to_string_lossy())
+        .collect::<Vec<_>>()
+        .join("/")
+}
+
+fn hash_to_string(data: &[&[u8]]) -> String {
+    let mut hasher = fnv::FnvHasher::default();
+    for d in data {
+        hasher.write(d);
+        hasher.write_u8(0);
+    }
+    format!("{:016x}", hasher.finish())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn pak(name: &str, hash: &str) -> DataFingerprint {
+        DataFingerprint {
+            kind: DataKind::Pak,
+            name: name.into(),
+            version: None,
+            hash: hash.into(),
+        }
+    }
+
+    #[test]
+    fn compare_fingerprints() {
+        let saved = vec![pak("a.pak", "1"), pak("b.pak", "2"), pak("c.pak", "3")];
+        let current = vec![pak("a.pak", "1"), pak("b.pak", "4"), pak("d.pak", "5")];
+        let diff = compare(&saved, &current);
+        assert_eq!(diff.missing, vec![pak("c.pak", "3")]);
+        assert_eq!(diff.added, vec![pak("d.pak", "5")]);
+        assert_eq!(diff.changed, vec![pak("b.pak", "4")]);
+
+        assert!(compare(&saved, &saved).is_empty());
+    }
+
+    fn write_pak(path: &Path, manifest: Option<&crate::pakutil::PakManifest>) {
+        use crate::pakutil::{write_pak_manifest, PAK_MANIFEST_ENTRY};
+
+        let mut builder = tar::Builder::new(fs::File::create(path).unwrap());
+        if let Some(manifest) = manifest {
+            let mut data = Vec::new();
+            write_pak_manifest(&mut data, manifest).unwrap();
+            let mut header = tar::Header::new_gnu();
+            header.set_path(PAK_MANIFEST_ENTRY).unwrap();
+            header.set_size(data.len() as u64);
+            header.set_cksum();
+            builder.append(&header, &data[..]).unwrap();
+        }
+        builder.finish().unwrap();
+    }
+
+    #[test]
+    fn pak_manifest_fingerprints() {
+        let dir = crate::saveload::test_dir("pak-fingerprints");
+        let manifest = crate::pakutil::PakManifest {
+            name: "base".into(),
+            version: "1.2.0".into(),
+            ..Default::default()
+        };
+        write_pak(&dir.join("a.pak"), Some(&manifest));
+        write_pak(&dir.join("b.pak"), None);
+
+        let fingerprints = pak_fingerprints(&[&dir]);
+        assert_eq!(fingerprints.len(), 2);
+        assert_eq!(fingerprints[0].name, "base");
+        assert_eq!(fingerprints[0].version.as_deref(), Some("1.2.0"));
+        assert_eq!(fingerprints[1].name, "b.pak");
+        assert_eq!(fingerprints[1].version, None);
+        assert_ne!(fingerprints[0].hash, fingerprints[1].hash);
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -12,6 +12,7 @@ use crate::item_selector::ItemSelector;
 use crate::objholder::{CharaTemplateIdx, ItemIdx};
 use arrayvec::ArrayString;
 use geom::Coords;
+use schemars::JsonSchema;
 
 #[derive(Serialize, Deserialize)]
 pub struct CharaTemplateObject {
@@ -38,7 +39,7 @@ pub struct CharaTemplateObject {
     pub drop_items: Vec<DropItem>,
 }
 
-#[derive(Clone, PartialEq, Eq, Debug, Serialize, Deserialize)]
+#[derive(Clone, PartialEq, Eq, Debug, Serialize, Deserialize, JsonSchema)]
 pub struct EquipGen {
     pub esk: EquipSlotKind,
     pub item_selector: ItemSelector,
@@ -48,7 +49,7 @@ pub struct EquipGen {
     pub quality_bonus: i32,
 }
 
-#[derive(Clone, PartialEq, Debug, Serialize, Deserialize)]
+#[derive(Clone, PartialEq, Debug, Serialize, Deserialize, JsonSchema)]
 pub struct DropItem {
     pub item_selector: ItemSelector,
     #[serde(default)]
@@ -61,7 +62,7 @@ pub struct DropItem {
 }
 
 /// Character classes
-#[derive(Clone, Copy, Hash, PartialEq, Eq, Default, Debug, Serialize, Deserialize)]
+#[derive(Clone, Copy, Hash, PartialEq, Eq, Default, Debug, Serialize, Deserialize, JsonSchema)]
 #[serde(transparent)]
 pub struct CharaClass(ArrayStringId);
 
@@ -150,7 +151,7 @@ pub struct CharaBaseAttr {
     pub travel_speed: i16,
 }
 
-#[derive(Clone, Copy, Debug, Serialize, Deserialize)]
+#[derive(Clone, Copy, Debug, Serialize, Deserialize, JsonSchema)]
 pub struct CharaAttrDiff {
     pub base_hp: i32,
     pub base_mp: i32,
@@ -180,12 +181,13 @@ pub enum CharaStatus {
     Strained,
     /// Encumbrance status
     Overloaded,
-    /// Scanned and can open StatusWindow
-    Scanned,
-    Asleep {
-        turn_left: u16,
+    /// Status effect defined in the status_effects rule
+    Effect {
+        id: String,
+        /// None if this status is permanent
+        turn_left: Option<u16>,
+        stack: u16,
     },
-    Poisoned,
     Work {
         turn_left: u16,
         needed_turn: u16,
@@ -195,19 +197,17 @@ pub enum CharaStatus {
 
 impl CharaStatus {
     pub fn turn_left(&self) -> Option<u16> {
-        match self {
-            &CharaStatus::Asleep { turn_left } | &CharaStatus::Work { turn_left, .. } => {
-                Some(turn_left)
-            }
+        match *self {
+            CharaStatus::Effect { turn_left, .. } => turn_left,
+            CharaStatus::Work { turn_left, .. } => Some(turn_left),
             _ => None,
         }
     }
 
     pub fn turn_left_mut(&mut self) -> Option<&mut u16> {
         match self {
-            CharaStatus::Asleep { turn_left } | CharaStatus::Work { turn_left, .. } => {
-                Some(turn_left)
-            }
+            CharaStatus::Effect { turn_left, .. } => turn_left.as_mut(),
+            CharaStatus::Work { turn_left, .. } => Some(turn_left),
             _ => None,
         }
     }
@@ -284,10 +284,16 @@ pub struct CharaAi {
 }
 
 /// Rough kind of NPC AI
-#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug, Serialize, Deserialize)]
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug, Serialize, Deserialize, JsonSchema)]
 #[serde(transparent)]
 pub struct NpcAiKind(ArrayStringId);
 
+impl NpcAiKind {
+    pub fn as_str(&self) -> &str {
+        self.0.as_str()
+    }
+}
+
 impl Default for NpcAiKind {
     fn default() -> NpcAiKind {
         NpcAiKind(ArrayStringId::from("default").unwrap())
@@ -336,7 +342,7 @@ impl AiState {
     }
 }
 
-#[derive(Serialize, Deserialize)]
+#[derive(Clone, Serialize, Deserialize)]
 pub struct CharaHolder {
     c: HashMap<CharaId, Chara>,
     on_map: HashMap<CharaId, Chara>,
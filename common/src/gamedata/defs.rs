@@ -1,6 +1,7 @@
 //! Miscellaneous type definitions
 
 use ordered_float::NotNan;
+use schemars::JsonSchema;
 
 use crate::gamedata::effect::Effect;
 use crate::gamedata::skill::{SkillKind, SpecialSkillKind, WeaponKind};
@@ -9,7 +10,9 @@ use enum_map::Enum;
 use std::ops::{Index, IndexMut};
 
 /// Elements of damage/attack
-#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Debug, Serialize, Deserialize)]
+#[derive(
+    Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Debug, Serialize, Deserialize, JsonSchema,
+)]
 pub enum Element {
     None = -1,
     Physical = 0,
@@ -30,7 +33,9 @@ pub const ELEMENTS: [Element; Element::Spirit as usize + 1] = [
 ];
 
 /// This array has the same size as element types.
-#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Debug, Default, Serialize, Deserialize)]
+#[derive(
+    Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Debug, Default, Serialize, Deserialize, JsonSchema,
+)]
 #[serde(transparent)]
 pub struct ElementArray<T>(pub [T; Element::Spirit as usize + 1]);
 
@@ -49,7 +54,9 @@ impl<T> IndexMut<Element> for ElementArray<T> {
     }
 }
 
-#[derive(Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Default, Debug, Serialize, Deserialize)]
+#[derive(
+    Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Default, Debug, Serialize, Deserialize, JsonSchema,
+)]
 #[serde(transparent)]
 pub struct ElementProtection(i8);
 
@@ -62,7 +69,7 @@ pub enum AttackKind {
 }
 
 /// A recipe for creation
-#[derive(Clone, PartialEq, Eq, PartialOrd, Ord, Debug, Serialize, Deserialize)]
+#[derive(Clone, PartialEq, Eq, PartialOrd, Ord, Debug, Serialize, Deserialize, JsonSchema)]
 pub struct Recipe {
     pub product: String,
     pub ingredients: Vec<(String, u32)>,
@@ -73,7 +80,9 @@ pub struct Recipe {
     pub put_on_ground: bool,
 }
 
-#[derive(Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Debug, Serialize, Deserialize)]
+#[derive(
+    Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Debug, Serialize, Deserialize, JsonSchema,
+)]
 pub enum CreationRequiredTime {
     VeryShort,
     Short,
@@ -82,14 +91,16 @@ pub enum CreationRequiredTime {
     VeryLong,
 }
 
-#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Debug, Serialize, Deserialize)]
+#[derive(
+    Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Debug, Serialize, Deserialize, JsonSchema,
+)]
 pub enum ToolEffect {
     Build,
     Chop,
     Mine,
 }
 
-#[derive(Clone, PartialEq, Eq, PartialOrd, Ord, Debug, Serialize, Deserialize)]
+#[derive(Clone, PartialEq, Eq, PartialOrd, Ord, Debug, Serialize, Deserialize, JsonSchema)]
 pub enum UseEffect {
     Effect(Effect),
     Deed,
@@ -99,11 +110,12 @@ pub enum UseEffect {
 }
 
 /// Reward for quests or events
-#[derive(Clone, PartialEq, Eq, Default, Debug, Serialize, Deserialize)]
+#[derive(Clone, PartialEq, Eq, Default, Debug, Serialize, Deserialize, JsonSchema)]
 pub struct Reward {
     #[serde(default)]
     pub money: i64,
     #[serde(default)]
+    #[schemars(with = "Vec<(u32, u32)>")]
     pub items: Vec<(ItemIdx, u32)>,
 }
 
@@ -115,7 +127,9 @@ pub struct Harvest {
     pub difficulty: u32,
 }
 
-#[derive(Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Debug, Serialize, Deserialize)]
+#[derive(
+    Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Debug, Serialize, Deserialize, JsonSchema,
+)]
 pub enum HarvestKind {
     Animal,
     Chop,
@@ -137,7 +151,9 @@ impl HarvestKind {
 }
 
 /// Ability id.
-#[derive(Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize, Debug)]
+#[derive(
+    Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize, Debug, JsonSchema,
+)]
 #[serde(transparent)]
 pub struct AbilityId(pub String);
 
@@ -147,7 +163,7 @@ impl std::fmt::Display for AbilityId {
     }
 }
 
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug, JsonSchema)]
 pub struct Ability {
     pub category: String,
     pub group: String,
@@ -162,13 +178,13 @@ pub struct Ability {
     pub cost_mp: u32,
 }
 
-#[derive(Clone, PartialEq, Eq, Debug, Serialize, Deserialize)]
+#[derive(Clone, PartialEq, Eq, Debug, Serialize, Deserialize, JsonSchema)]
 pub enum AbilityRequire {
     Level(u32),
     Skill(SkillKind, u32),
 }
 
-#[derive(Clone, PartialEq, Eq, Debug, Serialize, Deserialize)]
+#[derive(Clone, PartialEq, Eq, Debug, Serialize, Deserialize, JsonSchema)]
 pub enum PowerCalcMethod {
     Fixed,
     BareHands,
@@ -178,11 +194,17 @@ pub enum PowerCalcMethod {
     Magic(SkillKind),
     Medical,
     Throw(u32),
+    /// Formula like "(int*2 + skill(Magic))*1d6". Formula of hit can be given after ";".
     Custom(String),
 }
 
-#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Debug, Serialize, Deserialize)]
-pub struct BasePower(pub NotNan<f32>, pub NotNan<f32>);
+#[derive(
+    Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Debug, Serialize, Deserialize, JsonSchema,
+)]
+pub struct BasePower(
+    #[schemars(with = "f32")] pub NotNan<f32>,
+    #[schemars(with = "f32")] pub NotNan<f32>,
+);
 
 impl Default for BasePower {
     fn default() -> Self {
@@ -3,18 +3,22 @@ use super::skill::SkillKind;
 use super::BasePower;
 use geom::ShapeKind;
 use ordered_float::NotNan;
+use schemars::JsonSchema;
 
-#[derive(Clone, PartialEq, Eq, PartialOrd, Ord, Debug, Serialize, Deserialize)]
+#[derive(Clone, PartialEq, Eq, PartialOrd, Ord, Debug, Serialize, Deserialize, JsonSchema)]
 pub struct Effect {
     pub kind: Vec<EffectKind>,
     pub target_mode: TargetMode,
     #[serde(default)]
     pub base_power: BasePower,
     #[serde(default)]
+    #[schemars(with = "f32")]
     pub hit: NotNan<f32>,
     #[serde(default)]
+    #[schemars(with = "Vec<f32>")]
     pub power_adjust: Vec<NotNan<f32>>,
     pub range: u32,
+    #[schemars(with = "crate::schema::ShapeKindSchema")]
     pub shape: ShapeKind,
     pub size: u32,
     #[serde(default)]
@@ -47,22 +51,42 @@ impl Default for Effect {
 }
 
 /// Effect defines the game effect of items, magics, or other active skills.
-#[derive(Clone, PartialEq, Eq, PartialOrd, Ord, Debug, Serialize, Deserialize)]
+#[derive(Clone, PartialEq, Eq, PartialOrd, Ord, Debug, Serialize, Deserialize, JsonSchema)]
 pub enum EffectKind {
     None,
     RestoreHp,
     RestoreSp,
     RestoreMp,
-    Melee { element: Element },
-    Ranged { element: Element },
-    Explosion { element: Element },
-    Direct { element: Element },
-    Status { status: StatusEffect },
+    Melee {
+        element: Element,
+    },
+    Ranged {
+        element: Element,
+    },
+    Explosion {
+        element: Element,
+    },
+    Direct {
+        element: Element,
+    },
+    /// Gives the status effect of the id in the status_effects rule.
+    /// Ids were variants of a `StatusEffect` enum before, so its names are converted
+    /// when reading compiled paks. RON files need the id as a string, e.g. `status: "poisoned"`.
+    Status {
+        #[serde(deserialize_with = "deserialize_status_id")]
+        status: String,
+    },
     WallDamage,
     CharaScan,
-    SkillLearning { skills: Vec<SkillKind> },
-    PlaceTile { tile: String },
-    GenItem { id: String },
+    SkillLearning {
+        skills: Vec<SkillKind>,
+    },
+    PlaceTile {
+        tile: String,
+    },
+    GenItem {
+        id: String,
+    },
 }
 
 impl Default for EffectKind {
@@ -72,7 +96,7 @@ impl Default for EffectKind {
 }
 
 /// Default kind for target selection, used by NPC AI.
-#[derive(Clone, PartialEq, Eq, PartialOrd, Ord, Debug, Serialize, Deserialize)]
+#[derive(Clone, PartialEq, Eq, PartialOrd, Ord, Debug, Serialize, Deserialize, JsonSchema)]
 pub enum TargetMode {
     None,
     Player,
@@ -86,16 +110,10 @@ impl Default for TargetMode {
     }
 }
 
-/// Effect defines the game effect of items, magics, or other active skills.
-#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Debug, Serialize, Deserialize)]
-pub enum StatusEffect {
-    Asleep,
-    Poison,
-    Scanned,
-}
-
 /// Animation kind for this effect.
-#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Debug, Serialize, Deserialize)]
+#[derive(
+    Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Debug, Serialize, Deserialize, JsonSchema,
+)]
 pub enum EffectAnimKind {
     None,
     Tile,
@@ -108,3 +126,120 @@ impl Default for EffectAnimKind {
         EffectAnimKind::None
     }
 }
+
+/// Status effect ids for the variants of the previous `StatusEffect` enum
+const OLD_STATUS_EFFECTS: &[(&str, &str)] = &[
+    ("Asleep", "asleep"),
+    ("Poison", "poisoned"),
+    ("Scanned", "scanned"),
+];
+
+/// Deserialize a status effect id. Variants of the previous enum are given as their names
+/// in named format, and as their indices in packed format.
+fn deserialize_status_id<'de, D: serde::Deserializer<'de>>(
+    deserializer: D,
+) -> Result<String, D::Error> {
+    use serde::de::{self, Visitor};
+
+    struct StatusIdVisitor;
+
+    impl<'de> Visitor<'de> for StatusIdVisitor {
+        type Value = String;
+
+        fn expecting(&self, formatter: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+            formatter.write_str("status effect id string, e.g. \"poisoned\"")
+        }
+
+        fn visit_str<E: de::Error>(self, value: &str) -> Result<String, E> {
+            Ok(OLD_STATUS_EFFECTS
+                .iter()
+                .find(|(name, _)| *name == value)
+                .map(|(_, id)| *id)
+                .unwrap_or(value)
+                .to_owned())
+        }
+
+        fn visit_u64<E: de::Error>(self, value: u64) -> Result<String, E> {
+            OLD_STATUS_EFFECTS
+                .get(value as usize)
+                .map(|(_, id)| (*id).to_owned())
+                .ok_or_else(|| E::invalid_value(de::Unexpected::Unsigned(value), &self))
+        }
+    }
+
+    deserializer.deserialize_any(StatusIdVisitor)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// `EffectKind` before status effects were moved to rules.
+    /// Variants before `Status` are needed for the variant index in packed format.
+    #[derive(Serialize)]
+    #[allow(dead_code)]
+    enum OldEffectKind {
+        None,
+        RestoreHp,
+        RestoreSp,
+        RestoreMp,
+        Melee,
+        Ranged,
+        Explosion,
+        Direct,
+        Status { status: OldStatusEffect },
+    }
+
+    #[derive(Serialize)]
+    #[allow(dead_code)]
+    enum OldStatusEffect {
+        Asleep,
+        Poison,
+        Scanned,
+    }
+
+    #[test]
+    fn old_status_effect() {
+        let old = vec![
+            OldEffectKind::Status {
+                status: OldStatusEffect::Asleep,
+            },
+            OldEffectKind::Status {
+                status: OldStatusEffect::Poison,
+            },
+            OldEffectKind::Status {
+                status: OldStatusEffect::Scanned,
+            },
+        ];
+        let status = |id: &str| EffectKind::Status { status: id.into() };
+        let expected = vec![status("asleep"), status("poisoned"), status("scanned")];
+
+        let packed = serde_cbor::ser::to_vec_packed(&old).unwrap();
+        let named = serde_cbor::to_vec(&old).unwrap();
+        for v in [packed, named] {
+            let kinds: Vec<EffectKind> = serde_cbor::from_slice(&v).unwrap();
+            assert_eq!(kinds, expected);
+        }
+        let kinds: Vec<EffectKind> =
+            serde_json::from_str(&serde_json::to_string(&old).unwrap()).unwrap();
+        assert_eq!(kinds, expected);
+    }
+
+    #[test]
+    fn status_effect_id() {
+        let kinds = vec![
+            EffectKind::Status {
+                status: "poisoned".into(),
+            },
+            EffectKind::Status {
+                status: "confused".into(),
+            },
+        ];
+        let packed = serde_cbor::ser::to_vec_packed(&kinds).unwrap();
+        assert_eq!(
+            serde_cbor::from_slice::<Vec<EffectKind>>(&packed).unwrap(),
+            kinds
+        );
+        assert!(serde_json::from_str::<EffectKind>(r#"{"Status": {"status": 3}}"#).is_err());
+    }
+}
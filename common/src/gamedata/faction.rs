@@ -1,6 +1,7 @@
 use crate::basic::ArrayStringId;
 use crate::hashmap::HashMap;
 use arrayvec::ArrayString;
+use schemars::JsonSchema;
 
 /// Faction information.
 #[derive(Clone, PartialEq, Eq, Debug, Serialize, Deserialize)]
@@ -34,7 +35,9 @@ impl Faction {
     }
 }
 
-#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Debug, Serialize, Deserialize)]
+#[derive(
+    Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Debug, Serialize, Deserialize, JsonSchema,
+)]
 #[serde(transparent)]
 pub struct FactionId(ArrayStringId);
 
@@ -73,7 +76,9 @@ impl FactionId {
     }
 }
 
-#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Debug, Serialize, Deserialize)]
+#[derive(
+    Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Debug, Serialize, Deserialize, JsonSchema,
+)]
 #[serde(transparent)]
 pub struct FactionRelation(i16);
 const FACTION_RELATION_MAX: i16 = 10000;
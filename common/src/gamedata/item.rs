@@ -8,6 +8,7 @@ use crate::basic::ArrayStringId;
 use crate::objholder::ItemIdx;
 use bitflags::bitflags;
 use geom::Coords;
+use schemars::JsonSchema;
 use std::cmp::{Ord, Ordering, PartialOrd};
 use std::str::FromStr;
 
@@ -283,7 +284,9 @@ impl ItemQuality {
 }
 
 /// Quality kind for item.
-#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Debug, Serialize, Deserialize)]
+#[derive(
+    Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Debug, Serialize, Deserialize, JsonSchema,
+)]
 pub enum QualityKind {
     /// Item's quality is always zero including enchant and damage.
     None,
@@ -299,7 +302,9 @@ impl Default for QualityKind {
 
 pub type MaterialName = ArrayStringId;
 
-#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Debug, Serialize, Deserialize)]
+#[derive(
+    Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Debug, Serialize, Deserialize, JsonSchema,
+)]
 pub enum ArmorKind {
     Shield,
     Head,
@@ -428,9 +433,59 @@ pub type ItemLocation = (ItemListLocation, u32);
 /// Item list that records all items owned by one character or one tile
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct ItemList {
+    #[serde(deserialize_with = "deserialize_items")]
     pub items: Vec<(Item, u32)>,
 }
 
+/// Deserialize items, and remove items whose object is removed by the index conversion
+fn deserialize_items<'de, D: serde::Deserializer<'de>>(
+    deserializer: D,
+) -> Result<Vec<(Item, u32)>, D::Error> {
+    deserialize_items_with_removed(deserializer).map(|(items, _)| items)
+}
+
+/// Items and positions of removed items in the deserialized sequence
+type ItemsWithRemoved = (Vec<(Item, u32)>, Vec<usize>);
+
+/// Deserialize items, and returns positions of removed items also
+fn deserialize_items_with_removed<'de, D: serde::Deserializer<'de>>(
+    deserializer: D,
+) -> Result<ItemsWithRemoved, D::Error> {
+    use crate::idx_conv::check_removed_obj;
+    use serde::de::{SeqAccess, Visitor};
+
+    struct ItemsVisitor;
+
+    impl<'de> Visitor<'de> for ItemsVisitor {
+        type Value = ItemsWithRemoved;
+
+        fn expecting(&self, formatter: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+            formatter.write_str("a sequence of items")
+        }
+
+        fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<Self::Value, A::Error> {
+            let mut items = Vec::new();
+            let mut removed = Vec::new();
+            for i in 0.. {
+                let (item, is_removed) = check_removed_obj(|| seq.next_element::<(Item, u32)>());
+                let item = item?;
+
+                match item {
+                    Some(item) if is_removed => {
+                        trace!("Remove {} items of a removed object", item.1);
+                        removed.push(i);
+                    }
+                    Some(item) => items.push(item),
+                    None => break,
+                }
+            }
+            Ok((items, removed))
+        }
+    }
+
+    deserializer.deserialize_seq(ItemsVisitor)
+}
+
 impl Default for ItemList {
     fn default() -> Self {
         ItemList { items: Vec::new() }
@@ -608,7 +663,9 @@ impl From<u32> for ItemMoveNum {
 //
 
 /// equipment slots
-#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Debug, Serialize, Deserialize)]
+#[derive(
+    Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Debug, Serialize, Deserialize, JsonSchema,
+)]
 pub enum EquipSlotKind {
     MeleeWeapon,
     RangedWeapon,
@@ -660,12 +717,50 @@ impl ArmorKind {
 }
 
 #[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(from = "EquipItemListData")]
 pub struct EquipItemList {
     /// Slot information
     slots: Vec<SlotInfo>,
     item_list: ItemList,
 }
 
+/// Deserialized EquipItemList before fixing slots for removed items
+#[derive(Deserialize)]
+struct EquipItemListData {
+    slots: Vec<SlotInfo>,
+    item_list: ItemListData,
+}
+
+#[derive(Deserialize)]
+struct ItemListData {
+    #[serde(deserialize_with = "deserialize_items_with_removed")]
+    items: ItemsWithRemoved,
+}
+
+impl From<EquipItemListData> for EquipItemList {
+    fn from(data: EquipItemListData) -> Self {
+        let (items, removed) = data.item_list.items;
+        let mut slots = data.slots;
+        if !removed.is_empty() {
+            for slot in &mut slots {
+                if let Some(list_idx) = slot.list_idx {
+                    let list_idx = list_idx as usize;
+                    slot.list_idx = if removed.contains(&list_idx) {
+                        None
+                    } else {
+                        let n = removed.iter().filter(|r| **r < list_idx).count();
+                        Some((list_idx - n) as u8)
+                    };
+                }
+            }
+        }
+        EquipItemList {
+            slots,
+            item_list: ItemList { items },
+        }
+    }
+}
+
 #[derive(Clone, Copy, PartialEq, Eq, Debug, Serialize, Deserialize)]
 pub struct SlotInfo {
     /// The kind of equipment
@@ -891,3 +986,66 @@ impl<'a> Iterator for EquipItemIter<'a> {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn item() -> Item {
+        Item {
+            idx: ItemIdx::default(),
+            kind: ItemKind::Object,
+            flags: ItemFlags::empty(),
+            quality: ItemQuality::default(),
+            attrs: Vec::new(),
+            time: None,
+        }
+    }
+
+    #[test]
+    fn equip_slots_for_removed_items() {
+        let mut slots = EquipItemList::new(&[
+            EquipSlotKind::Head,
+            EquipSlotKind::Body,
+            EquipSlotKind::Arms,
+            EquipSlotKind::Legs,
+        ])
+        .slots;
+        for (i, slot) in slots.iter_mut().enumerate() {
+            slot.list_idx = Some(i as u8);
+        }
+        slots[3].list_idx = None;
+        let (head, body, arms) = (slots[0].esk, slots[1].esk, slots[2].esk);
+
+        // The item of the second slot is removed
+        let data = EquipItemListData {
+            slots,
+            item_list: ItemListData {
+                items: (vec![(item(), 1), (item(), 1)], vec![1]),
+            },
+        };
+        let list = EquipItemList::from(data);
+        assert_eq!(list.list_idx(head, 0), Some(0));
+        assert_eq!(list.list_idx(body, 0), None);
+        assert_eq!(list.list_idx(arms, 0), Some(1));
+        assert!(list.is_slot_empty(EquipSlotKind::Legs, 0));
+    }
+
+    #[test]
+    fn removed_obj_flag_outside_item_list() {
+        use crate::idx_conv::{check_removed_obj, set_removed_obj_found};
+
+        // Not recorded outside of item lists
+        set_removed_obj_found();
+        let ((), found) = check_removed_obj(|| ());
+        assert!(!found);
+
+        // Nested items do not affect the outer item
+        let (inner, outer) = check_removed_obj(|| {
+            let ((), inner) = check_removed_obj(set_removed_obj_found);
+            inner
+        });
+        assert!(inner);
+        assert!(!outer);
+    }
+}
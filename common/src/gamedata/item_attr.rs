@@ -1,4 +1,5 @@
 use ordered_float::NotNan;
+use schemars::JsonSchema;
 
 use crate::item_selector::ItemSelector;
 use crate::objholder::ItemIdx;
@@ -10,7 +11,7 @@ use super::{item::*, BasePower, UniqueId, UniqueIdGenerator};
 use std::cmp::{Eq, Ord, Ordering, PartialEq, PartialOrd};
 
 /// Attributes for ItemObject
-#[derive(Clone, PartialEq, Eq, PartialOrd, Ord, Debug, Serialize, Deserialize)]
+#[derive(Clone, PartialEq, Eq, PartialOrd, Ord, Debug, Serialize, Deserialize, JsonSchema)]
 pub enum ItemObjAttr {
     /// Item nutrition
     Nutrition(u16),
@@ -48,6 +49,7 @@ pub enum ItemObjAttr {
     /// Power for weapon items
     Weapon {
         base_power: BasePower,
+        #[schemars(with = "f32")]
         hit: NotNan<f32>,
     },
     /// Defence for armor items
@@ -82,6 +84,7 @@ pub enum ItemObjAttr {
     },
     /// Module effects with random choose weight
     Module {
+        #[schemars(with = "Vec<(ModuleEffect, f32)>")]
         effects: Vec<(ModuleEffect, NotNan<f32>)>,
     },
     CharaModifier(CharaModifier),
@@ -111,7 +114,7 @@ impl ItemObjAttr {
     }
 }
 
-#[derive(Clone, PartialEq, Eq, PartialOrd, Ord, Debug, Serialize, Deserialize)]
+#[derive(Clone, PartialEq, Eq, PartialOrd, Ord, Debug, Serialize, Deserialize, JsonSchema)]
 pub enum ContainerFunction {
     None,
     DeliveryChest,
@@ -171,7 +174,7 @@ pub enum ItemAttr {
     BuildObj(BuildObj),
 }
 
-#[derive(Clone, PartialEq, Eq, PartialOrd, Ord, Debug, Serialize, Deserialize)]
+#[derive(Clone, PartialEq, Eq, PartialOrd, Ord, Debug, Serialize, Deserialize, JsonSchema)]
 pub enum BuildObj {
     Tile(String),
     Wall(String),
@@ -241,7 +244,7 @@ pub enum ModuleSlotKind {
     Extend,
 }
 
-#[derive(Clone, PartialEq, Eq, PartialOrd, Ord, Debug, Serialize, Deserialize)]
+#[derive(Clone, PartialEq, Eq, PartialOrd, Ord, Debug, Serialize, Deserialize, JsonSchema)]
 pub enum ModuleEffect {
     Ability { group: String },
     Extend(ExtendModuleEffect),
@@ -258,14 +261,14 @@ impl ModuleEffect {
     }
 }
 
-#[derive(Clone, PartialEq, Eq, PartialOrd, Ord, Debug, Serialize, Deserialize)]
+#[derive(Clone, PartialEq, Eq, PartialOrd, Ord, Debug, Serialize, Deserialize, JsonSchema)]
 pub enum ExtendModuleEffect {
     Chara(CharaModifier),
     Weapon(WeaponModifier),
 }
 
 /// Represents modifier for weapon item
-#[derive(Clone, PartialEq, Eq, PartialOrd, Ord, Debug, Serialize, Deserialize)]
+#[derive(Clone, PartialEq, Eq, PartialOrd, Ord, Debug, Serialize, Deserialize, JsonSchema)]
 pub enum WeaponModifier {
-    PowerFactor(NotNan<f32>),
+    PowerFactor(#[schemars(with = "f32")] NotNan<f32>),
 }
@@ -8,6 +8,7 @@ use crate::hashmap::HashMap;
 use crate::objholder::*;
 use arrayvec::ArrayVec;
 use geom::*;
+use schemars::JsonSchema;
 use std::ops::{Index, IndexMut};
 
 pub use crate::piece_pattern::*;
@@ -107,7 +108,7 @@ pub enum StairsKind {
     DownStairs,
 }
 
-#[derive(Copy, Clone, PartialEq, Eq, Debug, Serialize, Deserialize)]
+#[derive(Copy, Clone, PartialEq, Eq, Debug, Serialize, Deserialize, JsonSchema)]
 #[serde(transparent)]
 pub struct SiteSymbolKind(ArrayStringId);
 
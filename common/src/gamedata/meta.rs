@@ -1,8 +1,30 @@
+use crate::basic::SAVE_FORMAT_VERSION;
+use crate::fingerprint::DataFingerprint;
+
 /// Meta data
 #[derive(Clone, PartialEq, Eq, Debug, Serialize, Deserialize)]
 pub struct MetaData {
     /// Save directory name
     save_name: String,
+    /// Save format version. Saves created before versioning are treated as version 0.
+    #[serde(default)]
+    format_version: u32,
+    /// Paks and rules used to play this game
+    #[serde(default)]
+    data: Vec<DataFingerprint>,
+    /// Information displayed in the save list
+    #[serde(default)]
+    info: Option<SaveInfo>,
+}
+
+/// Summary of the game at saving
+#[derive(Clone, PartialEq, Eq, Debug, Serialize, Deserialize)]
+pub struct SaveInfo {
+    pub player_level: u32,
+    /// Name of the current map
+    pub location: String,
+    /// Play time in seconds
+    pub play_time: u64,
 }
 
 impl MetaData {
@@ -13,12 +35,39 @@ impl MetaData {
     pub fn set_save_name(&mut self, s: &str) {
         self.save_name = s.to_owned();
     }
+
+    pub fn format_version(&self) -> u32 {
+        self.format_version
+    }
+
+    pub fn set_format_version(&mut self, version: u32) {
+        self.format_version = version;
+    }
+
+    pub fn data_fingerprints(&self) -> &[DataFingerprint] {
+        &self.data
+    }
+
+    pub fn set_data_fingerprints(&mut self, data: Vec<DataFingerprint>) {
+        self.data = data;
+    }
+
+    pub fn info(&self) -> Option<&SaveInfo> {
+        self.info.as_ref()
+    }
+
+    pub fn set_info(&mut self, info: SaveInfo) {
+        self.info = Some(info);
+    }
 }
 
 impl Default for MetaData {
     fn default() -> MetaData {
         MetaData {
             save_name: "uninit".to_owned(),
+            format_version: SAVE_FORMAT_VERSION,
+            data: Vec::new(),
+            info: None,
         }
     }
 }
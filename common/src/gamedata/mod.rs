@@ -48,7 +48,7 @@ pub use self::variables::*;
 
 /// Includes all data for one game world.
 /// This can be a snapshot of the current game, so it must implement Serialize and Deserialize.
-#[derive(Serialize, Deserialize)]
+#[derive(Clone, Serialize, Deserialize)]
 pub struct GameData {
     #[serde(skip)]
     pub meta: MetaData,
@@ -217,12 +217,27 @@ impl GameData {
 
         // Update current_mapid
         self.current_mapid = mid;
+        self.region.mark_map_used(mid);
     }
 
     pub fn set_initial_mapid(&mut self, mid: MapId) {
         let charas = self.region.get_map_mut(mid).charas.take().unwrap();
         self.chara.replace_on_map_chara(charas);
         self.current_mapid = mid;
+        self.region.mark_map_used(mid);
+    }
+
+    /// Unload least recently used maps if the number of loaded maps exceeds the limit
+    pub fn unload_old_maps<P: AsRef<std::path::Path>>(
+        &mut self,
+        map_dir: P,
+    ) -> Result<(), crate::impl_filebox::MapLoadError> {
+        let max = max_loaded_maps();
+        if max == 0 {
+            return Ok(());
+        }
+        self.region
+            .unload_old_maps(map_dir, max, self.current_mapid)
     }
 
     // Fuctions for item handling
@@ -2,9 +2,10 @@ use super::{skill::SkillKind, Element, ElementArray};
 use derivative::Derivative;
 use fnv::FnvHashMap;
 use ordered_float::NotNan;
+use schemars::JsonSchema;
 
 /// Represents modifier for character.
-#[derive(Clone, PartialEq, Eq, PartialOrd, Ord, Debug, Serialize, Deserialize)]
+#[derive(Clone, PartialEq, Eq, PartialOrd, Ord, Debug, Serialize, Deserialize, JsonSchema)]
 pub enum CharaModifier {
     Str(i16),
     Vit(i16),
@@ -15,10 +16,12 @@ pub enum CharaModifier {
     Spd(i16),
     Defence {
         element: Element,
+        #[schemars(with = "f32")]
         value: NotNan<f32>,
     },
     DefenceMultiplier {
         element: Element,
+        #[schemars(with = "f32")]
         value: NotNan<f32>,
     },
 }
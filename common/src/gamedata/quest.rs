@@ -9,7 +9,7 @@ pub enum TownQuestState {
     Reportable,
 }
 
-#[derive(Debug, Default, Serialize, Deserialize)]
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
 pub struct QuestHolder {
     pub town_quests: Vec<(TownQuestState, TownQuest)>,
     pub custom_quests: Vec<CustomQuest>,
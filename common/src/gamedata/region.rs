@@ -1,16 +1,19 @@
 use super::map::*;
 use super::site::*;
 use super::unknown_id_err;
+use crate::impl_filebox::MapLoadError;
+use crate::saveload::swap_map_dir;
 use filebox::FileBox;
 use geom::*;
-use std::collections::HashMap;
+use std::collections::{HashMap, VecDeque};
 use std::path::Path;
+use std::sync::atomic::{AtomicUsize, Ordering};
 
 #[derive(Copy, Clone, PartialEq, Eq, Hash, Debug, Serialize, Deserialize)]
 pub struct RegionId(pub(crate) u32);
 
 /// Region represents "Region Map", and sites on it
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct Region {
     pub name: String,
     id: RegionId,
@@ -19,15 +22,50 @@ pub struct Region {
     pub(crate) map: BoxedMap,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct SiteInfo {
     site: Site,
     /// Position on the region map
     pos: Option<Coords>,
 }
 
-#[derive(Serialize, Deserialize)]
-pub struct RegionHolder(pub(crate) HashMap<RegionId, Region>);
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct RegionHolder(
+    pub(crate) HashMap<RegionId, Region>,
+    #[serde(skip)] MapUsage,
+);
+
+/// Loaded site maps ordered from the least recently used
+#[derive(Clone, Default)]
+pub(crate) struct MapUsage(VecDeque<MapId>);
+
+impl MapUsage {
+    fn mark_used(&mut self, mid: MapId) {
+        self.remove(mid);
+        self.0.push_back(mid);
+    }
+
+    fn remove(&mut self, mid: MapId) {
+        self.0.retain(|m| *m != mid);
+    }
+
+    /// Order for unloading. Maps that have never been used come first.
+    fn order(&self, mid: MapId) -> Option<usize> {
+        self.0.iter().position(|m| *m == mid)
+    }
+}
+
+/// The maximum number of site maps kept in memory. 0 means unlimited.
+static MAX_LOADED_MAPS: AtomicUsize = AtomicUsize::new(0);
+
+pub fn set_max_loaded_maps(n: usize) {
+    MAX_LOADED_MAPS.store(n, Ordering::Relaxed);
+}
+
+pub(crate) fn max_loaded_maps() -> usize {
+    MAX_LOADED_MAPS.load(Ordering::Relaxed)
+}
 
 impl Default for RegionId {
     fn default() -> RegionId {
@@ -37,7 +75,7 @@ impl Default for RegionId {
 
 impl Default for RegionHolder {
     fn default() -> RegionHolder {
-        RegionHolder(HashMap::new())
+        RegionHolder(HashMap::new(), MapUsage::default())
     }
 }
 
@@ -171,7 +209,15 @@ impl RegionHolder {
         table_changed: bool,
     ) {
         info!("preload map {:?}", mid);
+        let map_dir_path = map_dir_path.as_ref();
+        let swap_dir_path = swap_map_dir(map_dir_path);
         let boxed_map = self.get_boxed_map_mut(mid);
+        // Maps unloaded after the last saving are in the swap directory
+        let map_dir_path = if boxed_map.path(&swap_dir_path).exists() {
+            &swap_dir_path
+        } else {
+            map_dir_path
+        };
         match boxed_map.read(map_dir_path, table_changed) {
             Ok(_) => (),
             Err(e) => {
@@ -179,6 +225,47 @@ impl RegionHolder {
                 std::process::exit(1)
             }
         }
+        self.mark_map_used(mid);
+    }
+
+    pub(crate) fn mark_map_used(&mut self, mid: MapId) {
+        if !mid.is_region_map() {
+            self.1.mark_used(mid);
+        }
+    }
+
+    /// Unload least recently used site maps until the number of loaded maps is under the limit.
+    /// Changed maps are written to the swap directory, and they will be included at the next saving.
+    /// Region maps and the given map are always kept.
+    pub(crate) fn unload_old_maps<P: AsRef<Path>>(
+        &mut self,
+        map_dir_path: P,
+        max_loaded: usize,
+        keep: MapId,
+    ) -> Result<(), MapLoadError> {
+        let mut loaded = Vec::new();
+        self.visit_all_maps(|mid, map| {
+            if map.is_loaded() && !mid.is_region_map() && mid != keep {
+                loaded.push(mid);
+            }
+        });
+
+        // The kept map is counted as loaded
+        if loaded.len() < max_loaded {
+            return Ok(());
+        }
+        let n_unload = loaded.len() + 1 - max_loaded;
+        loaded.sort_by_key(|mid| self.1.order(*mid));
+
+        let swap_dir_path = swap_map_dir(map_dir_path.as_ref());
+        std::fs::create_dir_all(&swap_dir_path)?;
+
+        for mid in loaded.into_iter().take(n_unload) {
+            trace!("unload map {:?}", mid);
+            self.get_boxed_map_mut(mid).unload(&swap_dir_path)?;
+            self.1.remove(mid);
+        }
+        Ok(())
     }
 
     pub fn get_map_mut_checked(&mut self, mid: MapId) -> Option<&mut Map> {
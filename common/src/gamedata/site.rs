@@ -7,12 +7,13 @@ use crate::hashmap::HashMap;
 use crate::sitegen::NpcGenId;
 use filebox::FileBox;
 use geom::Coords;
+use schemars::JsonSchema;
 
 pub type BoxedMap = FileBox<Map>;
 
 /// Site represents a dungeon, town, or other facility
 /// It is consist of one or multiple maps
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct Site {
     pub name: Option<String>,
     /// id of SiteGenObject. None for auto generated sites.
@@ -153,7 +154,7 @@ impl SiteContent {
     }
 }
 
-#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug, Serialize, Deserialize)]
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug, Serialize, Deserialize, JsonSchema)]
 pub enum SiteKind {
     AutoGenDungeon,
     Town,
@@ -1,6 +1,7 @@
 #![allow(clippy::manual_non_exhaustive)]
 
 use fnv::FnvHashMap;
+use schemars::JsonSchema;
 use std::str::FromStr;
 use thiserror::Error;
 
@@ -57,7 +58,7 @@ macro_rules! define_skill_kind {
         }
     } => {
         #[repr(u16)]
-        #[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Debug, Serialize, Deserialize)]
+        #[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Debug, Serialize, Deserialize, JsonSchema)]
         pub enum SkillKind {
             #[doc(hidden)]
             _DummyBasicSkill = $basic_skill_start_value,
@@ -86,7 +87,7 @@ macro_rules! define_skill_kind {
             )*
         }
 
-        #[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Debug, Serialize, Deserialize)]
+        #[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Debug, Serialize, Deserialize, JsonSchema)]
         pub enum WeaponKind {
             $(
                 $melee_weapon,
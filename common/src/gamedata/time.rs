@@ -1,5 +1,8 @@
 use once_cell::sync::Lazy;
 use regex::Regex;
+use schemars::gen::SchemaGenerator;
+use schemars::schema::Schema;
+use schemars::JsonSchema;
 use serde::de::{self, Deserialize, Visitor};
 use serde::Serialize;
 use serde_with::{serde_as, DeserializeAs, SerializeAs};
@@ -12,7 +15,7 @@ pub const SECS_PER_DAY: u64 = SECS_PER_HOUR * 24;
 pub const SECS_PER_MONTH: u64 = SECS_PER_DAY * DAYS_PER_MONTH;
 pub const SECS_PER_YEAR: u64 = SECS_PER_MONTH * 12;
 
-#[derive(Default, Debug, Serialize, Deserialize)]
+#[derive(Clone, Default, Debug, Serialize, Deserialize)]
 pub struct GameTime {
     start: Time,
     current: Time,
@@ -259,3 +262,19 @@ impl<'de> Deserialize<'de> for Duration {
         deserializer.deserialize_any(DurationVisitor)
     }
 }
+
+impl JsonSchema for Duration {
+    fn schema_name() -> String {
+        "Duration".into()
+    }
+
+    fn json_schema(_gen: &mut SchemaGenerator) -> Schema {
+        crate::schema::from_json(serde_json::json!({
+            "description": "Seconds, or digits with a suffix [dmhs]",
+            "anyOf": [
+                { "type": "integer", "minimum": 0 },
+                { "type": "string", "pattern": "^[0-9]+[dhms]$" },
+            ],
+        }))
+    }
+}
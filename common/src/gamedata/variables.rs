@@ -28,7 +28,7 @@ impl From<u32> for Value {
 }
 
 /// Stores variables which are referenced in scripts
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct Variables {
     global: HashMap<String, Value>,
     local: HashMap<(String, String), Value>,
@@ -1,42 +1,95 @@
 //! This module provides global state objholder
 
 use crate::objholder::*;
+use crate::reloadable::Reloadable;
 use once_cell::sync::Lazy;
+use std::ops::Deref;
 use std::path::PathBuf;
-use std::sync::Mutex;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Mutex, RwLock};
 
 /// Initialize lazy static
 pub fn init(pak_dirs: Vec<PathBuf>) {
     *PAK_DIRS.lock().unwrap() = Some(pak_dirs);
-    Lazy::force(&OBJ_HOLDER);
+    Reloadable::force(&OBJ_HOLDER);
+}
+
+/// Load objects again from the pak directories given to `init()`.
+/// Indices got before reloading are invalid for the new objects.
+pub fn reload() {
+    let objholder = ObjectHolder::load(PAK_DIRS.lock().unwrap().as_ref().unwrap());
+    OBJ_HOLDER.replace(objholder);
+    OBJ_HOLDER_HASH.replace(calc_hash());
+    N_RELOADED.fetch_add(1, Ordering::Relaxed);
+}
+
+/// The number of times objects are reloaded
+static N_RELOADED: AtomicU64 = AtomicU64::new(0);
+
+/// Value computed from objects lazily like `Lazy`, and computed again after `reload()`.
+/// Previous values are leaked, because `&'static` references to them may remain.
+pub struct ObjCache<T: 'static> {
+    init: fn() -> T,
+    value: RwLock<Option<(u64, &'static T)>>,
+}
+
+impl<T: Sync + 'static> ObjCache<T> {
+    pub const fn new(init: fn() -> T) -> Self {
+        ObjCache {
+            init,
+            value: RwLock::new(None),
+        }
+    }
+
+    pub fn get(&self) -> &'static T {
+        let n_reloaded = N_RELOADED.load(Ordering::Relaxed);
+        if let Some((n, value)) = *self.value.read().expect("object cache lock error") {
+            if n == n_reloaded {
+                return value;
+            }
+        }
+        let value: &'static T = Box::leak(Box::new((self.init)()));
+        *self.value.write().expect("object cache lock error") = Some((n_reloaded, value));
+        value
+    }
+}
+
+impl<T: Sync + 'static> Deref for ObjCache<T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        self.get()
+    }
 }
 
 static PAK_DIRS: Lazy<Mutex<Option<Vec<PathBuf>>>> = Lazy::new(|| Mutex::new(None));
-static OBJ_HOLDER: Lazy<ObjectHolder> = Lazy::new(|| {
+static OBJ_HOLDER: Reloadable<ObjectHolder> = Reloadable::new(|| {
     let pak_dirs = PAK_DIRS.lock().unwrap();
     ObjectHolder::load(pak_dirs.as_ref().unwrap())
 });
-pub static OBJ_HOLDER_HASH: Lazy<u64> = Lazy::new(|| {
+pub static OBJ_HOLDER_HASH: Reloadable<u64> = Reloadable::new(calc_hash);
+
+fn calc_hash() -> u64 {
     use std::hash::{Hash, Hasher};
     let mut hasher = fnv::FnvHasher::default();
     get_objholder().hash(&mut hasher);
     hasher.finish()
-});
+}
 
 pub fn get_objholder() -> &'static ObjectHolder {
-    &OBJ_HOLDER
+    OBJ_HOLDER.get()
 }
 
 pub fn get_obj<T: ObjectIndex>(idx: T) -> &'static T::ObjectType {
-    idx.get_obj_from_objholder(&OBJ_HOLDER)
+    idx.get_obj_from_objholder(get_objholder())
 }
 
 pub fn id_to_idx<T: ObjectIndex + Default>(id: &str) -> T {
-    T::search_idx(id, &OBJ_HOLDER).unwrap_or_default()
+    T::search_idx(id, get_objholder()).unwrap_or_default()
 }
 
 pub fn id_to_idx_checked<T: ObjectIndex>(id: &str) -> Option<T> {
-    if let Some(idx) = T::search_idx(id, &OBJ_HOLDER) {
+    if let Some(idx) = T::search_idx(id, get_objholder()) {
         Some(idx)
     } else {
         warn!("unknown id \"{}\" for {}", id, std::any::type_name::<T>());
@@ -45,11 +98,11 @@ pub fn id_to_idx_checked<T: ObjectIndex>(id: &str) -> Option<T> {
 }
 
 pub fn idx_to_id<T: ObjectIndex>(idx: T) -> &'static str {
-    idx.to_id(&OBJ_HOLDER)
+    idx.to_id(get_objholder())
 }
 
 pub fn get_by_id<T: FromId>(id: &str) -> &'static T {
-    if let Some(s) = T::get_obj_from_objholder_by_id(id, &OBJ_HOLDER) {
+    if let Some(s) = T::get_obj_from_objholder_by_id(id, get_objholder()) {
         s
     } else {
         eprintln!("Object \"{id}\" is not found");
@@ -58,7 +111,7 @@ pub fn get_by_id<T: FromId>(id: &str) -> &'static T {
 }
 
 pub fn get_by_id_checked<T: FromId>(id: &str) -> Option<&'static T> {
-    if let Some(obj) = T::get_obj_from_objholder_by_id(id, &OBJ_HOLDER) {
+    if let Some(obj) = T::get_obj_from_objholder_by_id(id, get_objholder()) {
         Some(obj)
     } else {
         warn!("unknown id \"{}\" for {}", id, std::any::type_name::<T>());
@@ -70,6 +123,34 @@ pub fn get_by_id_checked<T: FromId>(id: &str) -> Option<&'static T> {
 mod serde_with_impl {
     use super::*;
     use serde_with::{DeserializeAs, SerializeAs};
+    use std::cell::RefCell;
+
+    thread_local! {
+        /// Unknown ids found by `ObjIdxAsId` in `collect_unknown_ids()`
+        static UNKNOWN_IDS: RefCell<Option<Vec<(&'static str, String)>>> = const { RefCell::new(None) };
+    }
+
+    /// Run `f`, and returns ids which are not found when deserialized as `ObjIdxAsId` in it.
+    /// Types are object type names, e.g. "Item" for `ItemIdx`.
+    pub fn collect_unknown_ids<T>(f: impl FnOnce() -> T) -> (T, Vec<(&'static str, String)>) {
+        let outer = UNKNOWN_IDS.with(|ids| ids.replace(Some(Vec::new())));
+        let result = f();
+        let ids = UNKNOWN_IDS.with(|ids| ids.replace(outer));
+        (result, ids.unwrap_or_default())
+    }
+
+    fn record_unknown_id<T>(id: &str) {
+        let ty = std::any::type_name::<T>()
+            .rsplit("::")
+            .next()
+            .unwrap_or_default();
+        let ty = ty.strip_suffix("Idx").unwrap_or(ty);
+        UNKNOWN_IDS.with(|ids| {
+            if let Some(ids) = ids.borrow_mut().as_mut() {
+                ids.push((ty, id.to_owned()));
+            }
+        });
+    }
 
     /// serialize/deserialize object index as id string.
     /// gobj::init() must be called before using this.
@@ -99,9 +180,14 @@ mod serde_with_impl {
         {
             use serde::Deserialize;
             let id = String::deserialize(deserializer)?;
-            Ok(id_to_idx(&id))
+            if let Some(idx) = T::search_idx(&id, get_objholder()) {
+                Ok(idx)
+            } else {
+                record_unknown_id::<T>(&id);
+                Ok(T::default())
+            }
         }
     }
 }
 
-pub use serde_with_impl::ObjIdxAsId;
+pub use serde_with_impl::{collect_unknown_ids, ObjIdxAsId};
@@ -1,5 +1,9 @@
 pub use crate::objholder::IdxConvTable;
 use once_cell::sync::Lazy;
+use schemars::JsonSchema;
+use std::cell::Cell;
+use std::collections::HashMap;
+use std::sync::atomic::AtomicUsize;
 use std::sync::RwLock;
 
 pub(crate) static IDX_CONV_TABLE: Lazy<RwLock<Option<IdxConvTable>>> =
@@ -9,6 +13,127 @@ pub fn set_idx_conv_table(idx_conv_table: Option<IdxConvTable>) {
     *IDX_CONV_TABLE.write().expect("IDX_CONV_TABLE lock error") = idx_conv_table;
 }
 
+/// Action for an object which is not found in the current objects when loading a save
+#[derive(Clone, PartialEq, Eq, Debug, Serialize, Deserialize, JsonSchema)]
+pub enum RemovedObjAction {
+    /// Replace by the object of the given id
+    Replace(String),
+    /// Remove the item from its item list. Other objects are replaced by the default object.
+    Remove,
+}
+
+/// Actions for removed objects. Keys are section names in the id table, e.g. "ItemObject".
+#[derive(Clone, Default, Debug, Serialize, Deserialize, JsonSchema)]
+pub struct RemovedObjPolicy {
+    /// Actions for each id
+    #[serde(default)]
+    pub actions: HashMap<String, HashMap<String, RemovedObjAction>>,
+    /// Actions for ids which are not in `actions`
+    #[serde(default)]
+    pub default_actions: HashMap<String, RemovedObjAction>,
+}
+
+impl RemovedObjPolicy {
+    pub fn action(&self, section: &str, id: &str) -> Option<&RemovedObjAction> {
+        self.actions
+            .get(section)
+            .and_then(|actions| actions.get(id))
+            .or_else(|| self.default_actions.get(section))
+    }
+
+    pub fn append(&mut self, other: RemovedObjPolicy) {
+        for (section, actions) in other.actions {
+            self.actions.entry(section).or_default().extend(actions);
+        }
+        self.default_actions.extend(other.default_actions);
+    }
+}
+
+pub(crate) static REMOVED_OBJ_POLICY: Lazy<RwLock<RemovedObjPolicy>> =
+    Lazy::new(|| RwLock::new(RemovedObjPolicy::default()));
+
+pub fn set_removed_obj_policy(policy: RemovedObjPolicy) {
+    *REMOVED_OBJ_POLICY
+        .write()
+        .expect("REMOVED_OBJ_POLICY lock error") = policy;
+}
+
+/// An object in the loaded save which is not found in the current objects
+#[derive(Debug)]
+pub struct MissingObj {
+    pub section: &'static str,
+    pub id: String,
+    pub action: Option<RemovedObjAction>,
+    /// The id given by `RemovedObjAction::Replace` which is not found also
+    pub unknown_replacement: Option<String>,
+    /// The number of references converted while loading
+    pub count: AtomicUsize,
+}
+
+/// Index in conversion tables for removed items
+pub(crate) const REMOVED_IDX: u32 = u32::MAX;
+
+thread_local! {
+    /// Whether a removed object is found in the item being deserialized.
+    /// None outside of item lists, so removed objects elsewhere are not recorded.
+    static REMOVED_OBJ_FOUND: Cell<Option<bool>> = const { Cell::new(None) };
+}
+
+/// Run `f` to deserialize one item in an item list,
+/// and returns whether a removed object is found in it.
+pub(crate) fn check_removed_obj<T>(f: impl FnOnce() -> T) -> (T, bool) {
+    let outer = REMOVED_OBJ_FOUND.with(|flag| flag.replace(Some(false)));
+    let result = f();
+    let found = REMOVED_OBJ_FOUND.with(|flag| flag.replace(outer));
+    (result, found == Some(true))
+}
+
+pub(crate) fn set_removed_obj_found() {
+    REMOVED_OBJ_FOUND.with(|flag| {
+        if flag.get().is_some() {
+            flag.set(Some(true));
+        }
+    });
+}
+
+/// Create a report of missing objects in the current conversion table
+pub fn missing_obj_report() -> Option<String> {
+    use std::fmt::Write;
+    use std::sync::atomic::Ordering;
+
+    let lock = IDX_CONV_TABLE.read().expect("IDX_CONV_TABLE lock error");
+    let missing = lock.as_ref()?.missing_objs();
+    if missing.is_empty() {
+        return None;
+    }
+
+    let mut report = format!(
+        "{} objects are not found in the current data",
+        missing.len()
+    );
+    for m in missing {
+        let action = match (&m.action, &m.unknown_replacement) {
+            (_, Some(id)) => format!(
+                "replaced by the default because replacement \"{}\" is not found",
+                id
+            ),
+            (Some(RemovedObjAction::Replace(id)), None) => format!("replaced by \"{}\"", id),
+            (Some(RemovedObjAction::Remove), None) => "removed".to_owned(),
+            (None, None) => "replaced by the default".to_owned(),
+        };
+        write!(
+            report,
+            "\n  {} \"{}\": {} references, {}",
+            m.section,
+            m.id,
+            m.count.load(Ordering::Relaxed),
+            action
+        )
+        .unwrap();
+    }
+    Some(report)
+}
+
 #[macro_export]
 macro_rules! idx_conv {
     ($({$a:ident, $obj:ty, $mem:ident, $idx:ident}),*) => {
@@ -18,6 +143,9 @@ macro_rules! idx_conv {
             $(
                 $mem: Vec<u32>,
             )*
+            missing: Vec<$crate::idx_conv::MissingObj>,
+            /// Section name and index in the save to the index of missing
+            missing_map: std::collections::HashMap<(&'static str, u32), usize>,
         }
 
         impl IdxConvTable {
@@ -37,6 +165,7 @@ macro_rules! idx_conv {
 
                 let mut table = IdxConvTable::default();
                 let mut current_obj_type = String::new();
+                let policy = $crate::idx_conv::REMOVED_OBJ_POLICY.read().expect("REMOVED_OBJ_POLICY lock error");
 
                 for line in r.lines() {
                     let line = line?;
@@ -50,7 +179,33 @@ macro_rules! idx_conv {
                                     if let Some(dest_idx) = $crate::gobj::id_to_idx_checked::<$idx>(&line) {
                                         table.$mem.push(dest_idx.as_usize() as u32);
                                     } else {
-                                        table.$mem.push($idx::default().as_usize() as u32);
+                                        use $crate::idx_conv::RemovedObjAction;
+                                        let action = policy.action(stringify!($obj), &line).cloned();
+                                        let mut unknown_replacement = None;
+                                        let dest_idx = match &action {
+                                            Some(RemovedObjAction::Replace(id)) => {
+                                                if let Some(idx) = $crate::gobj::id_to_idx_checked::<$idx>(id) {
+                                                    idx.as_usize() as u32
+                                                } else {
+                                                    unknown_replacement = Some(id.clone());
+                                                    $idx::default().as_usize() as u32
+                                                }
+                                            }
+                                            Some(RemovedObjAction::Remove) if stringify!($obj) == "ItemObject" => {
+                                                $crate::idx_conv::REMOVED_IDX
+                                            }
+                                            _ => $idx::default().as_usize() as u32,
+                                        };
+                                        let src_idx = table.$mem.len() as u32;
+                                        table.$mem.push(dest_idx);
+                                        table.missing_map.insert((stringify!($obj), src_idx), table.missing.len());
+                                        table.missing.push($crate::idx_conv::MissingObj {
+                                            section: stringify!($obj),
+                                            id: line,
+                                            action,
+                                            unknown_replacement,
+                                            count: Default::default(),
+                                        });
                                     }
                                 }
                             )*
@@ -64,9 +219,22 @@ macro_rules! idx_conv {
                 Ok(Some(table))
             }
 
+            /// Objects in the save which are not found in the current objects
+            pub fn missing_objs(&self) -> &[$crate::idx_conv::MissingObj] {
+                &self.missing
+            }
+
             $(
                 pub fn $mem(&self, i: $idx) -> $idx {
+                    if let Some(&missing) = self.missing_map.get(&(stringify!($obj), i.as_usize() as u32)) {
+                        self.missing[missing].count.fetch_add(1, std::sync::atomic::Ordering::Relaxed);
+                    }
                     if let Some(i) = self.$mem.get(i.as_usize()).copied() {
+                        if i == $crate::idx_conv::REMOVED_IDX {
+                            // Item lists remove items by this flag
+                            $crate::idx_conv::set_removed_obj_found();
+                            return $idx::default();
+                        }
                         $idx::from_usize(i as usize)
                     } else {
                         warn!("invalid value in index conversion: {}({})", stringify!($idx), i.as_usize());
@@ -1,4 +1,6 @@
+use crate::basic::SAVE_FORMAT_VERSION;
 use crate::gamedata::Map;
+use crate::saveload::migration;
 use crate::utils::to_writer_with_mode;
 use filebox::*;
 use serde_cbor::{error::Error as SerdeError, from_reader};
@@ -14,7 +16,12 @@ impl WithId for Map {
     }
 
     fn read<R: Read>(r: R) -> Result<Self, MapLoadError> {
-        Ok(from_reader(r)?)
+        let version = migration::map_format_version();
+        if version == SAVE_FORMAT_VERSION {
+            Ok(from_reader(r)?)
+        } else {
+            migration::read_map(r, version).map_err(MapLoadError::Migration)
+        }
     }
 }
 
@@ -24,4 +31,8 @@ pub enum MapLoadError {
     Io(#[from] IoError),
     #[error("serde error")]
     Serde(#[from] SerdeError),
+    #[error("migration error: {0}")]
+    Migration(anyhow::Error),
+    #[error("broken map file: {0}")]
+    Broken(#[from] FileBoxError),
 }
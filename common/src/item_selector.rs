@@ -1,5 +1,8 @@
 use crate::gamedata::{EquipSlotKind, ItemKind, ItemObject, KindParseError, WeaponKind};
 use crate::objholder::ItemIdx;
+use schemars::gen::SchemaGenerator;
+use schemars::schema::Schema;
+use schemars::JsonSchema;
 use serde::de::{self, Deserialize, Visitor};
 use serde::Serialize;
 use std::str::FromStr;
@@ -215,6 +218,19 @@ impl<'de> Deserialize<'de> for ItemSelector {
     }
 }
 
+impl JsonSchema for ItemSelector {
+    fn schema_name() -> String {
+        "ItemSelector".into()
+    }
+
+    fn json_schema(_gen: &mut SchemaGenerator) -> Schema {
+        crate::schema::from_json(serde_json::json!({
+            "description": "\"*\", or comma separated item ids, \"kind/<kind>\" and \"group/<group>\"",
+            "type": "string",
+        }))
+    }
+}
+
 #[test]
 fn item_selector_test() {
     let s = "kind/food,group/food,hoge";
@@ -15,9 +15,10 @@ extern crate serde_derive;
 extern crate log;
 extern crate tile_geom as geom;
 
-mod utils;
+pub mod utils;
 
 pub mod basic;
+pub mod fingerprint;
 pub mod hashmap;
 pub mod obj;
 #[macro_use]
@@ -32,5 +33,7 @@ pub mod objholder;
 pub mod pakutil;
 pub mod piece_pattern;
 pub mod regiongen;
+pub mod reloadable;
 pub mod saveload;
+pub mod schema;
 pub mod sitegen;
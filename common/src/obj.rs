@@ -1,6 +1,7 @@
 use crate::gamedata;
 use crate::gamedata::CharaBaseAttr;
 use crate::hashmap::HashMap;
+use schemars::JsonSchema;
 use std::fmt;
 
 #[derive(PartialEq, Eq, Clone, Copy)]
@@ -27,6 +28,7 @@ pub enum Object {
     RegionGen(RegionGenObject),
     Script(ScriptObject),
     SiteGen(SiteGenObject),
+    Atlas(AtlasObject),
 }
 
 #[derive(Serialize, Deserialize)]
@@ -52,7 +54,9 @@ pub struct EffectImgObject {
 pub struct ScriptObject {
     pub id: String,
     pub script: String,
-    // pub byte_code: Option<Vec<u8>>,
+    /// Bytecode compiled by makepak. The script source is compiled at runtime if none.
+    #[serde(default)]
+    pub byte_code: Option<Vec<u8>>,
 }
 
 #[derive(Serialize, Deserialize)]
@@ -75,7 +79,7 @@ pub struct TileObject {
     pub materials: Vec<(String, u32)>,
 }
 
-#[derive(Clone, Copy, PartialEq, Eq, Debug, Serialize, Deserialize)]
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Serialize, Deserialize, JsonSchema)]
 pub enum TileKind {
     Ground,
     Water,
@@ -109,6 +113,7 @@ pub use crate::gamedata::item::ItemObject;
 
 #[derive(Serialize, Deserialize)]
 pub struct Img {
+    /// Png data. Empty if the image is packed into an atlas.
     pub data: Vec<u8>,
     pub w: u32,
     pub h: u32,
@@ -120,10 +125,23 @@ pub struct Img {
     pub n_anim_frame: u32,
     pub duration: u32,
     pub variation_rule: ImgVariationRule,
+    #[serde(default)]
+    pub atlas: Option<AtlasPos>,
+}
+
+/// Position of an image in an atlas
+#[derive(Clone, PartialEq, Eq, Debug, Serialize, Deserialize)]
+pub struct AtlasPos {
+    /// Id of the atlas object
+    pub id: String,
+    pub x: u32,
+    pub y: u32,
 }
 
 /// Image variation rule.
-#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Debug, Serialize, Deserialize)]
+#[derive(
+    Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Debug, Serialize, Deserialize, JsonSchema,
+)]
 pub enum ImgVariationRule {
     /// No variation.
     None,
@@ -147,6 +165,14 @@ pub struct Icon {
 
 // No image objects
 
+/// Image that images of other objects are packed into by makepak.
+#[derive(Serialize, Deserialize)]
+pub struct AtlasObject {
+    pub id: String,
+    /// Png data
+    pub data: Vec<u8>,
+}
+
 pub use crate::maptemplate::MapTemplateObject;
 pub use crate::regiongen::RegionGenObject;
 pub use crate::sitegen::SiteGenObject;
@@ -176,7 +202,8 @@ impl_object!(
     MapTemplateObject,
     RegionGenObject,
     SiteGenObject,
-    ScriptObject
+    ScriptObject,
+    AtlasObject
 );
 
 impl Object {
@@ -195,17 +222,97 @@ impl Object {
             Object::RegionGen(ref o) => &o.id,
             Object::Script(ref o) => &o.id,
             Object::SiteGen(ref o) => &o.id,
+            Object::Atlas(ref o) => &o.id,
+        }
+    }
+
+    /// Name of the variant
+    pub fn type_name(&self) -> &'static str {
+        match *self {
+            Object::AnimImg(_) => "AnimImg",
+            Object::CharaTemplate(_) => "CharaTemplate",
+            Object::Deco(_) => "Deco",
+            Object::EffectImg(_) => "EffectImg",
+            Object::Item(_) => "Item",
+            Object::SpecialTile(_) => "SpecialTile",
+            Object::Tile(_) => "Tile",
+            Object::UiImg(_) => "UiImg",
+            Object::Wall(_) => "Wall",
+            Object::MapTemplate(_) => "MapTemplate",
+            Object::RegionGen(_) => "RegionGen",
+            Object::Script(_) => "Script",
+            Object::SiteGen(_) => "SiteGen",
+            Object::Atlas(_) => "Atlas",
+        }
+    }
+
+    /// Returns image if the object implements ImgObject
+    pub fn get_img(&self) -> Option<&Img> {
+        match *self {
+            Object::AnimImg(ref o) => Some(o.get_img()),
+            Object::CharaTemplate(ref o) => Some(o.get_img()),
+            Object::Deco(ref o) => Some(o.get_img()),
+            Object::EffectImg(ref o) => Some(o.get_img()),
+            Object::Item(ref o) => Some(o.get_img()),
+            Object::SpecialTile(ref o) => Some(o.get_img()),
+            Object::Tile(ref o) => Some(o.get_img()),
+            Object::UiImg(ref o) => Some(o.get_img()),
+            Object::Wall(ref o) => Some(o.get_img()),
+            Object::MapTemplate(_)
+            | Object::RegionGen(_)
+            | Object::Script(_)
+            | Object::SiteGen(_)
+            | Object::Atlas(_) => None,
+        }
+    }
+
+    pub fn get_img_mut(&mut self) -> Option<&mut Img> {
+        match *self {
+            Object::AnimImg(ref mut o) => Some(&mut o.img),
+            Object::CharaTemplate(ref mut o) => Some(&mut o.img),
+            Object::Deco(ref mut o) => Some(&mut o.img),
+            Object::EffectImg(ref mut o) => Some(&mut o.img),
+            Object::Item(ref mut o) => Some(&mut o.img),
+            Object::SpecialTile(ref mut o) => Some(&mut o.img),
+            Object::Tile(ref mut o) => Some(&mut o.img),
+            Object::UiImg(ref mut o) => Some(&mut o.img),
+            Object::Wall(ref mut o) => Some(&mut o.img),
+            Object::MapTemplate(_)
+            | Object::RegionGen(_)
+            | Object::Script(_)
+            | Object::SiteGen(_)
+            | Object::Atlas(_) => None,
         }
     }
 }
 
-/// Objects that have image
+impl Img {
+    /// Top left position of this image in the texture
+    pub fn offset(&self) -> (i32, i32) {
+        if let Some(atlas) = &self.atlas {
+            (atlas.x as i32, atlas.y as i32)
+        } else {
+            (0, 0)
+        }
+    }
+}
+
+/// Objects that have image.
+/// Rects are positions in the texture, which may be an atlas.
 pub trait ImgObject {
     fn get_img(&self) -> &Img;
     /// Returns rect for the first image
     fn img_rect(&self) -> (i32, i32, u32, u32) {
         let img = self.get_img();
-        (0, 0, img.w, img.h)
+        let (x, y) = img.offset();
+        (x, y, img.w, img.h)
+    }
+
+    /// Returns rect for the whole image including all grids
+    fn img_rect_all(&self) -> (i32, i32, u32, u32) {
+        let img = self.get_img();
+        let (x, y) = img.offset();
+        (x, y, img.w * img.grid_nx, img.h * img.grid_ny)
     }
 
     /// Returns rect for nth image of grid
@@ -214,9 +321,10 @@ pub trait ImgObject {
         let n = if n < img.grid_nx * img.grid_ny { n } else { 0 };
         let grid_x = n % img.grid_nx;
         let grid_y = n / img.grid_nx;
+        let (x, y) = img.offset();
         (
-            (img.w * grid_x) as i32,
-            (img.h * grid_y) as i32,
+            x + (img.w * grid_x) as i32,
+            y + (img.h * grid_y) as i32,
             img.w,
             img.h,
         )
@@ -1,12 +1,14 @@
 use crate::obj::*;
-use crate::pakutil::load_objs_dir;
+use crate::pakutil::load_paks;
+use fnv::FnvHashMap;
+use std::collections::{BTreeMap, HashMap};
 use std::num::NonZeroU32;
 use std::path::Path;
 
 const NON_ZERO_U32_1: NonZeroU32 = unsafe { NonZeroU32::new_unchecked(1) };
 
 macro_rules! impl_idx {
-    ($idx:ident, $obj:ty, $mem:ident) => {
+    ($idx:ident, $obj:ty, $mem:ident, $index:ident) => {
         impl ObjectIndex for $idx {
             type ObjectType = $obj;
             fn get_obj_from_objholder<'a>(&self, objholder: &'a ObjectHolder) -> &'a $obj {
@@ -18,12 +20,7 @@ macro_rules! impl_idx {
             }
 
             fn search_idx(id: &str, objholder: &ObjectHolder) -> Option<$idx> {
-                for (i, ref o) in (&objholder.$mem).into_iter().enumerate() {
-                    if o.id == id {
-                        return Some($idx::from_usize(i));
-                    }
-                }
-                None
+                objholder.$index.get(id).copied()
             }
 
             fn as_raw_int(&self) -> u32 {
@@ -40,12 +37,8 @@ macro_rules! impl_idx {
                 id: &str,
                 objholder: &'a ObjectHolder,
             ) -> Option<&'a $obj> {
-                for ref o in (&objholder.$mem).iter() {
-                    if o.id == id {
-                        return Some(o);
-                    }
-                }
-                None
+                let idx = objholder.$index.get(id)?;
+                Some(&objholder.$mem[idx.as_usize()])
             }
         }
 
@@ -85,40 +78,122 @@ macro_rules! impl_idx {
 }
 
 macro_rules! impl_objholder {
-    ($({$a:ident, $obj:ty, $mem:ident, $idx:ident}),*) => {
+    ($({$a:ident, $obj:ty, $mem:ident, $idx:ident, $index:ident}),*) => {
         pub struct ObjectHolder {
-            $(pub $mem: Vec<$obj>),*
+            $(pub $mem: Vec<$obj>,)*
+            /// Atlases are not included in id tables because they are referred only by images
+            pub atlas: FnvHashMap<String, AtlasObject>,
+            // Id to index tables, built after sorting
+            $($index: FnvHashMap<String, $idx>,)*
         }
 
         impl Default for ObjectHolder {
             fn default() -> ObjectHolder {
                 ObjectHolder {
-                    $($mem: Vec::new()),*
+                    $($mem: Vec::new(),)*
+                    atlas: FnvHashMap::default(),
+                    $($index: FnvHashMap::default(),)*
                 }
             }
         }
 
         impl ObjectHolder {
 
+            /// Load paks in the given directories.
+            /// An object whose id is already loaded is ignored unless the pak overrides it.
             pub fn load<P: AsRef<Path>>(dirs: &[P]) -> ObjectHolder {
                 let mut objholder = ObjectHolder::default();
+                let (paks, err_stack) = load_paks(dirs);
 
-                for dir in dirs {
-                    let err_stack = load_objs_dir(dir.as_ref(), |object| {
-                        match object {
-                            $(Object::$a(o) => { objholder.$mem.push(o); }),*
+                if !err_stack.is_empty() {
+                    warn!("object loading error\n{:?}", err_stack);
+                }
+
+                // Object type and id to the pak name and the position in the vec
+                let mut loaded: HashMap<(&'static str, String), (String, usize)> = HashMap::new();
+                // Object type and id to the pak names which define it
+                let mut duplicates: BTreeMap<(&'static str, String), Vec<String>> = BTreeMap::new();
+
+                for pak in paks {
+                    info!(
+                        "loading pak \"{}\" {} ({})",
+                        pak.manifest.name,
+                        pak.manifest.version,
+                        pak.path.to_string_lossy(),
+                    );
+
+                    for object in pak.objs {
+                        let key = (object.type_name(), object.get_id().to_owned());
+
+                        if let Some((loaded_from, i)) = loaded.get(&key) {
+                            if pak.manifest.overrides.contains(&key.1) {
+                                info!(
+                                    "{} \"{}\" in \"{}\" overrides the one in \"{}\"",
+                                    key.0, key.1, pak.manifest.name, loaded_from,
+                                );
+                                let i = *i;
+                                match object {
+                                    $(Object::$a(o) => { objholder.$mem[i] = o; }),*
+                                    Object::Atlas(o) => { objholder.atlas.insert(o.id.clone(), o); }
+                                }
+                                loaded.insert(key, (pak.manifest.name.clone(), i));
+                            } else {
+                                duplicates
+                                    .entry(key)
+                                    .or_insert_with(|| vec![loaded_from.clone()])
+                                    .push(pak.manifest.name.clone());
+                            }
+                            continue;
                         }
-                    });
 
-                    if !err_stack.is_empty() {
-                        warn!("object loading error in {}\n{:?}", dir.as_ref().to_string_lossy(), err_stack);
+                        let i = match object {
+                            $(Object::$a(o) => { objholder.$mem.push(o); objholder.$mem.len() - 1 }),*
+                            Object::Atlas(o) => { objholder.atlas.insert(o.id.clone(), o); 0 }
+                        };
+                        loaded.insert(key, (pak.manifest.name.clone(), i));
                     }
                 }
 
+                if !duplicates.is_empty() {
+                    let list: Vec<String> = duplicates
+                        .iter()
+                        .map(|((ty, id), paks)| format!("{} \"{}\" in {}", ty, id, paks.join(", ")))
+                        .collect();
+                    error!(
+                        "ids defined twice without override (the first ones are used)\n{}",
+                        list.join("\n")
+                    );
+                }
+
                 objholder.sort();
+                objholder.build_index();
                 objholder
             }
 
+            /// Create from the given objects without paks. Duplicated ids are not checked.
+            pub fn from_objs(objs: Vec<Object>) -> ObjectHolder {
+                let mut objholder = ObjectHolder::default();
+                for object in objs {
+                    match object {
+                        $(Object::$a(o) => { objholder.$mem.push(o); }),*
+                        Object::Atlas(o) => { objholder.atlas.insert(o.id.clone(), o); }
+                    }
+                }
+                objholder.sort();
+                objholder.build_index();
+                objholder
+            }
+
+            fn build_index(&mut self) {
+                $(
+                    self.$index = self.$mem
+                        .iter()
+                        .enumerate()
+                        .map(|(i, o)| (o.id.clone(), $idx::from_usize(i)))
+                        .collect();
+                )*
+            }
+
             fn sort(&mut self) {
                 {
                     $(self.$mem.sort_by(|a, b| a.id.cmp(&b.id)));*
@@ -174,25 +249,25 @@ macro_rules! impl_objholder {
             #[serde(transparent)]
             pub struct $idx(NonZeroU32);
 
-            impl_idx!($idx, $obj, $mem);
+            impl_idx!($idx, $obj, $mem, $index);
         )*
     }
 }
 
 impl_objholder! {
-    {AnimImg, AnimImgObject, anim_img, AnimImgIdx},
-    {CharaTemplate, CharaTemplateObject, chara_template, CharaTemplateIdx},
-    {Deco, DecoObject, deco, DecoIdx},
-    {EffectImg, EffectImgObject, effect_img, EffectImgIdx},
-    {Item, ItemObject, item, ItemIdx},
-    {SpecialTile, SpecialTileObject, special_tile, SpecialTileIdx},
-    {Tile, TileObject, tile, TileIdx},
-    {UiImg, UiImgObject, ui_img, UiImgIdx},
-    {Wall, WallObject, wall, WallIdx},
-    {MapTemplate, MapTemplateObject, map_template, MapTemplateIdx},
-    {RegionGen, RegionGenObject, region_gen, RegionGenIdx},
-    {Script, ScriptObject, script, ScriptIdx},
-    {SiteGen, SiteGenObject, site_gen, SiteGenIdx}
+    {AnimImg, AnimImgObject, anim_img, AnimImgIdx, anim_img_index},
+    {CharaTemplate, CharaTemplateObject, chara_template, CharaTemplateIdx, chara_template_index},
+    {Deco, DecoObject, deco, DecoIdx, deco_index},
+    {EffectImg, EffectImgObject, effect_img, EffectImgIdx, effect_img_index},
+    {Item, ItemObject, item, ItemIdx, item_index},
+    {SpecialTile, SpecialTileObject, special_tile, SpecialTileIdx, special_tile_index},
+    {Tile, TileObject, tile, TileIdx, tile_index},
+    {UiImg, UiImgObject, ui_img, UiImgIdx, ui_img_index},
+    {Wall, WallObject, wall, WallIdx, wall_index},
+    {MapTemplate, MapTemplateObject, map_template, MapTemplateIdx, map_template_index},
+    {RegionGen, RegionGenObject, region_gen, RegionGenIdx, region_gen_index},
+    {Script, ScriptObject, script, ScriptIdx, script_index},
+    {SiteGen, SiteGenObject, site_gen, SiteGenIdx, site_gen_index}
 }
 
 pub trait ObjectIndex: Sized {
@@ -235,3 +310,81 @@ fn cmp_chara_template(a: &CharaTemplateObject, b: &CharaTemplateObject) -> std::
     }
     a.id.cmp(&b.id)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pakutil::{write_object, write_pak_manifest, PakManifest, PAK_MANIFEST_ENTRY};
+    use std::fs::File;
+
+    fn append(builder: &mut tar::Builder<File>, path: &str, data: &[u8]) {
+        let mut header = tar::Header::new_gnu();
+        header.set_path(path).unwrap();
+        header.set_size(data.len() as u64);
+        header.set_cksum();
+        builder.append(&header, data).unwrap();
+    }
+
+    /// Write a pak which has scripts of the given ids and contents
+    fn write_pak(path: &Path, manifest: &PakManifest, scripts: &[(&str, &str)]) {
+        let mut builder = tar::Builder::new(File::create(path).unwrap());
+        let mut data = Vec::new();
+        write_pak_manifest(&mut data, manifest).unwrap();
+        append(&mut builder, PAK_MANIFEST_ENTRY, &data);
+        for (id, script) in scripts {
+            let obj = Object::Script(ScriptObject {
+                id: id.to_string(),
+                script: script.to_string(),
+                byte_code: None,
+            });
+            let mut data = Vec::new();
+            write_object(&mut data, &obj).unwrap();
+            append(&mut builder, id, &data);
+        }
+        builder.finish().unwrap();
+    }
+
+    #[test]
+    fn index_after_sort() {
+        let dir = crate::saveload::test_dir("objholder-index");
+        let manifest = |name: &str, overrides: &[&str]| PakManifest {
+            name: name.into(),
+            overrides: overrides.iter().map(|s| s.to_string()).collect(),
+            ..PakManifest::default()
+        };
+        write_pak(
+            &dir.join("a.pak"),
+            &manifest("a", &[]),
+            &[("c", "a"), ("a", "a"), ("d", "a")],
+        );
+        write_pak(
+            &dir.join("b.pak"),
+            &manifest("b", &["a"]),
+            &[("b", "b"), ("a", "b"), ("d", "b")],
+        );
+
+        let objholder = ObjectHolder::load(&[&dir]);
+        let ids: Vec<&str> = objholder.script.iter().map(|o| o.id.as_str()).collect();
+        assert_eq!(ids, ["a", "b", "c", "d"]);
+        for id in ["a", "b", "c", "d"] {
+            let idx = ScriptIdx::search_idx(id, &objholder).unwrap();
+            assert_eq!(idx.to_id(&objholder), id);
+        }
+        // Overridden by "b", and the duplicated one without override is ignored
+        assert_eq!(
+            ScriptObject::get_obj_from_objholder_by_id("a", &objholder)
+                .unwrap()
+                .script,
+            "b"
+        );
+        assert_eq!(
+            ScriptObject::get_obj_from_objholder_by_id("d", &objholder)
+                .unwrap()
+                .script,
+            "a"
+        );
+        assert!(ScriptIdx::search_idx("e", &objholder).is_none());
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -14,11 +14,43 @@ pub fn write_object<W: Write>(w: &mut W, obj: &Object) -> Result<(), String> {
     to_writer_with_mode(w, obj).map_err(|e| e.to_string())
 }
 
+/// Write pak manifest in the same format as objects
+pub fn write_pak_manifest<W: Write>(w: &mut W, manifest: &PakManifest) -> Result<(), String> {
+    to_writer_with_mode(w, manifest).map_err(|e| e.to_string())
+}
+
 /*
-  Implement load_objs_dir
+  Implement load_paks
 */
+use std::collections::{BTreeSet, HashMap};
 use std::fs;
-use std::path::Path;
+use std::path::{Path, PathBuf};
+
+/// Entry name of the manifest in pak files
+pub const PAK_MANIFEST_ENTRY: &str = ".pak-manifest";
+
+/// Metadata of a pak. Paks without manifest are named by their file name.
+#[derive(Clone, PartialEq, Eq, Default, Debug, Serialize, Deserialize)]
+pub struct PakManifest {
+    pub name: String,
+    pub version: String,
+    /// Paks which must be loaded before this pak
+    #[serde(default)]
+    pub depends: Vec<String>,
+    /// Paks which are loaded before this pak if they exist
+    #[serde(default)]
+    pub load_after: Vec<String>,
+    /// Ids of objects which replace objects with the same id loaded from other paks
+    #[serde(default)]
+    pub overrides: Vec<String>,
+}
+
+#[derive(Debug)]
+pub struct Pak {
+    pub path: PathBuf,
+    pub manifest: PakManifest,
+    pub objs: Vec<Object>,
+}
 
 #[derive(Debug)]
 pub enum PakLoadingError {
@@ -26,16 +58,35 @@ pub enum PakLoadingError {
     Cbor(serde_cbor::error::Error),
 }
 
-/// Load objects from pak files recursively
-pub fn load_objs_dir<F: FnMut(Object)>(dir: &Path, cb: F) -> Vec<PakLoadingError> {
+/// Load pak files in the given directories recursively. Pak files can be given directly also.
+/// Returned paks are sorted by load order.
+pub fn load_paks<P: AsRef<Path>>(dirs: &[P]) -> (Vec<Pak>, Vec<PakLoadingError>) {
     let mut err_stack = Vec::new();
-    let mut cb = cb;
+    let mut files = Vec::new();
+    for dir in dirs {
+        let dir = dir.as_ref();
+        if dir.is_file() {
+            files.push(dir.to_owned());
+        } else {
+            walk_dir(dir, &mut files, &mut err_stack);
+        }
+    }
 
-    walk_dir(dir, &mut cb, &mut err_stack);
-    err_stack
+    let mut paks: Vec<Option<Pak>> = files
+        .into_iter()
+        .map(|path| Some(read_pak(&path, &mut err_stack)))
+        .collect();
+    let manifests: Vec<&PakManifest> = paks
+        .iter()
+        .map(|pak| &pak.as_ref().unwrap().manifest)
+        .collect();
+    let order = load_order(&manifests);
+
+    let paks = order.into_iter().map(|i| paks[i].take().unwrap()).collect();
+    (paks, err_stack)
 }
 
-fn walk_dir(dir: &Path, cb: &mut dyn FnMut(Object), err_stack: &mut Vec<PakLoadingError>) {
+fn walk_dir(dir: &Path, files: &mut Vec<PathBuf>, err_stack: &mut Vec<PakLoadingError>) {
     let entry_iter = match fs::read_dir(dir) {
         Ok(o) => o,
         Err(e) => {
@@ -44,25 +95,73 @@ fn walk_dir(dir: &Path, cb: &mut dyn FnMut(Object), err_stack: &mut Vec<PakLoadi
         }
     };
 
+    let mut paths = Vec::new();
     for entry in entry_iter {
-        let entry = match entry {
-            Ok(o) => o,
-            Err(e) => {
-                err_stack.push(PakLoadingError::Io(e));
-                continue;
-            }
-        };
-        let path = entry.path();
+        match entry {
+            Ok(o) => paths.push(o.path()),
+            Err(e) => err_stack.push(PakLoadingError::Io(e)),
+        }
+    }
+    paths.sort();
+
+    for path in paths {
         if path.is_dir() {
-            walk_dir(&path, cb, err_stack);
+            walk_dir(&path, files, err_stack);
         } else if path.extension().is_some() && path.extension().unwrap() == "pak" {
-            read_tar(&path, cb, err_stack);
+            files.push(path);
+        }
+    }
+}
+
+/// Read a pak file with its manifest
+pub fn read_pak(path: &Path, err_stack: &mut Vec<PakLoadingError>) -> Pak {
+    let mut objs = Vec::new();
+    let mut manifest = None;
+    read_tar_entries(path, &mut |obj| objs.push(obj), &mut manifest, err_stack);
+
+    let manifest = manifest.unwrap_or_else(|| PakManifest {
+        name: path
+            .file_stem()
+            .map(|s| s.to_string_lossy().into_owned())
+            .unwrap_or_default(),
+        ..PakManifest::default()
+    });
+
+    Pak {
+        path: path.to_owned(),
+        manifest,
+        objs,
+    }
+}
+
+/// Read only the manifest of a pak file. Returns None if the pak has no manifest.
+pub fn read_pak_manifest(path: &Path) -> Result<Option<PakManifest>, PakLoadingError> {
+    let file = fs::File::open(path).map_err(PakLoadingError::Io)?;
+    let mut ar = tar::Archive::new(file);
+    for file in ar.entries().map_err(PakLoadingError::Io)? {
+        let file = file.map_err(PakLoadingError::Io)?;
+        if file
+            .path()
+            .map(|p| p == Path::new(PAK_MANIFEST_ENTRY))
+            .unwrap_or(false)
+        {
+            return from_reader(file).map(Some).map_err(PakLoadingError::Cbor);
         }
     }
+    Ok(None)
 }
 
 /// Read tar file and load objects
 pub fn read_tar(path: &Path, cb: &mut dyn FnMut(Object), err_stack: &mut Vec<PakLoadingError>) {
+    read_tar_entries(path, cb, &mut None, err_stack);
+}
+
+fn read_tar_entries(
+    path: &Path,
+    cb: &mut dyn FnMut(Object),
+    manifest: &mut Option<PakManifest>,
+    err_stack: &mut Vec<PakLoadingError>,
+) {
     let outputfile = match fs::File::open(path) {
         Ok(o) => o,
         Err(e) => {
@@ -90,6 +189,18 @@ pub fn read_tar(path: &Path, cb: &mut dyn FnMut(Object), err_stack: &mut Vec<Pak
             }
         };
 
+        if file
+            .path()
+            .map(|p| p == Path::new(PAK_MANIFEST_ENTRY))
+            .unwrap_or(false)
+        {
+            match from_reader(file) {
+                Ok(o) => *manifest = Some(o),
+                Err(e) => err_stack.push(PakLoadingError::Cbor(e)),
+            }
+            continue;
+        }
+
         let object = match read_object(file) {
             Ok(o) => o,
             Err(e) => {
@@ -101,3 +212,97 @@ pub fn read_tar(path: &Path, cb: &mut dyn FnMut(Object), err_stack: &mut Vec<Pak
         cb(object);
     }
 }
+
+/// Sort paks topologically by `depends` and `load_after`.
+/// Paks that can be loaded at the same time are sorted by name.
+fn load_order(manifests: &[&PakManifest]) -> Vec<usize> {
+    let n = manifests.len();
+    let mut by_name: HashMap<&str, Vec<usize>> = HashMap::new();
+    for (i, m) in manifests.iter().enumerate() {
+        by_name.entry(m.name.as_str()).or_default().push(i);
+    }
+
+    let mut n_waiting = vec![0; n];
+    let mut dependents = vec![Vec::new(); n];
+    for (i, m) in manifests.iter().enumerate() {
+        for name in &m.depends {
+            if !by_name.contains_key(name.as_str()) {
+                error!(
+                    "pak \"{}\" depends on \"{}\", but it is not found",
+                    m.name, name
+                );
+            }
+        }
+        for name in m.depends.iter().chain(m.load_after.iter()) {
+            for &j in by_name.get(name.as_str()).into_iter().flatten() {
+                if j != i {
+                    n_waiting[i] += 1;
+                    dependents[j].push(i);
+                }
+            }
+        }
+    }
+
+    let mut ready: BTreeSet<(&str, usize)> = (0..n)
+        .filter(|&i| n_waiting[i] == 0)
+        .map(|i| (manifests[i].name.as_str(), i))
+        .collect();
+    let mut order = Vec::with_capacity(n);
+    while let Some((_, i)) = ready.pop_first() {
+        order.push(i);
+        for &k in &dependents[i] {
+            n_waiting[k] -= 1;
+            if n_waiting[k] == 0 {
+                ready.insert((manifests[k].name.as_str(), k));
+            }
+        }
+    }
+
+    if order.len() < n {
+        let mut rest: Vec<usize> = (0..n).filter(|&i| n_waiting[i] > 0).collect();
+        rest.sort_by_key(|&i| (manifests[i].name.as_str(), i));
+        error!(
+            "circular dependency between paks: {}",
+            rest.iter()
+                .map(|&i| manifests[i].name.as_str())
+                .collect::<Vec<_>>()
+                .join(", ")
+        );
+        order.extend(rest);
+    }
+    order
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn manifest(name: &str, depends: &[&str], load_after: &[&str]) -> PakManifest {
+        PakManifest {
+            name: name.into(),
+            depends: depends.iter().map(|s| s.to_string()).collect(),
+            load_after: load_after.iter().map(|s| s.to_string()).collect(),
+            ..PakManifest::default()
+        }
+    }
+
+    #[test]
+    fn pak_load_order() {
+        let m = [
+            manifest("addon", &["base"], &["extra"]),
+            manifest("extra", &[], &["missing"]),
+            manifest("base", &[], &[]),
+            manifest("another", &[], &[]),
+        ];
+        let manifests: Vec<&PakManifest> = m.iter().collect();
+        assert_eq!(load_order(&manifests), vec![3, 2, 1, 0]);
+
+        let m = [
+            manifest("a", &["b"], &[]),
+            manifest("b", &["a"], &[]),
+            manifest("c", &[], &[]),
+        ];
+        let manifests: Vec<&PakManifest> = m.iter().collect();
+        assert_eq!(load_order(&manifests), vec![2, 0, 1]);
+    }
+}
@@ -0,0 +1,63 @@
+//! Global state which can be replaced at runtime for development.
+
+use once_cell::sync::OnceCell;
+use std::ops::Deref;
+use std::sync::RwLock;
+
+/// Global state initialized lazily like `Lazy`, and replaced by `replace()`.
+/// Replaced values are leaked, because `&'static` references to them may remain.
+pub struct Reloadable<T: 'static> {
+    init: fn() -> T,
+    value: OnceCell<RwLock<&'static T>>,
+}
+
+impl<T: Sync + 'static> Reloadable<T> {
+    pub const fn new(init: fn() -> T) -> Self {
+        Reloadable {
+            init,
+            value: OnceCell::new(),
+        }
+    }
+
+    /// Initialize the value if it is not initialized
+    pub fn force(this: &Self) {
+        this.get();
+    }
+
+    pub fn get(&self) -> &'static T {
+        let value = self
+            .value
+            .get_or_init(|| RwLock::new(Box::leak(Box::new((self.init)()))));
+        *value.read().expect("reloadable value lock error")
+    }
+
+    /// Replace the value. References to the previous value are still valid.
+    pub fn replace(&self, value: T) {
+        let value: &'static T = Box::leak(Box::new(value));
+        let lock = self.value.get_or_init(|| RwLock::new(value));
+        *lock.write().expect("reloadable value lock error") = value;
+    }
+}
+
+impl<T: Sync + 'static> Deref for Reloadable<T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        self.get()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    static VALUE: Reloadable<String> = Reloadable::new(|| "first".to_owned());
+
+    #[test]
+    fn replace() {
+        let first: &'static str = &VALUE;
+        VALUE.replace("second".to_owned());
+        assert_eq!(first, "first");
+        assert_eq!(*VALUE, "second");
+    }
+}
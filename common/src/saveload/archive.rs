@@ -0,0 +1,296 @@
+//! Export and import a save as one archive file.
+//!
+//! An archive is a tar file. The first entry is "filelist",
+//! which has a header line and "<path> <size>" lines for all following entries.
+
+use super::sibling_path;
+use super::thumbnail::THUMBNAIL_FILE;
+use crate::basic::SAVE_EXTENSION;
+use crate::gamedata::*;
+use anyhow::{bail, ensure, Context, Error};
+use std::collections::HashMap;
+use std::fs::{self, File};
+use std::io::{BufRead, BufReader, BufWriter, Read, Write};
+use std::path::{Component, Path, PathBuf};
+
+const FILE_LIST: &str = "filelist";
+const FILE_LIST_HEADER: &str = "rrsave 1";
+const REQUIRED_FILES: &[&str] = &["metadata", "idtable", "gamedata"];
+
+impl GameData {
+    /// Export the save directory to an archive file.
+    /// The save must be written by `save()` before exporting.
+    /// Like `clean_map_dir()`, only maps used by this game data are included.
+    pub fn export_archive<P: AsRef<Path>, Q: AsRef<Path>>(
+        &self,
+        save_dir: P,
+        archive_path: Q,
+    ) -> Result<(), Error> {
+        let save_dir = save_dir.as_ref();
+
+        let mut files: Vec<String> = REQUIRED_FILES.iter().map(|f| f.to_string()).collect();
+        if save_dir.join(THUMBNAIL_FILE).exists() {
+            files.push(THUMBNAIL_FILE.to_owned());
+        }
+        self.region.visit_all_maps(|_, map| {
+            files.push(map_file_path(map.id()));
+        });
+
+        let mut entries = Vec::new();
+        for file in files {
+            let data = fs::read(save_dir.join(&file))
+                .with_context(|| format!("reading \"{}\" in the save", file))?;
+            entries.push((file, data));
+        }
+
+        let mut file_list = String::new();
+        file_list.push_str(FILE_LIST_HEADER);
+        file_list.push('\n');
+        for (path, data) in &entries {
+            file_list.push_str(&format!("{} {}\n", path, data.len()));
+        }
+
+        let archive_path = archive_path.as_ref();
+        let tmp_path = archive_path.with_extension("tmp");
+        let mut builder = tar::Builder::new(BufWriter::new(File::create(&tmp_path)?));
+        append_data(&mut builder, FILE_LIST, file_list.as_bytes())?;
+        for (path, data) in &entries {
+            append_data(&mut builder, path, data)?;
+        }
+        let file = builder.into_inner()?;
+        let file = file.into_inner().map_err(|e| e.into_error())?;
+        file.sync_all()?;
+        fs::rename(&tmp_path, archive_path)?;
+
+        Ok(())
+    }
+}
+
+/// Import an archive file to a new save directory in `save_root`.
+/// Returns the path of the created save directory.
+pub fn import_archive<P: AsRef<Path>, Q: AsRef<Path>>(
+    archive_path: P,
+    save_root: Q,
+) -> Result<PathBuf, Error> {
+    let file = BufReader::new(File::open(archive_path.as_ref())?);
+    let mut archive = tar::Archive::new(file);
+    let mut entries = archive.entries()?;
+
+    // Read file list
+    let mut entry = entries.next().context("empty archive")??;
+    ensure!(
+        entry.path()?.to_str() == Some(FILE_LIST),
+        "archive does not start with file list"
+    );
+    let file_list = read_file_list(&mut entry)?;
+
+    // Read and validate all files
+    let mut files: HashMap<String, Vec<u8>> = HashMap::new();
+    for entry in entries {
+        let mut entry = entry?;
+        let path = entry
+            .path()?
+            .to_str()
+            .context("invalid path in archive")?
+            .to_owned();
+        let size = if let Some(size) = file_list.get(&path) {
+            *size
+        } else {
+            bail!("\"{}\" is not in the file list", path);
+        };
+        ensure!(
+            entry.size() == size,
+            "size of \"{}\" does not match the file list",
+            path
+        );
+        let mut data = Vec::new();
+        entry.read_to_end(&mut data)?;
+        ensure!(
+            files.insert(path.clone(), data).is_none(),
+            "\"{}\" is duplicated",
+            path
+        );
+    }
+    for path in file_list.keys() {
+        ensure!(
+            files.contains_key(path),
+            "\"{}\" is missing in archive",
+            path
+        );
+    }
+
+    let meta: MetaData = serde_json::from_slice(&files["metadata"]).context("reading metadata")?;
+    ensure!(
+        is_valid_save_name(meta.save_name()),
+        "invalid save name \"{}\"",
+        meta.save_name()
+    );
+    let save_dir = save_root
+        .as_ref()
+        .join(format!("{}.{}", meta.save_name(), SAVE_EXTENSION));
+    ensure!(
+        !save_dir.exists(),
+        "save \"{}\" already exists",
+        save_dir.display()
+    );
+
+    // Write files to a staging directory, and rename it after all files are written
+    let staging_dir = sibling_path(&save_dir, "tmp");
+    if staging_dir.exists() {
+        fs::remove_dir_all(&staging_dir)?;
+    }
+    fs::create_dir_all(staging_dir.join("maps"))?;
+    for (path, data) in &files {
+        let mut file = BufWriter::new(File::create(staging_dir.join(path))?);
+        file.write_all(data)?;
+        super::sync_file(file)?;
+    }
+    fs::rename(&staging_dir, &save_dir)?;
+
+    Ok(save_dir)
+}
+
+/// Read file list, and check that all paths are valid save files
+fn read_file_list<R: Read>(r: R) -> Result<HashMap<String, u64>, Error> {
+    let mut lines = BufReader::new(r).lines();
+    let header = lines.next().context("empty file list")??;
+    ensure!(
+        header == FILE_LIST_HEADER,
+        "unsupported archive \"{}\"",
+        header
+    );
+
+    let mut file_list = HashMap::new();
+    for line in lines {
+        let line = line?;
+        let (path, size) = line.split_once(' ').context("invalid file list")?;
+        let size: u64 = size.parse().context("invalid file list")?;
+        ensure!(
+            is_save_file_path(path),
+            "invalid path \"{}\" in file list",
+            path
+        );
+        ensure!(
+            file_list.insert(path.to_owned(), size).is_none(),
+            "\"{}\" is duplicated in file list",
+            path
+        );
+    }
+
+    for file in REQUIRED_FILES {
+        ensure!(file_list.contains_key(*file), "\"{}\" is missing", file);
+    }
+    Ok(file_list)
+}
+
+fn is_save_file_path(path: &str) -> bool {
+    if REQUIRED_FILES.contains(&path) || path == THUMBNAIL_FILE {
+        return true;
+    }
+    if let Some(name) = path.strip_prefix("maps/") {
+        name.len() == 16 && name.chars().all(|c| c.is_ascii_hexdigit())
+    } else {
+        false
+    }
+}
+
+/// Save names in archives must not point outside of the save directory
+fn is_valid_save_name(name: &str) -> bool {
+    let mut components = Path::new(name).components();
+    !name.contains(['/', '\\'])
+        && !name.contains("..")
+        && matches!(
+            (components.next(), components.next()),
+            (Some(Component::Normal(_)), None)
+        )
+}
+
+fn map_file_path(id: u64) -> String {
+    format!("maps/{:016x}", id)
+}
+
+fn append_data<W: Write>(
+    builder: &mut tar::Builder<W>,
+    path: &str,
+    data: &[u8],
+) -> Result<(), std::io::Error> {
+    let mut header = tar::Header::new_gnu();
+    header.set_path(path)?;
+    header.set_size(data.len() as u64);
+    header.set_mtime(0);
+    header.set_mode(0o644);
+    header.set_cksum();
+    builder.append(&header, data)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn save_file_path() {
+        assert!(is_save_file_path("gamedata"));
+        assert!(is_save_file_path(THUMBNAIL_FILE));
+        assert!(is_save_file_path(&map_file_path(0x1234)));
+        assert!(!is_save_file_path("maps/../gamedata"));
+        assert!(!is_save_file_path("../metadata"));
+        assert!(!is_save_file_path("maps/0000"));
+    }
+
+    #[test]
+    fn file_list() {
+        let list = "rrsave 1\nmetadata 10\nidtable 20\ngamedata 30\nmaps/00000000000000ff 40\n";
+        let list = read_file_list(list.as_bytes()).unwrap();
+        assert_eq!(list["maps/00000000000000ff"], 40);
+
+        assert!(read_file_list("rrsave 1\nmetadata 10\n".as_bytes()).is_err());
+        assert!(read_file_list("rrsave 2\n".as_bytes()).is_err());
+    }
+
+    /// Write an archive which has the given save name
+    fn write_archive(path: &Path, save_name: &str) {
+        let mut meta = MetaData::default();
+        meta.set_save_name(save_name);
+        let entries = [
+            ("metadata", serde_json::to_vec(&meta).unwrap()),
+            ("idtable", b"0000000000000000\n".to_vec()),
+            ("gamedata", Vec::new()),
+        ];
+        let mut file_list = format!("{}\n", FILE_LIST_HEADER);
+        for (path, data) in &entries {
+            file_list.push_str(&format!("{} {}\n", path, data.len()));
+        }
+
+        let mut builder = tar::Builder::new(File::create(path).unwrap());
+        append_data(&mut builder, FILE_LIST, file_list.as_bytes()).unwrap();
+        for (path, data) in &entries {
+            append_data(&mut builder, path, data).unwrap();
+        }
+        builder.finish().unwrap();
+    }
+
+    #[test]
+    fn import_invalid_save_name() {
+        let root = super::super::test_dir("import-archive");
+        let save_root = root.join("saves");
+        fs::create_dir_all(&save_root).unwrap();
+        let archive = root.join("a.rrsave");
+
+        for name in ["../../x", "..", "a/b", "a\\b", "/x", ""] {
+            write_archive(&archive, name);
+            assert!(import_archive(&archive, &save_root).is_err(), "{}", name);
+        }
+        assert_eq!(fs::read_dir(&save_root).unwrap().count(), 0);
+        assert_eq!(fs::read_dir(&root).unwrap().count(), 2);
+
+        write_archive(&archive, "valid");
+        let save_dir = import_archive(&archive, &save_root).unwrap();
+        assert_eq!(
+            save_dir,
+            save_root.join(format!("valid.{}", SAVE_EXTENSION))
+        );
+        assert!(save_dir.join("gamedata").exists());
+
+        fs::remove_dir_all(&root).unwrap();
+    }
+}
@@ -0,0 +1,339 @@
+//! Migration of save data written by older versions.
+//!
+//! Each step upgrades `gamedata` and map blobs by one save format version.
+//! The blobs are handled as untyped CBOR values, so steps can rename, add or remove fields
+//! before the data is deserialized as the current struct layout.
+
+use crate::basic::SAVE_FORMAT_VERSION;
+use anyhow::{bail, Error};
+use serde::de::DeserializeOwned;
+use serde_cbor::Value;
+use std::io::Read;
+use std::sync::atomic::{AtomicU32, Ordering};
+
+/// Function to upgrade an untyped CBOR value in place
+pub type MigrationFn = fn(&mut Value) -> Result<(), Error>;
+
+/// Upgrade step from `from` to `from + 1`
+pub struct Migration {
+    pub from: u32,
+    pub gamedata: MigrationFn,
+    pub map: MigrationFn,
+}
+
+/// Registered migration steps. Must be sorted by `from`, and cover all versions
+/// from 0 to `SAVE_FORMAT_VERSION - 1`.
+static MIGRATIONS: &[Migration] = &[
+    // Version 0 saves do not have the version field in metadata.
+    // Their layout is the same as version 1.
+    Migration {
+        from: 0,
+        gamedata: no_change,
+        map: no_change,
+    },
+    Migration {
+        from: 1,
+        gamedata: status_effect_gamedata,
+        map: status_effect_map,
+    },
+];
+
+/// Format version of map files that are being read.
+/// Map files are read lazily by FileBox, so this is set while loading a save.
+static MAP_FORMAT_VERSION: AtomicU32 = AtomicU32::new(SAVE_FORMAT_VERSION);
+
+pub fn set_map_format_version(version: u32) {
+    MAP_FORMAT_VERSION.store(version, Ordering::Relaxed);
+}
+
+pub(crate) fn map_format_version() -> u32 {
+    MAP_FORMAT_VERSION.load(Ordering::Relaxed)
+}
+
+/// Check that the given version can be upgraded to the current version.
+pub fn check_version(version: u32) -> Result<(), Error> {
+    if version > SAVE_FORMAT_VERSION {
+        bail!(
+            "save format version {} is newer than supported version {}",
+            version,
+            SAVE_FORMAT_VERSION
+        );
+    }
+    for v in version..SAVE_FORMAT_VERSION {
+        if !MIGRATIONS.iter().any(|m| m.from == v) {
+            bail!("no migration step from save format version {}", v);
+        }
+    }
+    Ok(())
+}
+
+/// Read gamedata written in the given format version
+pub fn read_gamedata<T: DeserializeOwned, R: Read>(r: R, version: u32) -> Result<T, Error> {
+    read_with_migration(r, version, |m| m.gamedata)
+}
+
+/// Read a map written in the given format version
+pub(crate) fn read_map<T: DeserializeOwned, R: Read>(r: R, version: u32) -> Result<T, Error> {
+    read_with_migration(r, version, |m| m.map)
+}
+
+fn read_with_migration<T: DeserializeOwned, R: Read>(
+    r: R,
+    version: u32,
+    step: fn(&Migration) -> MigrationFn,
+) -> Result<T, Error> {
+    if version == SAVE_FORMAT_VERSION {
+        return Ok(serde_cbor::from_reader(r)?);
+    }
+
+    check_version(version)?;
+    let mut value: Value = serde_cbor::from_reader(r)?;
+    migrate(&mut value, version, step)?;
+    Ok(serde_cbor::value::from_value(value)?)
+}
+
+fn migrate(
+    value: &mut Value,
+    version: u32,
+    step: fn(&Migration) -> MigrationFn,
+) -> Result<(), Error> {
+    for m in MIGRATIONS
+        .iter()
+        .filter(|m| version <= m.from && m.from < SAVE_FORMAT_VERSION)
+    {
+        info!(
+            "migrate save data from version {} to {}",
+            m.from,
+            m.from + 1
+        );
+        step(m)(value)?;
+    }
+    Ok(())
+}
+
+fn no_change(_: &mut Value) -> Result<(), Error> {
+    Ok(())
+}
+
+// Version 2 moved Scanned, Asleep and Poisoned statuses to the status_effects rule.
+
+fn status_effect_gamedata(value: &mut Value) -> Result<(), Error> {
+    if let Some(chara_holder) = field_mut(value, 0, "chara") {
+        for (index, name) in [(0, "c"), (1, "on_map")] {
+            if let Some(charas) = field_mut(chara_holder, index, name) {
+                status_effect_charas(charas);
+            }
+        }
+    }
+    Ok(())
+}
+
+fn status_effect_map(value: &mut Value) -> Result<(), Error> {
+    if let Some(charas) = field_mut(value, 10, "charas") {
+        status_effect_charas(charas);
+    }
+    Ok(())
+}
+
+fn status_effect_charas(charas: &mut Value) {
+    if let Value::Map(charas) = charas {
+        for chara in charas.values_mut() {
+            if let Some(Value::Array(status)) = field_mut(chara, 16, "status") {
+                status.iter_mut().for_each(status_effect);
+            }
+        }
+    }
+}
+
+/// Convert a `CharaStatus` value. Variants are stored by index in packed format.
+fn status_effect(status: &mut Value) {
+    let new_status = match &mut *status {
+        Value::Map(map) if map.len() == 1 => {
+            let (variant, fields) = map.iter_mut().next().unwrap();
+            if is_variant(variant, 8, "Asleep") {
+                let turn_left = field_mut(fields, 0, "turn_left").cloned();
+                effect_status(variant, "asleep", turn_left.unwrap_or(Value::Integer(0)))
+            } else if *variant == Value::Integer(10) {
+                // Index of Work is changed
+                let fields = std::mem::replace(fields, Value::Null);
+                Value::Map([(Value::Integer(8), fields)].into())
+            } else {
+                return;
+            }
+        }
+        unit => {
+            if is_variant(unit, 7, "Scanned") {
+                effect_status(unit, "scanned", Value::Null)
+            } else if is_variant(unit, 9, "Poisoned") {
+                effect_status(unit, "poisoned", Value::Null)
+            } else {
+                return;
+            }
+        }
+    };
+    *status = new_status;
+}
+
+fn is_variant(variant: &Value, index: i128, name: &str) -> bool {
+    match variant {
+        Value::Integer(i) => *i == index,
+        Value::Text(s) => s == name,
+        _ => false,
+    }
+}
+
+/// `CharaStatus::Effect` in the same format as the given variant
+fn effect_status(variant: &Value, id: &str, turn_left: Value) -> Value {
+    let packed = matches!(variant, Value::Integer(_));
+    let key = |index: u32, name: &str| {
+        if packed {
+            Value::Integer(index.into())
+        } else {
+            Value::Text(name.into())
+        }
+    };
+    let fields = [
+        (key(0, "id"), Value::Text(id.into())),
+        (key(1, "turn_left"), turn_left),
+        (key(2, "stack"), Value::Integer(1)),
+    ];
+    Value::Map([(key(7, "Effect"), Value::Map(fields.into()))].into())
+}
+
+/// Get a struct field for migration steps.
+/// Fields are stored by index in packed format, and by name in named format.
+pub fn field_mut<'a>(value: &'a mut Value, index: u32, name: &str) -> Option<&'a mut Value> {
+    let map = if let Value::Map(map) = value {
+        map
+    } else {
+        return None;
+    };
+    let key = if map.contains_key(&Value::Text(name.into())) {
+        Value::Text(name.into())
+    } else {
+        Value::Integer(index.into())
+    };
+    map.get_mut(&key)
+}
+
+/// Insert a struct field for migration steps.
+/// The key type follows the existing fields of the struct.
+pub fn insert_field(value: &mut Value, index: u32, name: &str, field: Value) -> Result<(), Error> {
+    let map = if let Value::Map(map) = value {
+        map
+    } else {
+        bail!("tried to insert field \"{}\" to non-struct value", name);
+    };
+    let named = map.keys().any(|k| matches!(k, Value::Text(_)));
+    let key = if named {
+        Value::Text(name.into())
+    } else {
+        Value::Integer(index.into())
+    };
+    map.insert(key, field);
+    Ok(())
+}
+
+/// Remove a struct field for migration steps.
+pub fn remove_field(value: &mut Value, index: u32, name: &str) -> Option<Value> {
+    if let Value::Map(map) = value {
+        map.remove(&Value::Text(name.into()))
+            .or_else(|| map.remove(&Value::Integer(index.into())))
+    } else {
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Serialize, Deserialize)]
+    struct A {
+        a: u32,
+        b: String,
+    }
+
+    #[test]
+    fn migrations_cover_all_versions() {
+        for v in 0..=SAVE_FORMAT_VERSION {
+            check_version(v).unwrap();
+        }
+        assert!(check_version(SAVE_FORMAT_VERSION + 1).is_err());
+    }
+
+    #[test]
+    fn field_access() {
+        let packed = serde_cbor::ser::to_vec_packed(&A {
+            a: 1,
+            b: "b".into(),
+        })
+        .unwrap();
+        let named = serde_cbor::to_vec(&A {
+            a: 1,
+            b: "b".into(),
+        })
+        .unwrap();
+
+        for v in [packed, named] {
+            let mut value: Value = serde_cbor::from_slice(&v).unwrap();
+            assert_eq!(field_mut(&mut value, 0, "a"), Some(&mut Value::Integer(1)));
+            remove_field(&mut value, 0, "a").unwrap();
+            insert_field(&mut value, 0, "a", Value::Integer(2)).unwrap();
+            let a: A = serde_cbor::value::from_value(value).unwrap();
+            assert_eq!(a.a, 2);
+        }
+    }
+
+    /// `CharaStatus` in version 1 without Work
+    #[derive(Serialize)]
+    #[allow(dead_code)]
+    enum OldStatus {
+        Hungry,
+        Weak,
+        Starving,
+        Burdened,
+        Stressed,
+        Strained,
+        Overloaded,
+        Scanned,
+        Asleep { turn_left: u16 },
+        Poisoned,
+    }
+
+    #[test]
+    fn status_effect_variants() {
+        use crate::gamedata::CharaStatus;
+
+        let old = vec![
+            OldStatus::Hungry,
+            OldStatus::Scanned,
+            OldStatus::Asleep { turn_left: 3 },
+            OldStatus::Poisoned,
+        ];
+        let packed = serde_cbor::ser::to_vec_packed(&old).unwrap();
+        let named = serde_cbor::to_vec(&old).unwrap();
+
+        let effect = |id: &str, turn_left| CharaStatus::Effect {
+            id: id.into(),
+            turn_left,
+            stack: 1,
+        };
+        for v in [packed, named] {
+            let mut value: Value = serde_cbor::from_slice(&v).unwrap();
+            if let Value::Array(status) = &mut value {
+                status.iter_mut().for_each(status_effect);
+            }
+            let status: Vec<CharaStatus> = serde_cbor::value::from_value(value).unwrap();
+            assert_eq!(
+                status,
+                vec![
+                    CharaStatus::Hungry,
+                    effect("scanned", None),
+                    effect("asleep", Some(3)),
+                    effect("poisoned", None),
+                ]
+            );
+        }
+    }
+}
@@ -0,0 +1,703 @@
+pub mod archive;
+pub mod migration;
+pub mod snapshot;
+pub mod thumbnail;
+
+use crate::basic::{SAVE_EXTENSION, SAVE_FORMAT_VERSION};
+use crate::gamedata::*;
+use crate::impl_filebox::MapLoadError;
+use crate::utils::to_writer_with_mode;
+use anyhow::Error;
+use std::fs::{self, create_dir_all, File};
+use std::io::{BufReader, BufWriter, Write};
+use std::path::{Path, PathBuf};
+
+#[cfg(feature = "global_state_obj")]
+impl GameData {
+    /// Save game data to the specified directory.
+    /// Files are written to a staging directory at first, and it is swapped with the previous save.
+    /// The previous saves are kept as backups up to `n_backups` generations.
+    pub fn save<P: AsRef<Path>>(&self, path: P, n_backups: u32) -> Result<(), Error> {
+        if cfg!(debug_assertions) {
+            print_save_data_size(self); // Debug code for save file size optimization
+        }
+
+        let save_dir = path.as_ref();
+        let staging_dir = staging_dir(save_dir);
+
+        // Remove staging directory remaining by crash
+        if staging_dir.exists() {
+            fs::remove_dir_all(&staging_dir)?;
+        }
+        create_dir_all(&staging_dir)?;
+
+        if let Err(e) = self.write_save_files(save_dir, &staging_dir) {
+            // Maps written to the staging directory must be written again at the next time
+            self.region.visit_all_maps(|_, map| map.mark_changed());
+            let _ = fs::remove_dir_all(&staging_dir);
+            return Err(e);
+        }
+
+        replace_save(&staging_dir, save_dir, n_backups)?;
+
+        // Unloaded maps are included in the new save
+        let swap_dir = sibling_path(save_dir, "swap");
+        if swap_dir.exists() {
+            fs::remove_dir_all(&swap_dir)?;
+        }
+
+        Ok(())
+    }
+
+    fn write_save_files(&self, save_dir: &Path, staging_dir: &Path) -> Result<(), Error> {
+        // Write id table file
+        let mut file = BufWriter::new(File::create(staging_dir.join("idtable"))?);
+        writeln!(file, "{:016x}", *crate::gobj::OBJ_HOLDER_HASH)?;
+        crate::gobj::get_objholder().write_table(&mut file)?;
+        sync_file(file)?;
+
+        // Write metadata file
+        let mut file = BufWriter::new(File::create(staging_dir.join("metadata"))?);
+        serde_json::to_writer_pretty(&mut file, &self.meta_to_save())?;
+        sync_file(file)?;
+
+        // Write thumbnail. Saving is continued without it if failed.
+        match self.thumbnail().to_png() {
+            Ok(data) => fs::write(staging_dir.join(thumbnail::THUMBNAIL_FILE), data)?,
+            Err(e) => warn!("cannot create thumbnail: {}", e),
+        }
+
+        // Write GameData
+        let mut file = BufWriter::new(File::create(staging_dir.join("gamedata"))?);
+        to_writer_with_mode(&mut file, &self)?;
+        sync_file(file)?;
+
+        // Write maps
+        let prev_map_dir = save_dir.join("maps");
+        let swap_map_dir = swap_map_dir(&prev_map_dir);
+        let map_dir = staging_dir.join("maps");
+        create_dir_all(&map_dir)?;
+
+        let mut error_occured = false;
+
+        self.region.visit_all_maps(|mid, map| {
+            trace!("Saving map {:?}", mid);
+            // Unchanged maps are not written, so take over the files of the previous save.
+            // Maps unloaded after the previous save are taken from the swap directory.
+            let swap_path = map.path(&swap_map_dir);
+            let prev_path = if swap_path.exists() {
+                swap_path
+            } else {
+                map.path(&prev_map_dir)
+            };
+            if prev_path.exists() {
+                if let Err(e) = link_or_copy(&prev_path, &map.path(&map_dir)) {
+                    error!("{}", e);
+                    error_occured = true;
+                    return;
+                }
+            }
+            match BoxedMap::write(map, &map_dir) {
+                Ok(_) => (),
+                Err(e) => {
+                    error!("{}", e);
+                    error_occured = true;
+                }
+            }
+        });
+
+        if error_occured {
+            anyhow::bail!("Map saving failed");
+        }
+
+        Ok(())
+    }
+
+    /// Load game data from specified directory
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<GameData, Error> {
+        let save_dir = path.as_ref();
+
+        // Remove unloaded maps remaining from the previous play
+        let swap_dir = sibling_path(save_dir, "swap");
+        if swap_dir.exists() {
+            fs::remove_dir_all(&swap_dir)?;
+        }
+
+        // Read metadata file
+        let mut meta = read_metadata(save_dir)?;
+        let format_version = meta.format_version();
+        migration::check_version(format_version)?;
+        if format_version != SAVE_FORMAT_VERSION {
+            info!(
+                "Save format version is {}. Migration to version {} is needed.",
+                format_version, SAVE_FORMAT_VERSION
+            );
+        }
+
+        // Read index conversion table
+        let mut file = BufReader::new(File::open(save_dir.join("idtable"))?);
+        let idx_conv_table =
+            crate::idx_conv::IdxConvTable::read(&mut file, *crate::gobj::OBJ_HOLDER_HASH)?;
+        let is_table_changed = idx_conv_table.is_some();
+        if is_table_changed {
+            info!("Detected changes in the id table. Conversion table is created.");
+        }
+        crate::idx_conv::set_idx_conv_table(idx_conv_table);
+
+        // Read GameData
+        let mut file = BufReader::new(File::open(save_dir.join("gamedata"))?);
+        let mut gamedata: GameData = migration::read_gamedata(&mut file, format_version)?;
+        meta.set_format_version(SAVE_FORMAT_VERSION);
+        gamedata.meta = meta;
+
+        let map_dir = save_dir.join("maps");
+        if is_table_changed || format_version != SAVE_FORMAT_VERSION {
+            // Preload is needed if id table or save format is changed
+            migration::set_map_format_version(format_version);
+            let mut mid_vec = Vec::new();
+            gamedata.region.visit_all_maps(|mid, map| {
+                mid_vec.push(mid);
+            });
+            let current_mid = gamedata.get_current_mapid();
+            for mid in &mid_vec {
+                gamedata.region.preload_map_with_opts(*mid, &map_dir, true);
+                // Converted maps are written to the swap directory if there are too many
+                if max_loaded_maps() > 0 {
+                    gamedata
+                        .region
+                        .unload_old_maps(&map_dir, max_loaded_maps(), current_mid)?;
+                }
+            }
+            migration::set_map_format_version(SAVE_FORMAT_VERSION);
+        } else {
+            // Preload current map
+            let mid = gamedata.get_current_mapid();
+            gamedata.region.preload_map(mid, &map_dir);
+        }
+
+        if is_table_changed {
+            if let Some(report) = crate::idx_conv::missing_obj_report() {
+                warn!("{}", report);
+            }
+        }
+
+        Ok(gamedata)
+    }
+
+    /// Load game data from specified directory.
+    /// If loading fails, tries backups from the newest one.
+    /// The loaded backup is restored to the save directory, and the failed save is kept as "<save>.broken".
+    pub fn load_with_backups<P: AsRef<Path>>(path: P) -> Result<GameData, Error> {
+        let save_dir = path.as_ref();
+
+        let e = match GameData::load(save_dir) {
+            Ok(gamedata) => return Ok(gamedata),
+            Err(e) => e,
+        };
+        warn!("Failed to load {}: {}", save_dir.display(), e);
+
+        for backup_dir in backup_dirs(save_dir) {
+            match GameData::load(&backup_dir) {
+                Ok(gamedata) => {
+                    info!("Loaded backup {}", backup_dir.display());
+                    restore_backup(&backup_dir, save_dir)?;
+                    move_swap_dir(&backup_dir, save_dir)?;
+                    return Ok(gamedata);
+                }
+                Err(e) => {
+                    warn!("Failed to load backup {}: {}", backup_dir.display(), e);
+                }
+            }
+        }
+
+        Err(e)
+    }
+
+    /// Load the autosave of the given save directory, and import it as a new save
+    /// named "<save>-autosave". The save directory and its backups are not changed.
+    pub fn load_autosave<P: AsRef<Path>>(path: P) -> Result<GameData, Error> {
+        let save_dir = path.as_ref();
+        let auto_dir = autosave_dir(save_dir);
+        let mut gamedata = GameData::load(&auto_dir)?;
+
+        let (save_name, new_dir) = autosave_import_dir(save_dir);
+        gamedata.meta.set_save_name(&save_name);
+
+        let staging_dir = staging_dir(&new_dir);
+        if staging_dir.exists() {
+            fs::remove_dir_all(&staging_dir)?;
+        }
+        let result = copy_save_files(&auto_dir, &staging_dir).and_then(|_| {
+            let mut meta = read_metadata(&staging_dir)?;
+            meta.set_save_name(&save_name);
+            let mut file = BufWriter::new(File::create(staging_dir.join("metadata"))?);
+            serde_json::to_writer_pretty(&mut file, &meta)?;
+            Ok(sync_file(file)?)
+        });
+        if let Err(e) = result {
+            let _ = fs::remove_dir_all(&staging_dir);
+            return Err(e);
+        }
+        fs::rename(&staging_dir, &new_dir)?;
+        move_swap_dir(&auto_dir, &new_dir)?;
+        info!("Imported autosave to {}", new_dir.display());
+
+        Ok(gamedata)
+    }
+
+    /// Reload objects from pak files, and convert object indices in game data for them.
+    /// All maps are loaded from the save directory before the conversion.
+    pub fn reload_objs<P: AsRef<Path>>(&mut self, path: P) -> Result<(), Error> {
+        let map_dir = path.as_ref().join("maps");
+        let mut mid_vec = Vec::new();
+        self.region.visit_all_maps(|mid, _| mid_vec.push(mid));
+        for mid in &mid_vec {
+            self.region.preload_map(*mid, &map_dir);
+        }
+
+        // Serialize game data and maps with the id table before reloading
+        let mut idtable = Vec::new();
+        writeln!(idtable, "{:016x}", *crate::gobj::OBJ_HOLDER_HASH)?;
+        crate::gobj::get_objholder().write_table(&mut idtable)?;
+        let mut gamedata = Vec::new();
+        to_writer_with_mode(&mut gamedata, &*self)?;
+        let mut maps = Vec::new();
+        let mut error = None;
+        self.region.visit_all_maps(|mid, map| match map.to_bytes() {
+            Ok(Some(data)) => maps.push((mid, map.id(), data)),
+            Ok(None) => unreachable!("all maps are preloaded"),
+            Err(e) => error = Some(e),
+        });
+        if let Some(e) = error {
+            return Err(e.into());
+        }
+
+        crate::gobj::reload();
+
+        let idx_conv_table =
+            crate::idx_conv::IdxConvTable::read(&idtable[..], *crate::gobj::OBJ_HOLDER_HASH)?;
+        if idx_conv_table.is_some() {
+            crate::idx_conv::set_idx_conv_table(idx_conv_table);
+            let result = self.read_converted(&gamedata, maps);
+            if let Some(report) = crate::idx_conv::missing_obj_report() {
+                warn!("{}", report);
+            }
+            // Data in memory and the swap directory have the current indices after the conversion
+            crate::idx_conv::set_idx_conv_table(None);
+            result?;
+        } else {
+            info!("No changes in the id table");
+        }
+
+        if max_loaded_maps() > 0 {
+            let current_mid = self.get_current_mapid();
+            self.region
+                .unload_old_maps(&map_dir, max_loaded_maps(), current_mid)?;
+        }
+        Ok(())
+    }
+
+    /// Read serialized game data and maps with the current index conversion table
+    fn read_converted(
+        &mut self,
+        gamedata: &[u8],
+        maps: Vec<(MapId, u64, Vec<u8>)>,
+    ) -> Result<(), Error> {
+        use filebox::WithId;
+
+        let mut converted: GameData = migration::read_gamedata(gamedata, SAVE_FORMAT_VERSION)?;
+        for (mid, id, data) in maps {
+            let map = Map::read(&data[..])?;
+            *converted.region.get_boxed_map_mut(mid) = BoxedMap::new(id, map);
+        }
+        converted.meta = std::mem::take(&mut self.meta);
+        *self = converted;
+        Ok(())
+    }
+
+    /// Metadata written to saves, which records the current paks and rules
+    pub(crate) fn meta_to_save(&self) -> MetaData {
+        let mut meta = self.meta.clone();
+        meta.set_data_fingerprints(crate::fingerprint::current());
+        meta
+    }
+
+    pub fn clean_map_dir<P: AsRef<Path>>(&self, path: P) -> Result<(), Error> {
+        let map_dir = path.as_ref().join("maps");
+
+        let mut map_files: Vec<PathBuf> = Vec::new();
+        for entry in fs::read_dir(&map_dir)? {
+            let entry = entry?;
+            let path = entry.path();
+            if path.is_file() {
+                map_files.push(path);
+            }
+        }
+
+        let mut map_file_path: Vec<PathBuf> = Vec::new();
+        self.region.visit_all_maps(|_mid, map| {
+            map_file_path.push(map.path(map_dir.clone()));
+        });
+
+        let n_exist_file = map_files.len();
+        map_files.retain(|p| !map_file_path.iter().any(|a| a == p));
+
+        if n_exist_file == map_files.len() {
+            return Ok(());
+        }
+
+        for p in &map_files {
+            trace!("Remove unused map file {}", p.to_string_lossy());
+            fs::remove_file(p)?;
+        }
+
+        Ok(())
+    }
+
+    pub fn save_dir<P: AsRef<Path>>(&self, path: P) -> PathBuf {
+        path.as_ref()
+            .join(format!("{}.{}", self.meta.save_name(), SAVE_EXTENSION))
+    }
+}
+
+/// Read metadata file in the save directory
+pub fn read_metadata<P: AsRef<Path>>(save_dir: P) -> Result<MetaData, Error> {
+    let file = BufReader::new(File::open(save_dir.as_ref().join("metadata"))?);
+    Ok(serde_json::from_reader(file)?)
+}
+
+/// Get backup directories of the given save directory, sorted from the newest one
+pub fn backup_dirs<P: AsRef<Path>>(save_dir: P) -> Vec<PathBuf> {
+    let save_dir = save_dir.as_ref();
+    (1..)
+        .map(|n| backup_path(save_dir, n))
+        .take_while(|path| path.exists())
+        .collect()
+}
+
+/// Get the staging directory of the given save directory
+pub fn staging_dir<P: AsRef<Path>>(save_dir: P) -> PathBuf {
+    sibling_path(save_dir.as_ref(), "tmp")
+}
+
+/// Replace the save directory by the staging directory whose files are all written.
+/// The previous saves are kept as backups up to `n_backups` generations.
+pub fn replace_save<P: AsRef<Path>, Q: AsRef<Path>>(
+    staging_dir: P,
+    save_dir: Q,
+    n_backups: u32,
+) -> Result<(), Error> {
+    let save_dir = save_dir.as_ref();
+    rotate_backups(save_dir, n_backups)?;
+    fs::rename(staging_dir.as_ref(), save_dir)?;
+    remove_old_backups(save_dir, n_backups)?;
+    Ok(())
+}
+
+/// Get the autosave directory of the given save directory
+pub fn autosave_dir<P: AsRef<Path>>(save_dir: P) -> PathBuf {
+    sibling_path(save_dir.as_ref(), "auto")
+}
+
+/// Directory for maps unloaded from memory before saving
+pub(crate) fn swap_map_dir(map_dir: &Path) -> PathBuf {
+    let save_dir = map_dir.parent().unwrap_or(map_dir);
+    sibling_path(save_dir, "swap").join("maps")
+}
+
+fn backup_path(save_dir: &Path, n: u32) -> PathBuf {
+    sibling_path(save_dir, &format!("bak{n}"))
+}
+
+/// Append an extension to the save directory path, e.g. "foo.rrsve" to "foo.rrsve.tmp"
+fn sibling_path(save_dir: &Path, extension: &str) -> PathBuf {
+    let mut path = save_dir.as_os_str().to_owned();
+    path.push(".");
+    path.push(extension);
+    path.into()
+}
+
+/// Shift backups by one generation, and move the current save to the newest backup
+fn rotate_backups(save_dir: &Path, n_backups: u32) -> Result<(), Error> {
+    if !save_dir.exists() {
+        return Ok(());
+    }
+    // At least one backup is needed temporarily to swap directories
+    let n_backups = n_backups.max(1);
+
+    let oldest = backup_path(save_dir, n_backups);
+    if oldest.exists() {
+        fs::remove_dir_all(&oldest)?;
+    }
+    for n in (1..n_backups).rev() {
+        let path = backup_path(save_dir, n);
+        if path.exists() {
+            fs::rename(&path, backup_path(save_dir, n + 1))?;
+        }
+    }
+    fs::rename(save_dir, backup_path(save_dir, 1))?;
+    Ok(())
+}
+
+/// Remove backups exceeding the given number of generations
+fn remove_old_backups(save_dir: &Path, n_backups: u32) -> Result<(), Error> {
+    let mut n = n_backups + 1;
+    loop {
+        let path = backup_path(save_dir, n);
+        if !path.exists() {
+            return Ok(());
+        }
+        trace!("Remove old backup {}", path.display());
+        fs::remove_dir_all(&path)?;
+        n += 1;
+    }
+}
+
+/// Replace the save directory by the copy of the given backup.
+/// The replaced save is renamed to "<save>.broken" to keep it recoverable.
+fn restore_backup(backup_dir: &Path, save_dir: &Path) -> Result<(), Error> {
+    if save_dir.exists() {
+        let broken_dir = sibling_path(save_dir, "broken");
+        if broken_dir.exists() {
+            fs::remove_dir_all(&broken_dir)?;
+        }
+        fs::rename(save_dir, &broken_dir)?;
+        warn!("The broken save is moved to {}", broken_dir.display());
+    }
+    copy_save_files(backup_dir, save_dir)
+}
+
+/// Copy all files in the save directory to a new directory
+fn copy_save_files(src: &Path, dest: &Path) -> Result<(), Error> {
+    create_dir_all(dest.join("maps"))?;
+    for dir in ["", "maps"] {
+        for entry in fs::read_dir(src.join(dir))? {
+            let path = entry?.path();
+            if path.is_file() {
+                fs::copy(&path, dest.join(dir).join(path.file_name().unwrap()))?;
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Find an unused save name and directory to import the autosave of the given save
+fn autosave_import_dir(save_dir: &Path) -> (String, PathBuf) {
+    let stem = save_dir.file_stem().unwrap_or_default().to_string_lossy();
+    let parent = save_dir.parent().unwrap_or_else(|| Path::new(""));
+    (1..)
+        .map(|n| {
+            let name = if n == 1 {
+                format!("{}-autosave", stem)
+            } else {
+                format!("{}-autosave{}", stem, n)
+            };
+            let dir = parent.join(format!("{}.{}", name, SAVE_EXTENSION));
+            (name, dir)
+        })
+        .find(|(_, dir)| !dir.exists() && !sibling_path(dir, "auto").exists())
+        .unwrap()
+}
+
+/// Move maps unloaded during loading from another directory, and discard the old ones
+fn move_swap_dir(from: &Path, save_dir: &Path) -> Result<(), Error> {
+    let swap_dir = sibling_path(save_dir, "swap");
+    if swap_dir.exists() {
+        fs::remove_dir_all(&swap_dir)?;
+    }
+    let from = sibling_path(from, "swap");
+    if from.exists() {
+        fs::rename(&from, &swap_dir)?;
+    }
+    Ok(())
+}
+
+/// Create a hard link to the file, or copy it if linking is not available
+fn link_or_copy(src: &Path, dest: &Path) -> Result<(), std::io::Error> {
+    if fs::hard_link(src, dest).is_err() {
+        fs::copy(src, dest)?;
+    }
+    Ok(())
+}
+
+/// Flush the buffer and wait until the file is written to the disk
+pub fn sync_file(file: BufWriter<File>) -> Result<(), std::io::Error> {
+    let file = file.into_inner().map_err(|e| e.into_error())?;
+    file.sync_all()
+}
+
+/// Print save data size
+#[cfg(debug_assertions)]
+fn print_save_data_size(gd: &GameData) {
+    use serde_cbor::ser::to_vec_packed;
+    let v = to_vec_packed(&gd).unwrap();
+    println!("Total size = {}", v.len());
+    let v = to_vec_packed(&gd.region.0).unwrap();
+    println!("Regions size = {}", v.len());
+    let v = to_vec_packed(&gd.region.get(RegionId::default())).unwrap();
+    println!("Region size = {}", v.len());
+    let map = gd.get_current_map();
+    let v = to_vec_packed(&map).unwrap();
+    println!("Current map size = {}", v.len());
+    let v = to_vec_packed(&map.tile).unwrap();
+    println!("Current map tiles size = {}", v.len());
+}
+
+#[cfg(not(debug_assertions))]
+fn print_save_data_size(_gd: &GameData) {}
+
+/// Create an empty directory for tests
+#[cfg(test)]
+pub(crate) fn test_dir(name: &str) -> PathBuf {
+    let dir =
+        std::env::temp_dir().join(format!("rusted-ruins-test-{}-{}", name, std::process::id()));
+    if dir.exists() {
+        fs::remove_dir_all(&dir).unwrap();
+    }
+    create_dir_all(&dir).unwrap();
+    dir
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn restore_backup_keeps_broken_save() {
+        let root = test_dir("restore-backup");
+        let save_dir = root.join("a.rrsve");
+        let backup_dir = backup_path(&save_dir, 1);
+        create_dir_all(save_dir.join("maps")).unwrap();
+        create_dir_all(backup_dir.join("maps")).unwrap();
+        fs::write(save_dir.join("gamedata"), "broken").unwrap();
+        fs::write(backup_dir.join("gamedata"), "backup").unwrap();
+        fs::write(backup_dir.join("maps/0000000000000001"), "map").unwrap();
+
+        restore_backup(&backup_dir, &save_dir).unwrap();
+        assert_eq!(fs::read(save_dir.join("gamedata")).unwrap(), b"backup");
+        assert_eq!(
+            fs::read(save_dir.join("maps/0000000000000001")).unwrap(),
+            b"map"
+        );
+        let broken_dir = sibling_path(&save_dir, "broken");
+        assert_eq!(fs::read(broken_dir.join("gamedata")).unwrap(), b"broken");
+
+        fs::remove_dir_all(&root).unwrap();
+    }
+
+    /// Initialize the global object holder without objects
+    #[cfg(feature = "global_state_obj")]
+    pub(crate) fn init_objholder() {
+        static INIT: std::sync::Once = std::sync::Once::new();
+        INIT.call_once(|| crate::gobj::init(vec![test_dir("pak")]));
+    }
+
+    #[cfg(feature = "global_state_obj")]
+    #[test]
+    fn unload_and_save_maps() {
+        init_objholder();
+        let root = test_dir("unload-maps");
+        let save_dir = root.join("a.rrsve");
+        let map_dir = save_dir.join("maps");
+
+        let mut gd = GameData::empty();
+        let region = Region::new("region", Map::new(4, 4, Time::default()), 1);
+        let rid = gd.region.add_region(region);
+        let sid = gd
+            .add_site(Site::new(2, None), SiteKind::Other, rid, None)
+            .unwrap();
+        let mid0 = gd.add_map(Map::new(4, 4, Time::default()), sid, 2);
+        let mid1 = gd.add_map(Map::new(4, 4, Time::default()), sid, 3);
+        gd.set_initial_mapid(MapId::RegionMap { rid });
+        gd.region.mark_map_used(mid0);
+        gd.region.mark_map_used(mid1);
+        gd.save(&save_dir, 0).unwrap();
+
+        // Change the least recently used map, and unload it
+        gd.region.get_map_mut(mid0).music = "changed".into();
+        gd.region.mark_map_used(mid1);
+        gd.region
+            .unload_old_maps(&map_dir, 2, gd.get_current_mapid())
+            .unwrap();
+        let swap_dir = swap_map_dir(&map_dir);
+        assert!(!gd.region.get_boxed_map_mut(mid0).is_loaded());
+        assert!(gd.region.get_boxed_map_mut(mid1).is_loaded());
+        assert!(gd.region.get_boxed_map_mut(mid0).path(&swap_dir).exists());
+
+        // The unloaded map is loaded from the swap directory
+        gd.region.preload_map(mid0, &map_dir);
+        assert_eq!(gd.region.get_map(mid0).music, "changed");
+        gd.region.mark_map_used(mid1);
+        gd.region
+            .unload_old_maps(&map_dir, 2, gd.get_current_mapid())
+            .unwrap();
+        assert!(!gd.region.get_boxed_map_mut(mid0).is_loaded());
+
+        // Swapped maps are merged to the new save
+        gd.save(&save_dir, 0).unwrap();
+        assert!(!sibling_path(&save_dir, "swap").exists());
+        let mut gd = GameData::load(&save_dir).unwrap();
+        gd.region.preload_map(mid0, &map_dir);
+        assert_eq!(gd.region.get_map(mid0).music, "changed");
+
+        fs::remove_dir_all(&root).unwrap();
+    }
+
+    /// Read all files in the save directory
+    #[cfg(feature = "global_state_obj")]
+    fn read_save_files(save_dir: &Path) -> Vec<(PathBuf, Vec<u8>)> {
+        let mut files = Vec::new();
+        for dir in ["", "maps"] {
+            for entry in fs::read_dir(save_dir.join(dir)).unwrap() {
+                let path = entry.unwrap().path();
+                if path.is_file() {
+                    let data = fs::read(&path).unwrap();
+                    files.push((path, data));
+                }
+            }
+        }
+        files.sort();
+        files
+    }
+
+    #[cfg(feature = "global_state_obj")]
+    #[test]
+    fn load_autosave_keeps_save() {
+        fn assert_send<T: Send>(_: &T) {}
+
+        init_objholder();
+        let root = test_dir("load-autosave");
+        let save_dir = root.join("a.rrsve");
+
+        let mut gd = GameData::empty();
+        gd.meta.set_save_name("a");
+        let region = Region::new("region", Map::new(4, 4, Time::default()), 1);
+        let rid = gd.region.add_region(region);
+        gd.set_initial_mapid(MapId::RegionMap { rid });
+        gd.save(&save_dir, 0).unwrap();
+        let save_files = read_save_files(&save_dir);
+
+        gd.region.get_map_mut(MapId::RegionMap { rid }).music = "changed".into();
+        let snapshot = gd.snapshot(&save_dir).unwrap();
+        assert_send(&snapshot);
+        snapshot.write(autosave_dir(&save_dir)).unwrap();
+
+        let gd = GameData::load_autosave(&save_dir).unwrap();
+        assert_eq!(gd.meta.save_name(), "a-autosave");
+        assert_eq!(gd.get_current_map().music, "changed");
+        let new_dir = gd.save_dir(&root);
+        assert_eq!(read_metadata(&new_dir).unwrap().save_name(), "a-autosave");
+
+        // The manual save is not changed
+        assert_eq!(read_save_files(&save_dir), save_files);
+        assert!(!backup_path(&save_dir, 1).exists());
+        assert!(!sibling_path(&save_dir, "broken").exists());
+
+        // Loading again does not overwrite the imported save
+        let gd = GameData::load_autosave(&save_dir).unwrap();
+        assert_eq!(gd.meta.save_name(), "a-autosave2");
+        assert!(new_dir.exists());
+
+        fs::remove_dir_all(&root).unwrap();
+    }
+}
@@ -0,0 +1,110 @@
+//! Snapshot of game data, which can be serialized and written in another thread.
+
+use super::thumbnail::Thumbnail;
+use super::{remove_old_backups, rotate_backups, sibling_path, swap_map_dir, sync_file};
+use crate::gamedata::*;
+use anyhow::Error;
+use filebox::FileBox;
+use std::fs::{self, create_dir_all, File};
+use std::io::{BufWriter, Write};
+use std::path::{Path, PathBuf};
+
+/// Copy of game data including all loaded maps.
+/// Serializing is deferred to `write()`, so it does not block the main thread.
+pub struct SaveSnapshot {
+    idtable: Vec<u8>,
+    metadata: Vec<u8>,
+    gamedata: GameData,
+    thumbnail: Thumbnail,
+    map_dir: PathBuf,
+}
+
+#[cfg(feature = "global_state_obj")]
+impl GameData {
+    /// Take a snapshot of game data. Unloaded maps are taken from `save_dir` when writing.
+    /// Changed flags of maps are not cleared, so `save()` to `save_dir` is not affected.
+    pub fn snapshot<P: AsRef<Path>>(&self, save_dir: P) -> Result<SaveSnapshot, Error> {
+        let mut idtable = Vec::new();
+        writeln!(idtable, "{:016x}", *crate::gobj::OBJ_HOLDER_HASH)?;
+        crate::gobj::get_objholder().write_table(&mut idtable)?;
+
+        let metadata = serde_json::to_vec_pretty(&self.meta_to_save())?;
+
+        Ok(SaveSnapshot {
+            idtable,
+            metadata,
+            gamedata: self.clone(),
+            thumbnail: self.thumbnail(),
+            map_dir: save_dir.as_ref().join("maps"),
+        })
+    }
+}
+
+impl SaveSnapshot {
+    /// Write the snapshot to the given directory, replacing the previous data in it
+    pub fn write<P: AsRef<Path>>(&self, dir: P) -> Result<(), Error> {
+        let dir = dir.as_ref();
+        let staging_dir = sibling_path(dir, "tmp");
+
+        if staging_dir.exists() {
+            fs::remove_dir_all(&staging_dir)?;
+        }
+        if let Err(e) = self.write_files(&staging_dir) {
+            let _ = fs::remove_dir_all(&staging_dir);
+            return Err(e);
+        }
+
+        rotate_backups(dir, 0)?;
+        fs::rename(&staging_dir, dir)?;
+        remove_old_backups(dir, 0)?;
+        Ok(())
+    }
+
+    fn write_files(&self, dir: &Path) -> Result<(), Error> {
+        let map_dir = dir.join("maps");
+        create_dir_all(&map_dir)?;
+
+        let mut gamedata = Vec::new();
+        crate::utils::to_writer_with_mode(&mut gamedata, &self.gamedata)?;
+
+        for (name, data) in [
+            ("idtable", &self.idtable),
+            ("metadata", &self.metadata),
+            ("gamedata", &gamedata),
+        ] {
+            let mut file = BufWriter::new(File::create(dir.join(name))?);
+            file.write_all(data)?;
+            sync_file(file)?;
+        }
+        match self.thumbnail.to_png() {
+            Ok(data) => fs::write(dir.join(super::thumbnail::THUMBNAIL_FILE), data)?,
+            Err(e) => warn!("cannot create thumbnail: {}", e),
+        }
+
+        let mut result = Ok(());
+        self.gamedata.region.visit_all_maps(|_, map| {
+            if result.is_ok() {
+                result = write_map(map, &map_dir, &self.map_dir);
+            }
+        });
+        result
+    }
+}
+
+/// Write a loaded map, or copy an unloaded map from `src`
+fn write_map(map: &FileBox<Map>, map_dir: &Path, src: &Path) -> Result<(), Error> {
+    let name = format!("{:016x}", map.id());
+    if let Some(data) = map.to_bytes()? {
+        filebox::write_bytes(map_dir.join(&name), &data)?;
+    } else {
+        // Maps unloaded after the last saving are in the swap directory
+        let swap_path = swap_map_dir(src).join(&name);
+        let src_path = if swap_path.exists() {
+            swap_path
+        } else {
+            src.join(&name)
+        };
+        fs::copy(&src_path, map_dir.join(&name))?;
+    }
+    Ok(())
+}
@@ -0,0 +1,93 @@
+//! Thumbnail image of the current map, written to saves.
+
+use crate::gamedata::*;
+use anyhow::Error;
+use geom::Coords;
+
+/// File name of thumbnails in save directories
+pub const THUMBNAIL_FILE: &str = "thumbnail.png";
+/// Maximum width and height of thumbnails
+pub const THUMBNAIL_SIZE: u32 = 128;
+
+/// RGB image rendered from symbol colors of tiles and walls
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct Thumbnail {
+    pub w: u32,
+    pub h: u32,
+    pub pixels: Vec<u8>,
+}
+
+impl Thumbnail {
+    /// Render a thumbnail from the given color function.
+    /// The map is scaled to fit in `THUMBNAIL_SIZE` by nearest neighbor.
+    pub fn render<F>(map_size: (u32, u32), mut color: F) -> Thumbnail
+    where
+        F: FnMut(Coords) -> (u8, u8, u8),
+    {
+        let max_size = std::cmp::max(map_size.0, map_size.1).max(1);
+        let w = (map_size.0 * THUMBNAIL_SIZE / max_size).max(1);
+        let h = (map_size.1 * THUMBNAIL_SIZE / max_size).max(1);
+
+        let mut pixels = Vec::with_capacity((w * h * 3) as usize);
+        for y in 0..h {
+            for x in 0..w {
+                let p = Coords::new(
+                    (x * max_size / THUMBNAIL_SIZE) as i32,
+                    (y * max_size / THUMBNAIL_SIZE) as i32,
+                );
+                let c = color(p);
+                pixels.extend_from_slice(&[c.0, c.1, c.2]);
+            }
+        }
+
+        Thumbnail { w, h, pixels }
+    }
+
+    pub fn to_png(&self) -> Result<Vec<u8>, Error> {
+        let mut data = Vec::new();
+        let mut encoder = png::Encoder::new(&mut data, self.w, self.h);
+        encoder.set_color(png::ColorType::Rgb);
+        encoder.set_depth(png::BitDepth::Eight);
+        let mut writer = encoder.write_header()?;
+        writer.write_image_data(&self.pixels)?;
+        writer.finish()?;
+        Ok(data)
+    }
+}
+
+#[cfg(feature = "global_state_obj")]
+impl GameData {
+    /// Render a thumbnail of the current map. Unobserved tiles are black like the minimap.
+    pub fn thumbnail(&self) -> Thumbnail {
+        use crate::gobj;
+
+        let map = self.get_current_map();
+        Thumbnail::render(map.size(), |p| {
+            if let Some(wall_idx) = map.observed_tile[p].wall.idx() {
+                gobj::get_obj(wall_idx).symbol_color
+            } else if map.observed_tile[p].tile {
+                gobj::get_obj(map.tile[p].main_tile()).symbol_color
+            } else {
+                (0, 0, 0)
+            }
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn render_thumbnail() {
+        let t = Thumbnail::render((256, 64), |p| (p.0 as u8, p.1 as u8, 0));
+        assert_eq!((t.w, t.h), (128, 32));
+        assert_eq!(&t.pixels[3..6], &[2, 0, 0]);
+
+        let t = Thumbnail::render((16, 16), |_| (1, 2, 3));
+        assert_eq!((t.w, t.h), (128, 128));
+        assert_eq!(t.pixels.len(), 128 * 128 * 3);
+
+        assert!(t.to_png().unwrap().starts_with(b"\x89PNG"));
+    }
+}
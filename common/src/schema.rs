@@ -0,0 +1,66 @@
+//! Helpers to generate JSON Schema of content files.
+
+use schemars::gen::SchemaSettings;
+use schemars::schema::{RootSchema, Schema, SchemaObject};
+use schemars::visit::{self, Visitor};
+use schemars::JsonSchema;
+
+/// Generate a schema for content files.
+/// Unknown fields of structs are not allowed to find typos, even if serde ignores them.
+pub fn root_schema_for<T: JsonSchema>() -> RootSchema {
+    SchemaSettings::draft07()
+        .with_visitor(DenyUnknownFields)
+        .into_generator()
+        .into_root_schema_for::<T>()
+}
+
+#[derive(Clone, Debug)]
+struct DenyUnknownFields;
+
+impl Visitor for DenyUnknownFields {
+    fn visit_schema_object(&mut self, schema: &mut SchemaObject) {
+        if let Some(object) = schema.object.as_mut() {
+            if !object.properties.is_empty() && object.additional_properties.is_none() {
+                object.additional_properties = Some(Box::new(Schema::Bool(false)));
+            }
+        }
+        visit::visit_schema_object(self, schema);
+    }
+}
+
+/// Schema of `geom::ShapeKind`
+#[allow(dead_code)]
+#[derive(JsonSchema)]
+#[schemars(rename = "ShapeKind")]
+pub enum ShapeKindSchema {
+    OneTile,
+    Line,
+    Circle,
+}
+
+/// Create a schema from a JSON value written by hand
+pub(crate) fn from_json(value: serde_json::Value) -> Schema {
+    serde_json::from_value(value).expect("invalid schema")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[allow(dead_code)]
+    #[derive(JsonSchema)]
+    struct Sample {
+        a: u32,
+        map: std::collections::HashMap<String, u32>,
+    }
+
+    #[test]
+    fn deny_unknown_fields() {
+        let schema = serde_json::to_value(root_schema_for::<Sample>()).unwrap();
+        assert_eq!(schema["additionalProperties"], false);
+        assert_eq!(
+            schema["properties"]["map"]["additionalProperties"]["type"],
+            "integer"
+        );
+    }
+}
@@ -6,6 +6,7 @@ use crate::gamedata::Reward;
 use crate::hashmap::HashMap;
 use crate::item_selector::ItemSelector;
 use geom::Coords;
+use schemars::JsonSchema;
 
 /// Hold data for site generation
 #[derive(Clone, Serialize, Deserialize)]
@@ -24,10 +25,11 @@ pub struct SiteGenObject {
 }
 
 /// Data to generate a unique citizen
-#[derive(Clone, PartialEq, Eq, Debug, Serialize, Deserialize)]
+#[derive(Clone, PartialEq, Eq, Debug, Serialize, Deserialize, JsonSchema)]
 pub struct NpcGenData {
     /// Unique id in this site
     pub id: NpcGenId,
+    #[schemars(with = "(i32, i32)")]
     pub pos: Coords,
     pub floor: u32,
     #[serde(default)]
@@ -37,14 +39,14 @@ pub struct NpcGenData {
     pub talk_script: String,
 }
 
-#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug, Serialize, Deserialize)]
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug, Serialize, Deserialize, JsonSchema)]
 pub enum NpcGenId {
     Site(u32),
     Unique(ArrayStringId),
 }
 
 /// Data to generate a shop on the site
-#[derive(Clone, PartialEq, Eq, Debug, Serialize, Deserialize)]
+#[derive(Clone, PartialEq, Eq, Debug, Serialize, Deserialize, JsonSchema)]
 pub struct ShopGenData {
     #[serde(default)]
     pub shop_kind: String,
@@ -52,7 +54,7 @@ pub struct ShopGenData {
     pub item_selector: ItemSelector,
 }
 
-#[derive(Clone, PartialEq, Debug, Serialize, Deserialize)]
+#[derive(Clone, PartialEq, Debug, Serialize, Deserialize, JsonSchema)]
 pub enum QuestGenData {
     ItemDelivering {
         #[serde(default = "quest_gen_data_default_weight")]
@@ -0,0 +1,257 @@
+//! File header and compression codecs.
+//!
+//! A file starts with the header below, followed by the compressed data.
+//!
+//! | size | content                          |
+//! |------|----------------------------------|
+//! | 4    | magic "RRFB"                     |
+//! | 1    | header version                   |
+//! | 1    | codec                            |
+//! | 2    | reserved                         |
+//! | 8    | uncompressed length (LE)         |
+//! | 4    | CRC32 of uncompressed data (LE)  |
+//!
+//! Files without the magic are read as headerless gzip, written by older versions.
+
+use flate2::read::GzDecoder;
+use flate2::write::GzEncoder;
+use flate2::Compression;
+use std::io::{Read, Write};
+use std::sync::atomic::{AtomicU8, Ordering};
+
+const MAGIC: &[u8; 4] = b"RRFB";
+const HEADER_VERSION: u8 = 1;
+const HEADER_SIZE: usize = 20;
+const GZIP_MAGIC: &[u8; 2] = &[0x1f, 0x8b];
+
+/// Compression codec for files
+#[derive(Clone, Copy, PartialEq, Eq, Default, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Codec {
+    None,
+    #[default]
+    Gzip,
+    Zstd,
+}
+
+impl Codec {
+    fn from_u8(n: u8) -> Option<Codec> {
+        match n {
+            0 => Some(Codec::None),
+            1 => Some(Codec::Gzip),
+            2 => Some(Codec::Zstd),
+            _ => None,
+        }
+    }
+
+    /// Returns false if this codec is disabled by features of this build
+    pub fn is_available(self) -> bool {
+        self != Codec::Zstd || cfg!(feature = "zstd")
+    }
+
+    fn as_u8(self) -> u8 {
+        match self {
+            Codec::None => 0,
+            Codec::Gzip => 1,
+            Codec::Zstd => 2,
+        }
+    }
+}
+
+/// Errors for broken or unreadable files
+#[derive(Debug, thiserror::Error)]
+pub enum FileBoxError {
+    #[error("unknown file format")]
+    UnknownFormat,
+    #[error("unsupported header version {0}")]
+    UnsupportedVersion(u8),
+    #[error("unknown codec {0}")]
+    UnknownCodec(u8),
+    #[error("codec {0:?} is not available in this build")]
+    UnavailableCodec(Codec),
+    #[error("truncated data")]
+    Truncated,
+    #[error("decompression failed: {0}")]
+    Decompress(std::io::Error),
+    #[error("length mismatch (expected {expected}, found {found})")]
+    LengthMismatch { expected: u64, found: u64 },
+    #[error("checksum mismatch (expected {expected:08x}, found {found:08x})")]
+    ChecksumMismatch { expected: u32, found: u32 },
+}
+
+static CODEC: AtomicU8 = AtomicU8::new(1);
+
+/// Set the codec used for writing files. Files are read by the codec recorded in their header.
+/// Gzip is used instead if the given codec is not available in this build.
+pub fn set_codec(codec: Codec) {
+    let codec = if codec.is_available() {
+        codec
+    } else {
+        log::warn!(
+            "codec {:?} is not available in this build, gzip is used instead",
+            codec
+        );
+        Codec::Gzip
+    };
+    CODEC.store(codec.as_u8(), Ordering::Relaxed);
+}
+
+pub fn codec() -> Codec {
+    Codec::from_u8(CODEC.load(Ordering::Relaxed)).unwrap()
+}
+
+/// Write the header and compressed data
+pub(crate) fn encode<W: Write>(mut w: W, data: &[u8], codec: Codec) -> Result<W, std::io::Error> {
+    let mut header = [0u8; HEADER_SIZE];
+    header[0..4].copy_from_slice(MAGIC);
+    header[4] = HEADER_VERSION;
+    header[5] = codec.as_u8();
+    header[8..16].copy_from_slice(&(data.len() as u64).to_le_bytes());
+    header[16..20].copy_from_slice(&crc32fast::hash(data).to_le_bytes());
+    w.write_all(&header)?;
+
+    match codec {
+        Codec::None => {
+            w.write_all(data)?;
+            Ok(w)
+        }
+        Codec::Gzip => {
+            let mut e = GzEncoder::new(w, Compression::fast());
+            e.write_all(data)?;
+            e.finish()
+        }
+        #[cfg(feature = "zstd")]
+        Codec::Zstd => {
+            let mut e = zstd::Encoder::new(w, zstd::DEFAULT_COMPRESSION_LEVEL)?;
+            e.write_all(data)?;
+            e.finish()
+        }
+        #[cfg(not(feature = "zstd"))]
+        Codec::Zstd => Err(std::io::Error::new(
+            std::io::ErrorKind::Unsupported,
+            FileBoxError::UnavailableCodec(codec),
+        )),
+    }
+}
+
+/// Read the whole file, and verify its length and checksum
+pub(crate) fn decode(bytes: &[u8]) -> Result<Vec<u8>, FileBoxError> {
+    if bytes.starts_with(GZIP_MAGIC) {
+        return decompress(bytes, Codec::Gzip, None);
+    }
+
+    if bytes.len() < HEADER_SIZE {
+        return Err(if bytes.starts_with(MAGIC) {
+            FileBoxError::Truncated
+        } else {
+            FileBoxError::UnknownFormat
+        });
+    }
+    let (header, body) = bytes.split_at(HEADER_SIZE);
+    if &header[0..4] != MAGIC {
+        return Err(FileBoxError::UnknownFormat);
+    }
+    if header[4] != HEADER_VERSION {
+        return Err(FileBoxError::UnsupportedVersion(header[4]));
+    }
+    let codec = Codec::from_u8(header[5]).ok_or(FileBoxError::UnknownCodec(header[5]))?;
+    let len = u64::from_le_bytes(header[8..16].try_into().unwrap());
+    let checksum = u32::from_le_bytes(header[16..20].try_into().unwrap());
+
+    let data = decompress(body, codec, Some(len))?;
+    if data.len() as u64 != len {
+        return Err(FileBoxError::LengthMismatch {
+            expected: len,
+            found: data.len() as u64,
+        });
+    }
+    let found = crc32fast::hash(&data);
+    if found != checksum {
+        return Err(FileBoxError::ChecksumMismatch {
+            expected: checksum,
+            found,
+        });
+    }
+    Ok(data)
+}
+
+fn decompress(body: &[u8], codec: Codec, len: Option<u64>) -> Result<Vec<u8>, FileBoxError> {
+    // Do not trust the length for allocation, it may be broken
+    let mut data = Vec::with_capacity(len.unwrap_or(0).min(1 << 24) as usize);
+    // Read one byte more than expected to detect trailing garbage
+    let limit = len.map(|len| len + 1).unwrap_or(u64::MAX);
+    let result = match codec {
+        Codec::None => {
+            data.extend_from_slice(&body[..body.len().min(limit as usize)]);
+            Ok(data.len())
+        }
+        Codec::Gzip => GzDecoder::new(body).take(limit).read_to_end(&mut data),
+        #[cfg(feature = "zstd")]
+        Codec::Zstd => zstd::Decoder::new(body)
+            .map_err(FileBoxError::Decompress)?
+            .take(limit)
+            .read_to_end(&mut data),
+        #[cfg(not(feature = "zstd"))]
+        Codec::Zstd => return Err(FileBoxError::UnavailableCodec(codec)),
+    };
+    match result {
+        Ok(_) => Ok(data),
+        Err(e) if e.kind() == std::io::ErrorKind::UnexpectedEof => Err(FileBoxError::Truncated),
+        Err(e) => Err(FileBoxError::Decompress(e)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DATA: &[u8] = b"The quick brown fox jumps over the lazy dog";
+
+    #[test]
+    fn round_trip() {
+        for codec in [Codec::None, Codec::Gzip] {
+            let bytes = encode(Vec::new(), DATA, codec).unwrap();
+            assert_eq!(decode(&bytes).unwrap(), DATA);
+        }
+    }
+
+    #[test]
+    fn unavailable_codec() {
+        set_codec(Codec::Zstd);
+        if cfg!(feature = "zstd") {
+            assert_eq!(codec(), Codec::Zstd);
+        } else {
+            assert_eq!(codec(), Codec::Gzip);
+        }
+        set_codec(Codec::Gzip);
+    }
+
+    #[test]
+    fn legacy_gzip() {
+        let mut e = GzEncoder::new(Vec::new(), Compression::fast());
+        e.write_all(DATA).unwrap();
+        let bytes = e.finish().unwrap();
+        assert_eq!(decode(&bytes).unwrap(), DATA);
+    }
+
+    #[test]
+    fn detect_corruption() {
+        let mut bytes = encode(Vec::new(), DATA, Codec::None).unwrap();
+        bytes[HEADER_SIZE + 3] ^= 0xff;
+        assert!(matches!(
+            decode(&bytes),
+            Err(FileBoxError::ChecksumMismatch { .. })
+        ));
+
+        let bytes = encode(Vec::new(), DATA, Codec::Gzip).unwrap();
+        assert!(matches!(
+            decode(&bytes[..bytes.len() - 8]),
+            Err(FileBoxError::Truncated | FileBoxError::Decompress(_))
+        ));
+
+        assert!(matches!(
+            decode(b"broken"),
+            Err(FileBoxError::UnknownFormat)
+        ));
+    }
+}
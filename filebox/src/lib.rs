@@ -7,24 +7,28 @@
     nonstandard_style
 )]
 
+#[macro_use]
+extern crate serde_derive;
+
+mod codec;
 mod ser;
 
-use flate2::bufread::GzDecoder;
-use flate2::write::GzEncoder;
-use flate2::Compression;
+pub use codec::{codec, set_codec, Codec, FileBoxError};
+
 use std::cell::Cell;
 use std::fmt::Debug;
 use std::fs::File;
-use std::io::{BufReader, BufWriter, Read, Write};
+use std::io::{BufWriter, Read, Write};
 use std::ops::{Deref, DerefMut};
 use std::path::{Path, PathBuf};
 
 pub trait WithId: Sized {
-    type Error: From<std::io::Error>;
+    type Error: From<std::io::Error> + From<FileBoxError>;
     fn write<W: Write>(w: W, a: &Self) -> Result<(), Self::Error>;
     fn read<R: Read>(r: R) -> Result<Self, Self::Error>;
 }
 
+#[derive(Clone)]
 pub struct FileBox<T> {
     id: u64,
     changed: Cell<bool>,
@@ -80,19 +84,39 @@ impl<T: WithId> FileBox<T> {
         self.id
     }
 
+    pub fn is_loaded(&self) -> bool {
+        self.inner.is_some()
+    }
+
+    /// Mark as changed to be written at the next time
+    pub fn mark_changed(&self) {
+        self.changed.set(true);
+    }
+
+    /// Write data to the file. The data is written to a temporary file first,
+    /// and renamed to the actual path after flushing, so the existing file is never broken.
+    /// The file is compressed by the codec given by `set_codec`.
     pub fn write_force<P: AsRef<Path>>(s: &Self, p: P) -> Result<(), T::Error> {
-        if let Some(a) = &s.inner {
-            let mut file = GzEncoder::new(
-                BufWriter::new(File::create(s.path(p))?),
-                Compression::fast(),
-            );
-            T::write(&mut file, a)?;
+        if let Some(data) = s.to_bytes()? {
+            write_bytes(s.path(p), &data)?;
             s.changed.set(false);
         }
 
         Ok(())
     }
 
+    /// Serialize data to bytes without compression. Returns None if not loaded.
+    /// The changed flag is not cleared.
+    pub fn to_bytes(&self) -> Result<Option<Vec<u8>>, T::Error> {
+        if let Some(a) = &self.inner {
+            let mut data = Vec::new();
+            T::write(&mut data, a)?;
+            Ok(Some(data))
+        } else {
+            Ok(None)
+        }
+    }
+
     pub fn write<P: AsRef<Path>>(s: &Self, p: P) -> Result<(), T::Error> {
         if s.changed.get() {
             Self::write_force(s, p)
@@ -102,17 +126,27 @@ impl<T: WithId> FileBox<T> {
         }
     }
 
+    /// Write data if it is changed, and release it from memory
+    pub fn unload<P: AsRef<Path>>(&mut self, p: P) -> Result<(), T::Error> {
+        Self::write(self, p)?;
+        self.inner = None;
+        Ok(())
+    }
+
     pub fn path<P: AsRef<Path>>(&self, p: P) -> PathBuf {
         p.as_ref().join(format!("{:016x}", self.id))
     }
 
+    /// Read data from the file.
+    /// Broken files are detected by the checksum, and reported as `FileBoxError`.
     pub fn read<P: AsRef<Path>>(&mut self, p: P, read_as_changed: bool) -> Result<(), T::Error> {
         if self.inner.is_some() {
             return Ok(());
         }
 
-        let mut file = GzDecoder::new(BufReader::new(File::open(self.path(p))?));
-        self.inner = Some(Box::new(T::read(&mut file)?));
+        let bytes = std::fs::read(self.path(p))?;
+        let data = codec::decode(&bytes)?;
+        self.inner = Some(Box::new(T::read(&data[..])?));
 
         if read_as_changed {
             self.changed.set(true);
@@ -121,3 +155,15 @@ impl<T: WithId> FileBox<T> {
         Ok(())
     }
 }
+
+/// Write serialized data to the file with the header.
+/// The data is written to a temporary file first, and renamed to the given path.
+pub fn write_bytes<P: AsRef<Path>>(path: P, data: &[u8]) -> Result<(), std::io::Error> {
+    let path = path.as_ref();
+    let tmp_path = path.with_extension("tmp");
+    let file = BufWriter::new(File::create(&tmp_path)?);
+    let file = codec::encode(file, data, codec())?;
+    let file = file.into_inner().map_err(|e| e.into_error())?;
+    file.sync_all()?;
+    std::fs::rename(&tmp_path, path)
+}
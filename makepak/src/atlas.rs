@@ -0,0 +1,212 @@
+//! Pack images of objects into atlases.
+//!
+//! Images of the same object type are packed into atlas objects,
+//! and `Img::atlas` of packed images refers to the position in the atlas.
+
+use crate::compile::write_to_vec;
+use anyhow::{anyhow, Result};
+use common::obj::{AtlasObject, AtlasPos, Img, Object};
+use common::pakutil::read_object;
+use image::{imageops, DynamicImage, ImageOutputFormat, RgbaImage};
+use std::collections::{BTreeMap, HashMap};
+use std::io::Cursor;
+
+/// Maximum width and height of atlases
+pub const ATLAS_SIZE: u32 = 2048;
+/// Ids of atlas objects start with this
+pub const ATLAS_ID_PREFIX: &str = "!atlas/";
+
+/// Pack images of serialized objects into atlases.
+/// Images already packed are restored from atlas objects in `objs` before packing.
+pub fn pack_atlases(
+    pak_name: &str,
+    objs: Vec<(String, Vec<u8>)>,
+) -> Result<Vec<(String, Vec<u8>)>> {
+    let objs = objs
+        .iter()
+        .map(|(_, data)| read_object(&data[..]))
+        .collect::<Result<Vec<Object>, _>>()?;
+    let mut objs = unpack_atlases(objs)?;
+
+    // Object type to indices of objects which have an image
+    let mut groups: BTreeMap<&'static str, Vec<usize>> = BTreeMap::new();
+    for (i, obj) in objs.iter().enumerate() {
+        if obj.get_img().is_some() {
+            groups.entry(obj.type_name()).or_default().push(i);
+        }
+    }
+
+    let mut atlases = Vec::new();
+    for (ty, indices) in groups {
+        // No need to pack a single image
+        if indices.len() < 2 {
+            continue;
+        }
+        let images = indices
+            .iter()
+            .map(|&i| Ok(image::load_from_memory(&objs[i].get_img().unwrap().data)?.to_rgba8()))
+            .collect::<Result<Vec<RgbaImage>>>()?;
+        let sizes: Vec<(u32, u32)> = images.iter().map(|image| image.dimensions()).collect();
+        let placements = shelf_pack(&sizes, ATLAS_SIZE);
+
+        // Calculate the size of each atlas
+        let mut bin_sizes: Vec<(u32, u32)> = Vec::new();
+        for (&(w, h), placement) in sizes.iter().zip(&placements) {
+            if let Some((bin, x, y)) = *placement {
+                if bin_sizes.len() <= bin {
+                    bin_sizes.resize(bin + 1, (0, 0));
+                }
+                bin_sizes[bin].0 = bin_sizes[bin].0.max(x + w);
+                bin_sizes[bin].1 = bin_sizes[bin].1.max(y + h);
+            }
+        }
+        let mut bins: Vec<RgbaImage> = bin_sizes
+            .iter()
+            .map(|&(w, h)| RgbaImage::new(w, h))
+            .collect();
+
+        for ((&i, image), placement) in indices.iter().zip(&images).zip(&placements) {
+            if let Some((bin, x, y)) = *placement {
+                imageops::replace(&mut bins[bin], image, x.into(), y.into());
+                let img = objs[i].get_img_mut().unwrap();
+                img.data = Vec::new();
+                img.atlas = Some(AtlasPos {
+                    id: atlas_id(pak_name, ty, bin),
+                    x,
+                    y,
+                });
+            }
+        }
+
+        for (bin, image) in bins.into_iter().enumerate() {
+            atlases.push(Object::Atlas(AtlasObject {
+                id: atlas_id(pak_name, ty, bin),
+                data: encode_png(image)?,
+            }));
+        }
+    }
+    objs.extend(atlases);
+
+    objs.iter()
+        .map(|obj| Ok((obj.get_id().to_owned(), write_to_vec(obj)?)))
+        .collect()
+}
+
+/// Restore images of objects packed into atlases. Atlas objects are removed.
+pub fn unpack_atlases(objs: Vec<Object>) -> Result<Vec<Object>> {
+    let mut atlases = HashMap::new();
+    let mut objs: Vec<Object> = objs
+        .into_iter()
+        .filter_map(|obj| match obj {
+            Object::Atlas(atlas) => {
+                atlases.insert(atlas.id, atlas.data);
+                None
+            }
+            obj => Some(obj),
+        })
+        .collect();
+    let mut decoded: HashMap<String, RgbaImage> = HashMap::new();
+
+    for obj in &mut objs {
+        let id = obj.get_id().to_owned();
+        let img = if let Some(img) = obj.get_img_mut() {
+            img
+        } else {
+            continue;
+        };
+        let pos = if let Some(pos) = img.atlas.take() {
+            pos
+        } else {
+            continue;
+        };
+
+        if !decoded.contains_key(&pos.id) {
+            let data = atlases
+                .get(&pos.id)
+                .ok_or_else(|| anyhow!("atlas \"{}\" for \"{}\" is not found", pos.id, id))?;
+            decoded.insert(pos.id.clone(), image::load_from_memory(data)?.to_rgba8());
+        }
+        img.data = crop(&decoded[&pos.id], &pos, img)?;
+    }
+
+    Ok(objs)
+}
+
+fn crop(atlas: &RgbaImage, pos: &AtlasPos, img: &Img) -> Result<Vec<u8>> {
+    let image = imageops::crop_imm(
+        atlas,
+        pos.x,
+        pos.y,
+        img.w * img.grid_nx,
+        img.h * img.grid_ny,
+    );
+    encode_png(image.to_image())
+}
+
+fn encode_png(image: RgbaImage) -> Result<Vec<u8>> {
+    let mut data = Vec::new();
+    DynamicImage::ImageRgba8(image)
+        .write_to(&mut Cursor::new(&mut data), ImageOutputFormat::Png)?;
+    Ok(data)
+}
+
+fn atlas_id(pak_name: &str, ty: &str, n: usize) -> String {
+    format!("{ATLAS_ID_PREFIX}{pak_name}/{ty}/{n}")
+}
+
+/// Place rects on shelves in bins of the given size.
+/// Returns bin index and position for each rect, or None if it is larger than the bin.
+fn shelf_pack(sizes: &[(u32, u32)], bin_size: u32) -> Vec<Option<(usize, u32, u32)>> {
+    let mut order: Vec<usize> = (0..sizes.len()).collect();
+    // Higher rects first to reduce spaces on shelves
+    order.sort_by_key(|&i| {
+        (
+            std::cmp::Reverse(sizes[i].1),
+            std::cmp::Reverse(sizes[i].0),
+            i,
+        )
+    });
+
+    let mut placements = vec![None; sizes.len()];
+    let (mut bin, mut x, mut y, mut shelf_h) = (0, 0, 0, 0);
+
+    for i in order {
+        let (w, h) = sizes[i];
+        if w > bin_size || h > bin_size {
+            continue;
+        }
+        if x + w > bin_size {
+            // Next shelf
+            x = 0;
+            y += shelf_h;
+            shelf_h = 0;
+        }
+        if y + h > bin_size {
+            // Next bin
+            bin += 1;
+            x = 0;
+            y = 0;
+            shelf_h = 0;
+        }
+        placements[i] = Some((bin, x, y));
+        x += w;
+        shelf_h = shelf_h.max(h);
+    }
+    placements
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn shelf_packing() {
+        let sizes = [(48, 48), (48, 96), (64, 48), (200, 10), (20, 20)];
+        let p = shelf_pack(&sizes, 128);
+        assert_eq!(p[1], Some((0, 0, 0)));
+        assert_eq!(p[2], Some((0, 48, 0)));
+        assert_eq!(p[0], Some((1, 0, 0)));
+        assert_eq!(p[3], None);
+        assert_eq!(p[4], Some((1, 48, 0)));
+    }
+}
@@ -52,6 +52,7 @@ pub fn build_img(input: ImgInput) -> Result<(Img, ImgData), Error> {
             n_anim_frame,
             duration,
             variation_rule,
+            atlas: None,
         },
         imgdata,
     ))
@@ -7,7 +7,6 @@ use common::gamedata::*;
 pub fn build_item_object(input: Input) -> Result<ItemObject, Error> {
     let img = get_optional_field!(input, image);
     let item = get_optional_field!(input, item);
-    let flags = ItemFlags::empty();
 
     let kind = match item.item_kind.as_str() {
         "object" => ItemKind::Object,
@@ -34,7 +33,7 @@ pub fn build_item_object(input: Input) -> Result<ItemObject, Error> {
     Ok(ItemObject {
         id: input.id,
         img: build_img(img)?.0,
-        default_flags: flags,
+        default_flags: item.default_flags,
         kind,
         group: item.group,
         basic_price: item.basic_price,
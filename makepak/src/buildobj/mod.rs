@@ -9,7 +9,8 @@ use common::gamedata::CharaBaseAttr;
 use common::obj::*;
 use geom::Coords;
 
-pub fn build_object(input: Input) -> Result<Object, Error> {
+/// Build an object from the input. Compiled bytecode of scripts is stored if `bytecode` is true.
+pub fn build_object(input: Input, bytecode: bool) -> Result<Object, Error> {
     let object_type = input.object_type.clone();
     match object_type.as_ref() {
         "anim_img" => build_anim_img_object(input).map(Object::AnimImg),
@@ -22,7 +23,7 @@ pub fn build_object(input: Input) -> Result<Object, Error> {
         "ui_img" => build_ui_img_object(input).map(Object::UiImg),
         "wall" => build_wall_object(input).map(Object::Wall),
         "region_gen" => build_region_gen_object(input).map(Object::RegionGen),
-        "script" => build_script_object(input).map(Object::Script),
+        "script" => build_script_object(input, bytecode).map(Object::Script),
         "site_gen" => build_site_gen_object(input).map(Object::SiteGen),
         _ => {
             bail!("Unknown object_type");
@@ -177,12 +178,14 @@ fn build_region_gen_object(input: Input) -> Result<RegionGenObject, Error> {
     })
 }
 
-fn build_script_object(input: Input) -> Result<ScriptObject, Error> {
+fn build_script_object(input: Input, bytecode: bool) -> Result<ScriptObject, Error> {
     let s = get_optional_field!(input, script);
+    let byte_code = crate::pyscript::compile_script(&input.id, &s.script, bytecode)?;
 
     Ok(ScriptObject {
         id: input.id,
         script: s.script,
+        byte_code,
     })
 }
 
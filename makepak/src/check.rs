@@ -0,0 +1,407 @@
+//! Check references between objects in paks and rules, and duplicated ids in paks.
+//!
+//! Rule files are read as the types of the rules crate.
+//! The object holder is initialized by the checked paks to resolve ids in rules.
+
+use anyhow::{bail, Result};
+use common::gamedata::CreationKind;
+use common::gobj;
+use common::obj::Object;
+use common::pakutil::read_pak;
+use rules::biome::Biomes;
+use rules::dungeon_gen::DungeonGen;
+use rules::newgame::NewGame;
+use rules::patch::is_patch_file;
+use rules::recipe::Recipes;
+use rules::world::World;
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Reference to an object, which should exist in paks
+#[derive(Debug)]
+struct ObjRef {
+    /// Type name of the referred object
+    ty: &'static str,
+    id: String,
+    /// File which includes this reference
+    source: PathBuf,
+    /// Describes where the reference is in the file
+    location: String,
+}
+
+#[derive(Default)]
+struct Checker {
+    /// Object type and id to the pak file which defines it
+    objs: HashMap<(&'static str, String), PathBuf>,
+    refs: Vec<ObjRef>,
+    n_errors: usize,
+}
+
+impl Checker {
+    fn add_ref(&mut self, ty: &'static str, id: &str, source: &Path, location: String) {
+        self.refs.push(ObjRef {
+            ty,
+            id: id.to_owned(),
+            source: source.to_owned(),
+            location,
+        });
+    }
+
+    fn is_defined(&self, r: &ObjRef) -> bool {
+        self.objs.contains_key(&(r.ty, r.id.clone()))
+    }
+
+    fn error(&mut self, source: &Path, msg: impl std::fmt::Display) {
+        eprintln!("{}: {}", source.to_string_lossy(), msg);
+        self.n_errors += 1;
+    }
+}
+
+/// Load paks and rules, and report all references to unknown objects
+pub fn check(paks: &[PathBuf], rule_dirs: &[PathBuf]) -> Result<()> {
+    let mut checker = Checker::default();
+
+    let mut pak_files = Vec::new();
+    for path in paks {
+        collect_files(path, "pak", &mut pak_files);
+    }
+    for pak_file in &pak_files {
+        check_pak(&mut checker, pak_file);
+    }
+
+    if !rule_dirs.is_empty() {
+        gobj::init(pak_files.clone());
+    }
+    for rule_dir in rule_dirs {
+        rule_refs(&mut checker, rule_dir);
+    }
+
+    let refs = std::mem::take(&mut checker.refs);
+    for r in &refs {
+        if !checker.is_defined(r) {
+            checker.error(
+                &r.source,
+                format!("{}: unknown {} \"{}\"", r.location, r.ty, r.id),
+            );
+        }
+    }
+
+    if checker.n_errors > 0 {
+        bail!("{} errors found", checker.n_errors);
+    }
+    Ok(())
+}
+
+/// Collect objects and references in a pak, and report duplicated ids
+fn check_pak(checker: &mut Checker, pak_file: &Path) {
+    let mut errors = Vec::new();
+    let pak = read_pak(pak_file, &mut errors);
+    for e in errors {
+        checker.error(pak_file, format!("{e:?}"));
+    }
+    for obj in &pak.objs {
+        let key = (obj.type_name(), obj.get_id().to_owned());
+        if let Some(defined_in) = checker.objs.get(&key) {
+            if !pak.manifest.overrides.contains(&key.1) {
+                let msg = format!(
+                    "{} \"{}\" is already defined in \"{}\"",
+                    key.0,
+                    key.1,
+                    defined_in.to_string_lossy()
+                );
+                checker.error(pak_file, msg);
+            }
+        } else {
+            checker.objs.insert(key, pak_file.to_owned());
+        }
+        if let Object::Item(o) = obj {
+            if !o.material_group.is_empty() {
+                let key = ("MaterialGroup", o.material_group.clone());
+                checker
+                    .objs
+                    .entry(key)
+                    .or_insert_with(|| pak_file.to_owned());
+            }
+        }
+        obj_refs(checker, obj, pak_file);
+    }
+}
+
+/// Collect references in an object
+fn obj_refs(checker: &mut Checker, obj: &Object, source: &Path) {
+    let loc = |field: &str| format!("{} \"{}\" {}", obj.type_name(), obj.get_id(), field);
+
+    if let Some(atlas) = obj.get_img().and_then(|img| img.atlas.as_ref()) {
+        checker.add_ref("Atlas", &atlas.id, source, loc("img.atlas"));
+    }
+
+    match obj {
+        Object::MapTemplate(o) => {
+            for id in &o.tile_table {
+                checker.add_ref("Tile", id, source, loc("tile_table"));
+            }
+            for id in &o.wall_table {
+                checker.add_ref("Wall", id, source, loc("wall_table"));
+            }
+            for id in &o.deco_table {
+                checker.add_ref("Deco", id, source, loc("deco_table"));
+            }
+            for (_, item_gen) in &o.items {
+                checker.add_ref("Item", &item_gen.id, source, loc("items"));
+            }
+        }
+        Object::RegionGen(o) => {
+            checker.add_ref(
+                "MapTemplate",
+                &o.map_template_id,
+                source,
+                loc("map_template_id"),
+            );
+            for (id, _) in &o.towns {
+                checker.add_ref("SiteGen", id, source, loc("towns"));
+            }
+            for (id, _) in &o.others {
+                checker.add_ref("SiteGen", id, source, loc("others"));
+            }
+        }
+        Object::SiteGen(o) => {
+            for id in &o.map_template_id {
+                checker.add_ref("MapTemplate", id, source, loc("map_template_id"));
+            }
+            for npc in &o.npcs {
+                checker.add_ref(
+                    "CharaTemplate",
+                    &npc.chara_template_id,
+                    source,
+                    loc("npcs.chara_template_id"),
+                );
+                if !npc.talk_script.is_empty() {
+                    checker.add_ref("Script", &npc.talk_script, source, loc("npcs.talk_script"));
+                }
+            }
+            if let Some((_, _, id)) = &o.delivery_chest {
+                checker.add_ref("Item", id, source, loc("delivery_chest"));
+            }
+        }
+        _ => (),
+    }
+}
+
+/// Collect references in rule files in the given directory.
+/// Ids resolved to indices when deserialized are checked by `gobj::collect_unknown_ids()`.
+fn rule_refs(checker: &mut Checker, rule_dir: &Path) {
+    for file in rule_files(rule_dir, "recipes") {
+        if let Some(r) = read_rule::<Recipes>(checker, &file) {
+            for kind in CreationKind::ALL {
+                for recipe in r.get(*kind) {
+                    let loc = format!("{:?} recipe \"{}\"", kind, recipe.product);
+                    checker.add_ref("Item", &recipe.product, &file, loc.clone());
+                    for (ingredient, _) in &recipe.ingredients {
+                        // Any item in the material group can be used
+                        if let Some(group) = ingredient.strip_prefix("group/") {
+                            checker.add_ref("MaterialGroup", group, &file, loc.clone());
+                        } else {
+                            checker.add_ref("Item", ingredient, &file, loc.clone());
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    for file in rule_files(rule_dir, "biomes") {
+        read_rule::<Biomes>(checker, &file);
+    }
+
+    for file in rule_files(rule_dir, "dungeon_gen") {
+        if let Some(r) = read_rule::<DungeonGen>(checker, &file) {
+            for (kind, params) in &r {
+                let loc = format!("dungeon \"{kind:?}\"");
+                for [tile, wall] in &params.terrain {
+                    checker.add_ref("Tile", tile, &file, loc.clone());
+                    checker.add_ref("Wall", wall, &file, loc.clone());
+                }
+                for (id, _) in &params.sub_walls {
+                    checker.add_ref("Wall", id, &file, loc.clone());
+                }
+            }
+        }
+    }
+
+    for file in rule_files(rule_dir, "item") {
+        if let Some(r) = read_rule::<rules::item::Item>(checker, &file) {
+            checker.add_ref("Item", &r.rotten_item, &file, "rotten_item".into());
+        }
+    }
+
+    for file in rule_files(rule_dir, "newgame") {
+        if let Some(r) = read_rule::<NewGame>(checker, &file) {
+            checker.add_ref("RegionGen", &r.start_region, &file, "start_region".into());
+            for id in r.chara_template_table.values() {
+                checker.add_ref("CharaTemplate", id, &file, "chara_template_table".into());
+            }
+        }
+    }
+
+    for file in rule_files(rule_dir, "world") {
+        if let Some(r) = read_rule::<World>(checker, &file) {
+            checker.add_ref("Script", &r.restart_script, &file, "restart_script".into());
+        }
+    }
+}
+
+fn read_rule<T: serde::de::DeserializeOwned>(checker: &mut Checker, file: &Path) -> Option<T> {
+    let (result, unknown_ids) = gobj::collect_unknown_ids(|| {
+        fs::read_to_string(file)
+            .map_err(anyhow::Error::from)
+            .and_then(|s| Ok(ron::de::from_str::<T>(&s)?))
+    });
+    let name = file.file_stem().unwrap_or_default().to_string_lossy();
+    for (ty, id) in unknown_ids {
+        checker.add_ref(ty, &id, file, name.to_string());
+    }
+    match result {
+        Ok(rule) => Some(rule),
+        Err(e) => {
+            checker.error(file, e);
+            None
+        }
+    }
+}
+
+/// Get rule files in the same way as the rules crate.
+/// A rule is "<name>.ron", or all files except patches in "<name>" directory.
+fn rule_files(rule_dir: &Path, name: &str) -> Vec<PathBuf> {
+    let d = rule_dir.join(name);
+    if d.is_dir() {
+        let mut files: Vec<PathBuf> = match fs::read_dir(&d) {
+            Ok(entries) => entries
+                .filter_map(|e| e.ok())
+                .map(|e| e.path())
+                .filter(|path| !is_patch_file(path))
+                .collect(),
+            Err(_) => Vec::new(),
+        };
+        files.sort();
+        files
+    } else {
+        let file = rule_dir.join(format!("{name}.ron"));
+        if file.exists() {
+            vec![file]
+        } else {
+            Vec::new()
+        }
+    }
+}
+
+/// Collect files which have the given extension recursively
+fn collect_files(path: &Path, extension: &str, files: &mut Vec<PathBuf>) {
+    if !path.is_dir() {
+        files.push(path.to_owned());
+        return;
+    }
+    let mut paths: Vec<PathBuf> = match fs::read_dir(path) {
+        Ok(entries) => entries.filter_map(|e| e.ok()).map(|e| e.path()).collect(),
+        Err(_) => return,
+    };
+    paths.sort();
+    for path in paths {
+        if path.is_dir() {
+            collect_files(&path, extension, files);
+        } else if path.extension().is_some_and(|e| e == extension) {
+            files.push(path);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_dir;
+
+    #[test]
+    fn unknown_refs_in_rules() {
+        let dir = test_dir("check-rules");
+        fs::write(
+            dir.join("recipes.ron"),
+            r#"(craft_recipes: [(
+                product: "plank",
+                ingredients: [("group/wood", 1), ("group/stone", 1)],
+                facility: None,
+                difficulty: 0,
+                required_time: Short,
+            )])"#,
+        )
+        .unwrap();
+        fs::write(
+            dir.join("biomes.ron"),
+            r#"(
+                biomes: {"grassland": (tile: "grass", wall: "tree", plants: [], items: [])},
+                sub_biomes: {},
+            )"#,
+        )
+        .unwrap();
+        fs::create_dir_all(dir.join("paks")).unwrap();
+        gobj::init(vec![dir.join("paks")]);
+
+        let mut checker = Checker::default();
+        for (ty, id) in [
+            ("Item", "plank"),
+            ("MaterialGroup", "wood"),
+            ("Tile", "grass"),
+        ] {
+            checker.objs.insert((ty, id.into()), dir.clone());
+        }
+        rule_refs(&mut checker, &dir);
+        assert_eq!(checker.n_errors, 0);
+
+        let mut unknown: Vec<(&str, &str)> = checker
+            .refs
+            .iter()
+            .filter(|r| !checker.is_defined(r))
+            .map(|r| (r.ty, r.id.as_str()))
+            .collect();
+        unknown.sort();
+        assert_eq!(unknown, [("MaterialGroup", "stone"), ("Wall", "tree")]);
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn duplicated_ids() {
+        use crate::compile::{write_pak, write_to_vec};
+        use common::obj::ScriptObject;
+        use common::pakutil::PakManifest;
+
+        let dir = test_dir("check-duplicated");
+        let script = |id: &str| {
+            let obj = Object::Script(ScriptObject {
+                id: id.into(),
+                script: String::new(),
+                byte_code: None,
+            });
+            (id.to_owned(), write_to_vec(&obj).unwrap())
+        };
+        let manifest = |overrides: &[&str]| PakManifest {
+            overrides: overrides.iter().map(|s| s.to_string()).collect(),
+            ..PakManifest::default()
+        };
+        write_pak(&dir.join("a.pak"), None, &[script("a"), script("b")]).unwrap();
+        write_pak(&dir.join("b.pak"), Some(&manifest(&["a"])), &[script("a")]).unwrap();
+        write_pak(&dir.join("c.pak"), Some(&manifest(&["a"])), &[script("b")]).unwrap();
+
+        let mut checker = Checker::default();
+        check_pak(&mut checker, &dir.join("a.pak"));
+        check_pak(&mut checker, &dir.join("b.pak"));
+        assert_eq!(checker.n_errors, 0);
+        check_pak(&mut checker, &dir.join("c.pak"));
+        assert_eq!(checker.n_errors, 1);
+        assert_eq!(checker.objs[&("Script", "b".to_owned())], dir.join("a.pak"));
+
+        assert!(check(&[dir.join("a.pak"), dir.join("b.pak")], &[]).is_ok());
+        assert!(check(&[dir.clone()], &[]).is_err());
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -1,10 +1,11 @@
+use crate::atlas::pack_atlases;
 use crate::dir;
 use crate::error::*;
 use crate::pyscript::read_pyscript;
 use crate::verbose::print_verbose;
-use anyhow::{bail, Result};
+use anyhow::{bail, Context, Result};
 use common::obj::Object;
-use common::pakutil::write_object;
+use common::pakutil::{write_object, write_pak_manifest, PakManifest, PAK_MANIFEST_ENTRY};
 use std::fs::File;
 use std::io::{Read, Write};
 use std::path::Path;
@@ -13,41 +14,84 @@ use std::path::PathBuf;
 use crate::buildobj::build_object;
 use crate::input::Input;
 
-pub fn compile(files: &[PathBuf], output_file: &Path) {
-    let out = File::create(output_file).unwrap();
-    let mut builder = tar::Builder::new(out);
+/// Compile input files into a pak file. Images are packed into atlases if `atlas` is true,
+/// and compiled bytecode of scripts is stored if `bytecode` is true.
+pub fn compile(files: &[PathBuf], output_file: &Path, atlas: bool, bytecode: bool) -> Result<()> {
+    let mut objs = Vec::new();
+    let mut n_errors = 0;
 
     for f in files {
-        let f = Path::new(f);
-        if f.is_relative() {
-            dir::set_src_dir(f.parent());
-        } else {
-            dir::set_src_dir(None);
+        match compile_file(f, bytecode) {
+            Ok(obj) => objs.push(obj),
+            Err(e) => {
+                print_file_error(f, &e);
+                n_errors += 1;
+            }
         }
+    }
 
-        let read_result = if Some(true) == f.extension().map(|e| e == "py") {
-            read_pyscript(f)
-        } else {
-            read_input_file(f)
-        };
+    if n_errors > 0 {
+        bail!(
+            "{} errors occurred, \"{}\" was not written",
+            n_errors,
+            output_file.to_string_lossy()
+        );
+    }
+    if atlas {
+        let pak_name = output_file
+            .file_stem()
+            .map(|s| s.to_string_lossy().into_owned())
+            .unwrap_or_default();
+        objs = pack_atlases(&pak_name, objs)?;
+    }
+    write_pak(output_file, None, &objs)
+}
 
-        let obj = match read_result {
-            Ok(o) => o,
-            Err(e) => {
-                eprintln!("Cannot process \"{}\"", f.to_string_lossy());
-                for e in e.chain() {
-                    eprintln!("{e}");
-                }
-                continue;
-            }
-        };
-        let v = write_to_vec(&obj).unwrap();
-        write_data_to_tar(&mut builder, &v, obj.get_id());
+/// Compile an input file, and returns the object id and serialized data
+pub fn compile_file(f: &Path, bytecode: bool) -> Result<(String, Vec<u8>)> {
+    // Paths in input files are relative to the input file
+    dir::set_src_dir(f.parent());
+
+    let obj = if Some(true) == f.extension().map(|e| e == "py") {
+        read_pyscript(f, bytecode)?
+    } else {
+        read_input_file(f, bytecode)?
+    };
+    let v = write_to_vec(&obj)?;
+    Ok((obj.get_id().to_owned(), v))
+}
+
+/// Write serialized objects to a pak file
+pub fn write_pak(
+    output_file: &Path,
+    manifest: Option<&PakManifest>,
+    objs: &[(String, Vec<u8>)],
+) -> Result<()> {
+    let out = File::create(output_file)
+        .with_context(|| format!("cannot create \"{}\"", output_file.to_string_lossy()))?;
+    let mut builder = tar::Builder::new(out);
+    if let Some(manifest) = manifest {
+        let mut v = Vec::new();
+        if let Err(e) = write_pak_manifest(&mut v, manifest) {
+            bail!(PakCompileError::ObjWriteError { description: e });
+        }
+        write_data_to_tar(&mut builder, &v, PAK_MANIFEST_ENTRY);
+    }
+    for (id, data) in objs {
+        write_data_to_tar(&mut builder, data, id);
+    }
+    builder.finish()?;
+    Ok(())
+}
+
+pub fn print_file_error(f: &Path, e: &anyhow::Error) {
+    eprintln!("Cannot process \"{}\"", f.to_string_lossy());
+    for e in e.chain() {
+        eprintln!("{e}");
     }
-    builder.finish().unwrap();
 }
 
-fn read_input_file<P: AsRef<Path>>(path: P) -> Result<Object> {
+fn read_input_file<P: AsRef<Path>>(path: P, bytecode: bool) -> Result<Object> {
     let path = path.as_ref();
     let s = {
         let mut f = File::open(path)?;
@@ -58,6 +102,16 @@ fn read_input_file<P: AsRef<Path>>(path: P) -> Result<Object> {
 
     print_verbose(|| format!("Processing \"{path:?}\""));
 
+    let input = parse_input(path, &s)?;
+
+    print_verbose(|| format!("{input:?}"));
+    let object = build_object(input, bytecode)?;
+
+    Ok(object)
+}
+
+/// Deserialize an input file. The format is decided by the extension.
+pub fn parse_input(path: &Path, s: &str) -> Result<Input> {
     let ext = if let Some(ext) = path.extension() {
         ext
     } else {
@@ -67,19 +121,17 @@ fn read_input_file<P: AsRef<Path>>(path: P) -> Result<Object> {
         );
     };
 
-    let input: Input = if ext == "ron" {
-        ron::de::from_str(&s)?
-    } else {
-        bail!("invalid input file type: {}", path.to_string_lossy());
+    let input = match ext.to_string_lossy().as_ref() {
+        "ron" => ron::de::from_str(s)?,
+        "toml" => toml::from_str(s)?,
+        "json" => serde_json::from_str(s)?,
+        "yaml" | "yml" => serde_yaml::from_str(s)?,
+        _ => bail!("invalid input file type: {}", path.to_string_lossy()),
     };
-
-    print_verbose(|| format!("{input:?}"));
-    let object = build_object(input)?;
-
-    Ok(object)
+    Ok(input)
 }
 
-fn write_to_vec(obj: &Object) -> Result<Vec<u8>> {
+pub fn write_to_vec(obj: &Object) -> Result<Vec<u8>> {
     let mut v = Vec::new();
     match write_object(&mut v, obj) {
         Ok(_) => Ok(v),
@@ -87,7 +139,7 @@ fn write_to_vec(obj: &Object) -> Result<Vec<u8>> {
     }
 }
 
-fn write_data_to_tar<W: Write>(builder: &mut tar::Builder<W>, data: &[u8], path: &str) {
+pub fn write_data_to_tar<W: Write>(builder: &mut tar::Builder<W>, data: &[u8], path: &str) {
     let mut header = tar::Header::new_gnu();
     header.set_path(path).unwrap();
     header.set_size(data.len() as u64);
@@ -96,3 +148,108 @@ fn write_data_to_tar<W: Write>(builder: &mut tar::Builder<W>, data: &[u8], path:
 
     builder.append(&header, data).unwrap();
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use common::sitegen::NpcGenId;
+
+    fn parse(file_name: &str, s: &str) -> Result<Input> {
+        parse_input(Path::new(file_name), s)
+    }
+
+    fn assert_site_gen(input: Input) {
+        assert_eq!(input.object_type, "site_gen");
+        assert_eq!(input.id, "town");
+        let site_gen = input.site_gen.unwrap();
+        assert_eq!(site_gen.map_template_id, ["town-map"]);
+        assert_eq!(site_gen.shops[&NpcGenId::Site(1)].shop_kind, "food");
+        assert_eq!(site_gen.delivery_chest, None);
+    }
+
+    #[test]
+    fn parse_ron() {
+        let input = parse(
+            "town.ron",
+            r#"(
+                object_type: "site_gen",
+                id: "town",
+                site_gen: (
+                    kind: Town,
+                    site_symbol: "town",
+                    map_template_id: ["town-map"],
+                    default_faction_id: "player",
+                    shops: {Site(1): (shop_kind: "food")},
+                ),
+            )"#,
+        );
+        assert_site_gen(input.unwrap());
+    }
+
+    #[test]
+    fn parse_json() {
+        let input = parse(
+            "town.json",
+            r#"{
+                "object_type": "site_gen",
+                "id": "town",
+                "site_gen": {
+                    "kind": "Town",
+                    "site_symbol": "town",
+                    "map_template_id": ["town-map"],
+                    "default_faction_id": "player",
+                    "shops": [[{"Site": 1}, {"shop_kind": "food"}]]
+                }
+            }"#,
+        );
+        assert_site_gen(input.unwrap());
+    }
+
+    #[test]
+    fn parse_toml() {
+        let input = parse(
+            "town.toml",
+            r#"
+                object_type = "site_gen"
+                id = "town"
+
+                [site_gen]
+                kind = "Town"
+                site_symbol = "town"
+                map_template_id = ["town-map"]
+                default_faction_id = "player"
+                shops = [[{ Site = 1 }, { shop_kind = "food" }]]
+            "#,
+        );
+        assert_site_gen(input.unwrap());
+    }
+
+    #[test]
+    fn parse_yaml() {
+        let input = parse(
+            "town.yaml",
+            r#"
+                object_type: site_gen
+                id: town
+                site_gen:
+                  kind: Town
+                  site_symbol: town
+                  map_template_id: [town-map]
+                  default_faction_id: player
+                  shops:
+                    - [!Site 1, {shop_kind: food}]
+            "#,
+        );
+        assert_site_gen(input.unwrap());
+    }
+
+    #[test]
+    fn unknown_field() {
+        let input = parse(
+            "item.json",
+            r#"{"object_type": "item", "id": "a", "unknown": 1}"#,
+        );
+        assert!(input.is_err());
+        assert!(parse("item.txt", "").is_err());
+    }
+}
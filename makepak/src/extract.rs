@@ -0,0 +1,345 @@
+//! Extract objects in pak files to input files, which are compiled to the same objects again.
+//!
+//! Files are written to "<output>/<object_type>/".
+//! Map templates are written as pak files because they are edited by map-editor.
+//! Images packed into atlases are written as separated files.
+
+use crate::atlas::unpack_atlases;
+use crate::compile::{write_data_to_tar, write_to_vec};
+use crate::input::*;
+use crate::pyscript::is_pyscript_source;
+use crate::verbose::print_verbose;
+use anyhow::{bail, Context, Result};
+use common::gamedata::ItemKind;
+use common::obj::*;
+use common::pakutil::read_tar;
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+pub fn extract(paks: &[PathBuf], output_dir: &Path) -> Result<()> {
+    let mut n_errors = 0;
+    // Extracted file paths without extension to the object id
+    let mut extracted: HashMap<PathBuf, String> = HashMap::new();
+
+    for pak in paks {
+        let mut objs = Vec::new();
+        let mut errors = Vec::new();
+        read_tar(pak, &mut |obj| objs.push(obj), &mut errors);
+        for e in &errors {
+            eprintln!("Error in \"{}\": {:?}", pak.to_string_lossy(), e);
+        }
+        n_errors += errors.len();
+
+        let objs = match unpack_atlases(objs) {
+            Ok(objs) => objs,
+            Err(e) => {
+                eprintln!("Error in \"{}\": {}", pak.to_string_lossy(), e);
+                n_errors += 1;
+                continue;
+            }
+        };
+
+        for obj in objs {
+            print_verbose(|| format!("Extracting {obj:?}"));
+            if let Err(e) = extract_object(obj, output_dir, &mut extracted) {
+                for e in e.chain() {
+                    eprintln!("{e}");
+                }
+                n_errors += 1;
+            }
+        }
+    }
+
+    if n_errors > 0 {
+        bail!("{} errors occurred while extracting", n_errors);
+    }
+    Ok(())
+}
+
+fn extract_object(
+    obj: Object,
+    output_dir: &Path,
+    extracted: &mut HashMap<PathBuf, String>,
+) -> Result<()> {
+    let object_type = object_type(&obj);
+    let dir = output_dir.join(object_type);
+    fs::create_dir_all(&dir)?;
+    let name = file_name(obj.get_id());
+    if let Some(id) = extracted.insert(dir.join(&name), obj.get_id().to_owned()) {
+        bail!(
+            "{} \"{}\" and \"{}\" are extracted to the same file name \"{}\"",
+            object_type,
+            id,
+            obj.get_id(),
+            name
+        );
+    }
+
+    let mut input = Input {
+        object_type: object_type.into(),
+        id: obj.get_id().into(),
+        ..Input::default()
+    };
+    match obj {
+        Object::AnimImg(o) => {
+            input.image = Some(extract_img(&o.img, &dir, &name)?);
+        }
+        Object::CharaTemplate(o) => {
+            input.image = Some(extract_img(&o.img, &dir, &name)?);
+            let attr = &o.base_attr;
+            input.chara_template = Some(CharaTemplateDepInput {
+                race: o.race,
+                class: o.class,
+                faction: o.faction,
+                gen_weight: o.gen_weight,
+                gen_level: o.gen_level,
+                default_ai_kind: o.default_ai_kind,
+                skill_bonus: o.skill_bonus,
+                abilities: o.abilities,
+                equips: o.equips,
+                drop_items: o.drop_items,
+                base_hp: attr.base_hp,
+                base_mp: attr.base_mp,
+                str: attr.str as u16,
+                vit: attr.vit as u16,
+                dex: attr.dex as u16,
+                int: attr.int as u16,
+                wil: attr.wil as u16,
+                cha: attr.cha as u16,
+                spd: attr.spd as u16,
+                carry: attr.carry as u16,
+                travel_speed: attr.travel_speed as u16,
+            });
+        }
+        Object::Deco(o) => {
+            input.image = Some(extract_img(&o.img, &dir, &name)?);
+        }
+        Object::EffectImg(o) => {
+            input.image = Some(extract_img(&o.img, &dir, &name)?);
+        }
+        Object::Item(o) => {
+            input.image = Some(extract_img(&o.img, &dir, &name)?);
+            let (item_kind, weapon_kind, armor_kind) = match o.kind {
+                ItemKind::Object => ("object", None, None),
+                ItemKind::Potion => ("potion", None, None),
+                ItemKind::Throwing => ("throwing", None, None),
+                ItemKind::Food => ("food", None, None),
+                ItemKind::MagicDevice => ("magic_device", None, None),
+                ItemKind::Weapon(weapon_kind) => ("weapon", Some(weapon_kind), None),
+                ItemKind::Armor(armor_kind) => ("armor", None, Some(armor_kind)),
+                ItemKind::Tool => ("tool", None, None),
+                ItemKind::Container => ("container", None, None),
+                ItemKind::Readable => ("readable", None, None),
+                ItemKind::Material => ("material", None, None),
+                ItemKind::Module => ("module", None, None),
+                ItemKind::Special => ("special", None, None),
+            };
+            input.item = Some(ItemDepInput {
+                item_kind: item_kind.into(),
+                group: o.group,
+                basic_price: o.basic_price,
+                w: o.w,
+                quality_kind: o.quality_kind,
+                gen_weight: o.gen_weight,
+                shop_weight: Some(o.shop_weight),
+                gen_level: o.gen_level,
+                weapon_kind,
+                armor_kind,
+                default_flags: o.default_flags,
+                attrs: o.attrs,
+                material_group: o.material_group,
+                material: o.material,
+            });
+        }
+        Object::SpecialTile(o) => {
+            input.image = Some(extract_img(&o.img, &dir, &name)?);
+            input.special_tile = Some(SpecialTileDepInput {
+                always_background: Some(o.always_background),
+            });
+        }
+        Object::Tile(o) => {
+            input.image = Some(extract_img(&o.img, &dir, &name)?);
+            input.tile = Some(TileDepInput {
+                kind: o.kind,
+                fertility: o.fertility,
+                build_skill: o.build_skill,
+                materials: o.materials,
+            });
+        }
+        Object::UiImg(o) => {
+            input.image = Some(extract_img(&o.img, &dir, &name)?);
+            input.ui_img = Some(UiImgDepInput { hot: o.hot });
+        }
+        Object::Wall(o) => {
+            input.image = Some(extract_img(&o.img, &dir, &name)?);
+            input.wall = Some(WallDepInput {
+                hp: Some(o.hp),
+                base_draw: Some(o.base_draw),
+                build_skill: o.build_skill,
+                materials: o.materials,
+                mining_rewards: o.mining_rewards,
+            });
+        }
+        Object::MapTemplate(_) => {
+            let data = write_to_vec(&obj)?;
+            let file = fs::File::create(dir.join(format!("{name}.pak")))?;
+            let mut builder = tar::Builder::new(file);
+            write_data_to_tar(&mut builder, &data, obj.get_id());
+            builder.finish()?;
+            return Ok(());
+        }
+        Object::RegionGen(o) => {
+            let f = |v: Vec<(String, geom::Coords)>| -> Vec<SiteGenIdAndPos> {
+                v.into_iter()
+                    .map(|(id, pos)| SiteGenIdAndPos { id, pos })
+                    .collect()
+            };
+            input.region_gen = Some(RegionGenDepInput {
+                map_template_id: o.map_template_id,
+                towns: f(o.towns),
+                others: f(o.others),
+            });
+        }
+        Object::Script(o) => {
+            if is_pyscript_source(&o.id, &o.script) {
+                fs::write(dir.join(format!("{name}.py")), &o.script)?;
+                return Ok(());
+            }
+            input.script = Some(ScriptDepInput { script: o.script });
+        }
+        Object::Atlas(_) => unreachable!("atlases are removed by unpack_atlases"),
+        Object::SiteGen(o) => {
+            input.site_gen = Some(SiteGenDepInput {
+                kind: o.kind,
+                site_symbol: o.site_symbol,
+                map_template_id: o.map_template_id,
+                default_faction_id: o.default_faction_id,
+                npcs: o.npcs,
+                shops: o.shops,
+                quests: o.quests,
+                delivery_chest: o.delivery_chest,
+            });
+        }
+    }
+
+    let s = ron::ser::to_string_pretty(&input, ron::ser::PrettyConfig::default())?;
+    let path = dir.join(format!("{name}.ron"));
+    fs::write(&path, s).with_context(|| format!("writing \"{}\"", path.to_string_lossy()))?;
+    Ok(())
+}
+
+/// Write image data as a png file, and returns input for it
+fn extract_img(img: &Img, dir: &Path, name: &str) -> Result<ImgInput> {
+    let path = format!("{name}.png");
+    fs::write(dir.join(&path), &img.data)?;
+
+    Ok(ImgInput {
+        path,
+        copyright: String::new(),
+        w: Some(img.w),
+        h: Some(img.h),
+        grid_nx: Some(img.grid_nx),
+        grid_ny: Some(img.grid_ny),
+        n_frame: Some(img.n_frame),
+        n_pattern: Some(img.n_pattern),
+        n_anim_frame: Some(img.n_anim_frame),
+        duration: Some(img.duration),
+        variation_rule: img.variation_rule,
+    })
+}
+
+/// Value of `object_type` in input files
+fn object_type(obj: &Object) -> &'static str {
+    match obj {
+        Object::AnimImg(_) => "anim_img",
+        Object::CharaTemplate(_) => "chara_template",
+        Object::Deco(_) => "deco",
+        Object::EffectImg(_) => "effect_img",
+        Object::Item(_) => "item",
+        Object::SpecialTile(_) => "special_tile",
+        Object::Tile(_) => "tile",
+        Object::UiImg(_) => "ui_img",
+        Object::Wall(_) => "wall",
+        Object::MapTemplate(_) => "map_template",
+        Object::RegionGen(_) => "region_gen",
+        Object::Script(_) => "script",
+        Object::SiteGen(_) => "site_gen",
+        Object::Atlas(_) => unreachable!("atlases are removed by unpack_atlases"),
+    }
+}
+
+/// Object ids may include path separators
+fn file_name(id: &str) -> String {
+    id.replace(['/', '\\'], "_")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::compile::compile;
+    use crate::test_dir;
+
+    /// Write item input files with the given ids and default flags
+    fn write_items(dir: &Path, items: &[(&str, u64)]) -> Vec<PathBuf> {
+        let img = dir.join("img.png");
+        image::RgbImage::new(2, 2).save(&img).unwrap();
+        items
+            .iter()
+            .enumerate()
+            .map(|(i, (id, flags))| {
+                let path = dir.join(format!("{i}.ron"));
+                let input = format!(
+                    r#"(
+                        object_type: "item",
+                        id: "{id}",
+                        image: (path: "{}"),
+                        item: (
+                            item_kind: "object",
+                            group: "misc",
+                            basic_price: 10,
+                            w: 100,
+                            gen_weight: 1.0,
+                            default_flags: {flags},
+                        ),
+                    )"#,
+                    img.display()
+                );
+                fs::write(&path, input).unwrap();
+                path
+            })
+            .collect()
+    }
+
+    #[test]
+    fn extract_and_compile() {
+        let dir = test_dir("extract");
+        let files = write_items(&dir, &[("test/fixed", 1), ("plain", 0)]);
+        let pak = dir.join("a.pak");
+        compile(&files, &pak, false, false).unwrap();
+
+        let output_dir = dir.join("extracted");
+        extract(&[pak.clone()], &output_dir).unwrap();
+        let files = [
+            output_dir.join("item/test_fixed.ron"),
+            output_dir.join("item/plain.ron"),
+        ];
+        let compiled = dir.join("b.pak");
+        compile(&files, &compiled, false, false).unwrap();
+        assert_eq!(fs::read(&pak).unwrap(), fs::read(&compiled).unwrap());
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn file_name_collision() {
+        let dir = test_dir("extract-collision");
+        let files = write_items(&dir, &[("a/b", 0), ("a_b", 0)]);
+        let pak = dir.join("a.pak");
+        compile(&files, &pak, false, false).unwrap();
+
+        assert!(extract(&[pak], &dir.join("extracted")).is_err());
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
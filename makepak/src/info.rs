@@ -0,0 +1,123 @@
+use anyhow::{bail, Result};
+use common::obj::Object;
+use common::pakutil::{read_tar, write_object, PakLoadingError};
+use std::path::{Path, PathBuf};
+
+#[derive(Debug, Serialize)]
+struct PakInfo {
+    file: PathBuf,
+    objects: Vec<ObjInfo>,
+}
+
+#[derive(Debug, Serialize)]
+struct ObjInfo {
+    #[serde(rename = "type")]
+    ty: &'static str,
+    id: String,
+    img: Option<ImgInfo>,
+    /// Serialized size in bytes
+    size: usize,
+}
+
+#[derive(Debug, Serialize)]
+struct ImgInfo {
+    w: u32,
+    h: u32,
+    grid_nx: u32,
+    grid_ny: u32,
+    n_frame: u32,
+}
+
+/// Print objects in the given pak files as a table or JSON
+pub fn print_info(files: &[PathBuf], json: bool) -> Result<()> {
+    let mut paks = Vec::new();
+    let mut n_errors = 0;
+
+    for file in files {
+        let (pak, errors) = read_pak_info(file);
+        for e in &errors {
+            eprintln!("Error in \"{}\": {:?}", file.to_string_lossy(), e);
+        }
+        n_errors += errors.len();
+        paks.push(pak);
+    }
+
+    if json {
+        println!("{}", serde_json::to_string_pretty(&paks)?);
+    } else {
+        for pak in &paks {
+            print_table(pak);
+        }
+    }
+
+    if n_errors > 0 {
+        bail!("{} errors occurred while reading paks", n_errors);
+    }
+    Ok(())
+}
+
+fn read_pak_info(file: &Path) -> (PakInfo, Vec<PakLoadingError>) {
+    let mut objects = Vec::new();
+    let mut errors = Vec::new();
+
+    read_tar(file, &mut |obj| objects.push(obj_info(&obj)), &mut errors);
+    objects.sort_by(|a, b| (a.ty, &a.id).cmp(&(b.ty, &b.id)));
+
+    let pak = PakInfo {
+        file: file.to_owned(),
+        objects,
+    };
+    (pak, errors)
+}
+
+fn obj_info(obj: &Object) -> ObjInfo {
+    let img = obj.get_img().map(|img| ImgInfo {
+        w: img.w,
+        h: img.h,
+        grid_nx: img.grid_nx,
+        grid_ny: img.grid_ny,
+        n_frame: img.n_frame,
+    });
+
+    // Objects are written in the same way as compiling, so this is equal to the size in the pak
+    let mut v = Vec::new();
+    let size = match write_object(&mut v, obj) {
+        Ok(_) => v.len(),
+        Err(e) => {
+            eprintln!("Cannot serialize \"{}\": {}", obj.get_id(), e);
+            0
+        }
+    };
+
+    ObjInfo {
+        ty: obj.type_name(),
+        id: obj.get_id().to_owned(),
+        img,
+        size,
+    }
+}
+
+fn print_table(pak: &PakInfo) {
+    println!("{}", pak.file.to_string_lossy());
+    println!(
+        "{:<14} {:<32} {:>9} {:>5} {:>6} {:>9}",
+        "TYPE", "ID", "IMAGE", "GRID", "FRAMES", "SIZE"
+    );
+    for obj in &pak.objects {
+        let (image, grid, frames) = if let Some(img) = &obj.img {
+            (
+                format!("{}x{}", img.w, img.h),
+                format!("{}x{}", img.grid_nx, img.grid_ny),
+                img.n_frame.to_string(),
+            )
+        } else {
+            ("-".into(), "-".into(), "-".into())
+        };
+        println!(
+            "{:<14} {:<32} {:>9} {:>5} {:>6} {:>9}",
+            obj.ty, obj.id, image, grid, frames, obj.size
+        );
+    }
+    let total: usize = pak.objects.iter().map(|obj| obj.size).sum();
+    println!("{} objects, {} bytes", pak.objects.len(), total);
+}
@@ -4,30 +4,39 @@ use common::hashmap::HashMap;
 use common::sitegen;
 use geom::Coords;
 use rusted_ruins_common::sitegen::NpcGenId;
+use schemars::JsonSchema;
 
-#[derive(Debug, Serialize, Deserialize)]
+#[serde_with::apply(
+    Option => #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        with = "::serde_with::rust::unwrap_or_skip"
+    )],
+)]
+#[derive(Debug, Default, Serialize, Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
 pub struct Input {
     pub object_type: String,
     pub id: String,
-    #[serde(default, with = "::serde_with::rust::unwrap_or_skip")]
+    #[schemars(with = "ImgInput")]
     pub image: Option<ImgInput>,
-    #[serde(default, with = "::serde_with::rust::unwrap_or_skip")]
+    #[schemars(with = "CharaTemplateDepInput")]
     pub chara_template: Option<CharaTemplateDepInput>,
-    #[serde(default, with = "::serde_with::rust::unwrap_or_skip")]
+    #[schemars(with = "ItemDepInput")]
     pub item: Option<ItemDepInput>,
-    #[serde(default, with = "::serde_with::rust::unwrap_or_skip")]
+    #[schemars(with = "TileDepInput")]
     pub tile: Option<TileDepInput>,
-    #[serde(default, with = "::serde_with::rust::unwrap_or_skip")]
+    #[schemars(with = "UiImgDepInput")]
     pub ui_img: Option<UiImgDepInput>,
-    #[serde(default, with = "::serde_with::rust::unwrap_or_skip")]
+    #[schemars(with = "WallDepInput")]
     pub wall: Option<WallDepInput>,
-    #[serde(default, with = "::serde_with::rust::unwrap_or_skip")]
+    #[schemars(with = "SpecialTileDepInput")]
     pub special_tile: Option<SpecialTileDepInput>,
-    #[serde(default, with = "::serde_with::rust::unwrap_or_skip")]
+    #[schemars(with = "RegionGenDepInput")]
     pub region_gen: Option<RegionGenDepInput>,
-    #[serde(default, with = "::serde_with::rust::unwrap_or_skip")]
+    #[schemars(with = "ScriptDepInput")]
     pub script: Option<ScriptDepInput>,
-    #[serde(default, with = "::serde_with::rust::unwrap_or_skip")]
+    #[schemars(with = "SiteGenDepInput")]
     pub site_gen: Option<SiteGenDepInput>,
 }
 
@@ -43,28 +52,35 @@ macro_rules! get_optional_field {
     };
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[serde_with::apply(
+    Option => #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        with = "::serde_with::rust::unwrap_or_skip"
+    )],
+)]
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
 #[serde(deny_unknown_fields)]
 pub struct ImgInput {
     pub path: String,
     /// Name of the creator and other copyright information.
     #[serde(default)]
     pub copyright: String,
-    #[serde(default, with = "::serde_with::rust::unwrap_or_skip")]
+    #[schemars(with = "u32")]
     pub w: Option<u32>,
-    #[serde(default, with = "::serde_with::rust::unwrap_or_skip")]
+    #[schemars(with = "u32")]
     pub h: Option<u32>,
-    #[serde(default, with = "::serde_with::rust::unwrap_or_skip")]
+    #[schemars(with = "u32")]
     pub grid_nx: Option<u32>,
-    #[serde(default, with = "::serde_with::rust::unwrap_or_skip")]
+    #[schemars(with = "u32")]
     pub grid_ny: Option<u32>,
-    #[serde(default, with = "::serde_with::rust::unwrap_or_skip")]
+    #[schemars(with = "u32")]
     pub n_frame: Option<u32>,
-    #[serde(default, with = "::serde_with::rust::unwrap_or_skip")]
+    #[schemars(with = "u32")]
     pub n_pattern: Option<u32>,
-    #[serde(default, with = "::serde_with::rust::unwrap_or_skip")]
+    #[schemars(with = "u32")]
     pub n_anim_frame: Option<u32>,
-    #[serde(default, with = "::serde_with::rust::unwrap_or_skip")]
+    #[schemars(with = "u32")]
     pub duration: Option<u32>,
     #[serde(default)]
     pub variation_rule: common::obj::ImgVariationRule,
@@ -72,7 +88,7 @@ pub struct ImgInput {
 
 // Type dependent fields
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
 #[serde(deny_unknown_fields)]
 pub struct CharaTemplateDepInput {
     pub race: String,
@@ -106,32 +122,47 @@ pub struct CharaTemplateDepInput {
     pub travel_speed: u16,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[serde_with::apply(
+    Option => #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        with = "::serde_with::rust::unwrap_or_skip"
+    )],
+)]
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
 #[serde(deny_unknown_fields)]
 pub struct TileDepInput {
     pub kind: ::common::obj::TileKind,
     #[serde(default)]
     pub fertility: u8,
-    #[serde(default, with = "::serde_with::rust::unwrap_or_skip")]
+    #[schemars(with = "u32")]
     pub build_skill: Option<u32>,
     #[serde(default)]
     pub materials: Vec<(String, u32)>,
 }
 
-#[derive(Debug, Default, Serialize, Deserialize)]
+#[derive(Debug, Default, Serialize, Deserialize, JsonSchema)]
 #[serde(deny_unknown_fields)]
 pub struct UiImgDepInput {
     #[serde(default)]
     pub hot: (u8, u8),
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[serde_with::apply(
+    Option => #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        with = "::serde_with::rust::unwrap_or_skip"
+    )],
+)]
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
 #[serde(deny_unknown_fields)]
 pub struct WallDepInput {
-    #[serde(default, with = "::serde_with::rust::unwrap_or_skip")]
+    #[schemars(with = "u16")]
     pub hp: Option<u16>,
+    #[serde_with(skip_apply)]
     pub base_draw: Option<bool>,
-    #[serde(default, with = "::serde_with::rust::unwrap_or_skip")]
+    #[schemars(with = "u32")]
     pub build_skill: Option<u32>,
     #[serde(default)]
     pub materials: Vec<(String, u32)>,
@@ -139,13 +170,20 @@ pub struct WallDepInput {
     pub mining_rewards: Vec<(String, u32)>,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
 #[serde(deny_unknown_fields)]
 pub struct SpecialTileDepInput {
     pub always_background: Option<bool>,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[serde_with::apply(
+    Option => #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        with = "::serde_with::rust::unwrap_or_skip"
+    )],
+)]
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
 #[serde(deny_unknown_fields)]
 pub struct ItemDepInput {
     pub item_kind: String,
@@ -155,14 +193,20 @@ pub struct ItemDepInput {
     #[serde(default)]
     pub quality_kind: gamedata::QualityKind,
     pub gen_weight: f32,
-    #[serde(default, with = "::serde_with::rust::unwrap_or_skip")]
+    #[schemars(with = "f32")]
     pub shop_weight: Option<f32>,
     #[serde(default)]
     pub gen_level: u32,
-    #[serde(default, with = "::serde_with::rust::unwrap_or_skip")]
+    #[schemars(with = "gamedata::WeaponKind")]
     pub weapon_kind: Option<gamedata::WeaponKind>,
-    #[serde(default, with = "::serde_with::rust::unwrap_or_skip")]
+    #[schemars(with = "gamedata::ArmorKind")]
     pub armor_kind: Option<gamedata::ArmorKind>,
+    #[serde(
+        default = "gamedata::ItemFlags::empty",
+        skip_serializing_if = "gamedata::ItemFlags::is_empty"
+    )]
+    #[schemars(with = "u64")]
+    pub default_flags: gamedata::ItemFlags,
     #[serde(default)]
     pub attrs: Vec<gamedata::ItemObjAttr>,
     #[serde(default)]
@@ -171,7 +215,7 @@ pub struct ItemDepInput {
     pub material: gamedata::MaterialName,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
 #[serde(deny_unknown_fields)]
 pub struct RegionGenDepInput {
     pub map_template_id: String,
@@ -179,14 +223,22 @@ pub struct RegionGenDepInput {
     pub others: Vec<SiteGenIdAndPos>,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
 #[serde(deny_unknown_fields)]
 pub struct SiteGenIdAndPos {
     pub id: String,
+    #[schemars(with = "(i32, i32)")]
     pub pos: Coords,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[serde_with::apply(
+    Option => #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        with = "::serde_with::rust::unwrap_or_skip"
+    )],
+)]
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
 #[serde(deny_unknown_fields)]
 pub struct SiteGenDepInput {
     pub kind: gamedata::site::SiteKind,
@@ -195,16 +247,61 @@ pub struct SiteGenDepInput {
     pub default_faction_id: FactionId,
     #[serde(default)]
     pub npcs: Vec<sitegen::NpcGenData>,
-    #[serde(default)]
+    /// A map, or a sequence of pairs for formats which cannot have non-string keys
+    #[serde(default, deserialize_with = "map_or_pairs")]
     pub shops: HashMap<NpcGenId, sitegen::ShopGenData>,
     #[serde(default)]
     pub quests: Vec<sitegen::QuestGenData>,
-    #[serde(default, with = "::serde_with::rust::unwrap_or_skip")]
+    #[schemars(with = "(u32, (i32, i32), String)")]
     pub delivery_chest: Option<(u32, Coords, String)>,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
 #[serde(deny_unknown_fields)]
 pub struct ScriptDepInput {
     pub script: String,
 }
+
+/// Deserialize a map from a map, or a sequence of key-value pairs.
+/// Maps which have non-string keys cannot be written in JSON and TOML.
+fn map_or_pairs<'de, D, K, V>(deserializer: D) -> Result<HashMap<K, V>, D::Error>
+where
+    D: serde::Deserializer<'de>,
+    K: serde::Deserialize<'de> + Eq + std::hash::Hash,
+    V: serde::Deserialize<'de>,
+{
+    use serde::de::{MapAccess, SeqAccess, Visitor};
+    use std::marker::PhantomData;
+
+    struct MapVisitor<K, V>(PhantomData<(K, V)>);
+
+    impl<'de, K, V> Visitor<'de> for MapVisitor<K, V>
+    where
+        K: serde::Deserialize<'de> + Eq + std::hash::Hash,
+        V: serde::Deserialize<'de>,
+    {
+        type Value = HashMap<K, V>;
+
+        fn expecting(&self, formatter: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+            formatter.write_str("a map or a sequence of key-value pairs")
+        }
+
+        fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<Self::Value, A::Error> {
+            let mut m = HashMap::default();
+            while let Some((k, v)) = map.next_entry()? {
+                m.insert(k, v);
+            }
+            Ok(m)
+        }
+
+        fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<Self::Value, A::Error> {
+            let mut m = HashMap::default();
+            while let Some((k, v)) = seq.next_element()? {
+                m.insert(k, v);
+            }
+            Ok(m)
+        }
+    }
+
+    deserializer.deserialize_any(MapVisitor(PhantomData))
+}
@@ -8,37 +8,122 @@
 #[macro_use]
 extern crate serde_derive;
 extern crate rusted_ruins_common as common;
+extern crate rusted_ruins_rules as rules;
 extern crate tile_geom as geom;
 
 mod verbose;
 #[macro_use]
 mod input;
+mod atlas;
 mod buildobj;
+mod check;
 mod compile;
 mod dir;
 mod error;
+mod extract;
+mod info;
+mod manifest;
 mod pyscript;
+mod schema;
 
-use clap::Parser;
+use clap::{Parser, Subcommand};
 use std::path::PathBuf;
 
 #[derive(Parser, Debug)]
-#[clap(author, version, about)]
+#[clap(author, version, about, args_conflicts_with_subcommands = true)]
 struct Args {
+    #[clap(subcommand)]
+    command: Option<Command>,
     input_files: Vec<PathBuf>,
     #[clap(short, long)]
     output: Option<PathBuf>,
+    /// Print objects in the given pak files instead of compiling
     #[clap(long)]
     info: bool,
+    /// Print information as JSON. Used with --info
+    #[clap(long)]
+    json: bool,
+    /// Pack images of the same object type into atlases
+    #[clap(long)]
+    atlas: bool,
+    /// Store compiled bytecode of scripts in the pak
+    #[clap(long)]
+    bytecode: bool,
     #[clap(long)]
     verbose: bool,
 }
 
+#[derive(Subcommand, Debug)]
+enum Command {
+    /// Build a pak file from a manifest. Only changed input files are compiled
+    Build {
+        /// Manifest file
+        manifest: PathBuf,
+    },
+    /// Check that all objects referred by paks and rules exist, and ids are not duplicated
+    Check {
+        /// Pak files or directories including pak files
+        #[clap(required = true)]
+        paks: Vec<PathBuf>,
+        /// Rule directories
+        #[clap(short, long)]
+        rules: Vec<PathBuf>,
+    },
+    /// Extract objects in pak files to input files and images
+    Extract {
+        /// Pak files
+        #[clap(required = true)]
+        paks: Vec<PathBuf>,
+        /// Output directory
+        #[clap(short, long, default_value = ".")]
+        output: PathBuf,
+    },
+    /// Write JSON Schema of input files and rule files
+    Schema {
+        /// Output directory
+        #[clap(short, long, default_value = ".")]
+        output: PathBuf,
+    },
+}
+
 fn main() {
     let args = Args::parse();
 
     verbose::set_verbose(args.verbose);
 
+    match &args.command {
+        Some(Command::Build { manifest }) => {
+            if let Err(e) = manifest::build(manifest, args.bytecode) {
+                eprintln!("{e}");
+                std::process::exit(1);
+            }
+            return;
+        }
+        Some(Command::Check { paks, rules }) => {
+            if let Err(e) = check::check(paks, rules) {
+                eprintln!("{e}");
+                std::process::exit(1);
+            }
+            println!("No errors found");
+            return;
+        }
+        Some(Command::Extract { paks, output }) => {
+            if let Err(e) = extract::extract(paks, output) {
+                eprintln!("{e}");
+                std::process::exit(1);
+            }
+            return;
+        }
+        Some(Command::Schema { output }) => {
+            if let Err(e) = schema::export_schema(output) {
+                eprintln!("{e}");
+                std::process::exit(1);
+            }
+            return;
+        }
+        None => (),
+    }
+
     if args.input_files.is_empty() {
         let _ = <Args as clap::CommandFactory>::command().print_help();
         return;
@@ -46,7 +131,11 @@ fn main() {
 
     // Print information of pak files
     if args.info {
-        print_info(&args.input_files);
+        if let Err(e) = info::print_info(&args.input_files, args.json) {
+            eprintln!("{e}");
+            std::process::exit(1);
+        }
+        return;
     }
 
     let output_file = args.output.unwrap_or_else(|| {
@@ -55,9 +144,23 @@ fn main() {
         path
     });
 
-    compile::compile(&args.input_files, &output_file);
+    if let Err(e) = compile::compile(&args.input_files, &output_file, args.atlas, args.bytecode) {
+        eprintln!("{e}");
+        std::process::exit(1);
+    }
 }
 
-fn print_info(files: &[PathBuf]) {
-    println!("Information of {files:?} will be printed");
+/// Create an empty directory for tests
+#[cfg(test)]
+pub(crate) fn test_dir(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!(
+        "rusted-ruins-makepak-test-{}-{}",
+        name,
+        std::process::id()
+    ));
+    if dir.exists() {
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+    std::fs::create_dir_all(&dir).unwrap();
+    dir
 }
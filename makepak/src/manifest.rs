@@ -0,0 +1,422 @@
+//! Build a pak file from a manifest.
+//!
+//! Hashes of input files are saved to a cache file next to the output pak.
+//! Objects whose inputs are unchanged are copied from the previous output without compiling.
+
+use crate::atlas::{pack_atlases, ATLAS_ID_PREFIX};
+use crate::compile::{compile_file, parse_input, print_file_error, write_pak};
+use crate::verbose::print_verbose;
+use anyhow::{bail, Context, Result};
+use common::hashmap::HashMap;
+use common::pakutil::{PakManifest, PAK_MANIFEST_ENTRY};
+use regex::Regex;
+use std::fs;
+use std::hash::Hasher;
+use std::io::Read;
+use std::path::{Path, PathBuf};
+
+/// Manifest file to describe a pak
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Manifest {
+    pub name: String,
+    pub version: String,
+    /// Glob patterns of input files, relative to the manifest directory.
+    /// `*` and `?` do not match `/`, and `**/` matches any directories.
+    pub include: Vec<String>,
+    /// Output pak file path, relative to the manifest directory
+    pub output: PathBuf,
+    /// Paks which must be loaded before this pak
+    #[serde(default)]
+    pub depends: Vec<String>,
+    /// Paks which are loaded before this pak if they exist
+    #[serde(default)]
+    pub load_after: Vec<String>,
+    /// Ids of objects which replace objects with the same id in other paks
+    #[serde(default)]
+    pub overrides: Vec<String>,
+    /// Store compiled bytecode of scripts
+    #[serde(default)]
+    pub bytecode: bool,
+    /// Pack images of the same object type into atlases
+    #[serde(default)]
+    pub atlas: bool,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct BuildCache {
+    makepak_version: String,
+    bytecode: bool,
+    atlas: bool,
+    /// Input file path relative to the manifest directory to its hash and object id
+    files: HashMap<PathBuf, CacheEntry>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct CacheEntry {
+    hash: String,
+    id: String,
+}
+
+/// Build the pak of the manifest.
+/// Compiled bytecode of scripts is stored if `bytecode` or the manifest option is true.
+/// Returns the number of compiled objects.
+pub fn build(manifest_file: &Path, bytecode: bool) -> Result<usize> {
+    let s = fs::read_to_string(manifest_file)
+        .with_context(|| format!("cannot read \"{}\"", manifest_file.to_string_lossy()))?;
+    let manifest: Manifest = ron::de::from_str(&s)
+        .with_context(|| format!("invalid manifest \"{}\"", manifest_file.to_string_lossy()))?;
+    let base_dir = manifest_file.parent().unwrap_or_else(|| Path::new(""));
+
+    let mut files = expand_globs(base_dir, &manifest.include)?;
+    files.retain(|f| f != manifest_file);
+    if files.is_empty() {
+        bail!("no input files for \"{}\"", manifest.name);
+    }
+
+    let output_file = base_dir.join(&manifest.output);
+    let cache_file = output_file.with_extension("cache");
+    let bytecode = bytecode || manifest.bytecode;
+    let old_cache = load_cache(&cache_file, &manifest, bytecode);
+    let mut old_objs = if old_cache.files.is_empty() {
+        HashMap::default()
+    } else {
+        read_pak_data(&output_file).unwrap_or_default()
+    };
+
+    let mut cache = BuildCache {
+        makepak_version: env!("CARGO_PKG_VERSION").into(),
+        bytecode,
+        atlas: manifest.atlas,
+        files: HashMap::default(),
+    };
+    let mut objs = Vec::new();
+    let mut n_errors = 0;
+    let mut n_compiled = 0;
+
+    for f in &files {
+        let rel_path = f.strip_prefix(base_dir).unwrap_or(f).to_owned();
+        let hash = match hash_input(f) {
+            Ok(hash) => hash,
+            Err(e) => {
+                print_file_error(f, &e);
+                n_errors += 1;
+                continue;
+            }
+        };
+
+        let cached = old_cache
+            .files
+            .get(&rel_path)
+            .filter(|entry| entry.hash == hash)
+            .and_then(|entry| {
+                old_objs
+                    .remove(&entry.id)
+                    .map(|data| (entry.id.clone(), data))
+            });
+
+        let (id, data) = if let Some(obj) = cached {
+            print_verbose(|| format!("Unchanged \"{}\"", f.to_string_lossy()));
+            obj
+        } else {
+            match compile_file(f, bytecode) {
+                Ok(obj) => {
+                    n_compiled += 1;
+                    obj
+                }
+                Err(e) => {
+                    print_file_error(f, &e);
+                    n_errors += 1;
+                    continue;
+                }
+            }
+        };
+
+        cache.files.insert(
+            rel_path,
+            CacheEntry {
+                hash,
+                id: id.clone(),
+            },
+        );
+        objs.push((id, data));
+    }
+
+    if n_errors > 0 {
+        bail!(
+            "{} errors occurred, \"{}\" was not written",
+            n_errors,
+            output_file.to_string_lossy()
+        );
+    }
+
+    let objs = if manifest.atlas {
+        // Unchanged objects may refer to atlases in the previous output
+        let mut old_atlases: Vec<(String, Vec<u8>)> = old_objs
+            .into_iter()
+            .filter(|(id, _)| id.starts_with(ATLAS_ID_PREFIX))
+            .collect();
+        old_atlases.sort();
+        objs.extend(old_atlases);
+        pack_atlases(&manifest.name, objs)?
+    } else {
+        objs
+    };
+
+    if let Some(parent) = output_file.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    let pak_manifest = PakManifest {
+        name: manifest.name.clone(),
+        version: manifest.version.clone(),
+        depends: manifest.depends.clone(),
+        load_after: manifest.load_after.clone(),
+        overrides: manifest.overrides.clone(),
+    };
+    write_pak(&output_file, Some(&pak_manifest), &objs)?;
+    let s = ron::ser::to_string_pretty(&cache, ron::ser::PrettyConfig::default())?;
+    fs::write(&cache_file, s)?;
+
+    println!(
+        "{} {}: {} objects ({} compiled)",
+        manifest.name,
+        manifest.version,
+        objs.len(),
+        n_compiled
+    );
+    Ok(n_compiled)
+}
+
+/// Load the cache file.
+/// Returns an empty cache if it is missing or created by another version or options.
+fn load_cache(cache_file: &Path, manifest: &Manifest, bytecode: bool) -> BuildCache {
+    let cache: Option<BuildCache> = fs::read_to_string(cache_file)
+        .ok()
+        .and_then(|s| ron::de::from_str(&s).ok());
+    match cache {
+        Some(cache)
+            if cache.makepak_version == env!("CARGO_PKG_VERSION")
+                && cache.bytecode == bytecode
+                && cache.atlas == manifest.atlas =>
+        {
+            cache
+        }
+        _ => BuildCache::default(),
+    }
+}
+
+/// Read serialized objects in a pak file without deserializing them
+fn read_pak_data(pak: &Path) -> Result<HashMap<String, Vec<u8>>> {
+    let mut archive = tar::Archive::new(fs::File::open(pak)?);
+    let mut objs = HashMap::default();
+    for entry in archive.entries()? {
+        let mut entry = entry?;
+        let id = entry.path()?.to_string_lossy().into_owned();
+        if id == PAK_MANIFEST_ENTRY {
+            continue;
+        }
+        let mut data = Vec::new();
+        entry.read_to_end(&mut data)?;
+        objs.insert(id, data);
+    }
+    Ok(objs)
+}
+
+/// Hash an input file, including the image file it refers to
+fn hash_input(f: &Path) -> Result<String> {
+    let s = fs::read(f)?;
+    let mut hasher = fnv::FnvHasher::default();
+    hasher.write(&s);
+
+    if f.extension().is_some_and(|e| e != "py") {
+        // Hash of invalid input files does not matter because compiling them will fail
+        let input = std::str::from_utf8(&s)
+            .ok()
+            .and_then(|s| parse_input(f, s).ok());
+        if let Some(img) = input.and_then(|input| input.image) {
+            let img_path = f.parent().unwrap_or_else(|| Path::new("")).join(img.path);
+            hasher.write_u8(0);
+            hasher.write(&fs::read(&img_path).with_context(|| {
+                format!("cannot read image \"{}\"", img_path.to_string_lossy())
+            })?);
+        }
+    }
+
+    Ok(format!("{:016x}", hasher.finish()))
+}
+
+/// Get files matched with glob patterns. Results are sorted and deduplicated.
+fn expand_globs(base_dir: &Path, patterns: &[String]) -> Result<Vec<PathBuf>> {
+    let regexes = patterns
+        .iter()
+        .map(|pattern| glob_to_regex(pattern))
+        .collect::<Result<Vec<Regex>>>()?;
+
+    let mut files = Vec::new();
+    walk_dir(base_dir, "", &regexes, &mut files)?;
+    files.sort();
+    files.dedup();
+    Ok(files)
+}
+
+fn walk_dir(dir: &Path, rel: &str, regexes: &[Regex], files: &mut Vec<PathBuf>) -> Result<()> {
+    let dir_path = if dir.as_os_str().is_empty() {
+        Path::new(".")
+    } else {
+        dir
+    };
+    for entry in fs::read_dir(dir_path)? {
+        let entry = entry?;
+        let name = entry.file_name();
+        let name = name.to_string_lossy();
+        let rel = if rel.is_empty() {
+            name.into_owned()
+        } else {
+            format!("{rel}/{name}")
+        };
+        let path = dir.join(entry.file_name());
+        if entry.file_type()?.is_dir() {
+            walk_dir(&path, &rel, regexes, files)?;
+        } else if regexes.iter().any(|re| re.is_match(&rel)) {
+            files.push(path);
+        }
+    }
+    Ok(())
+}
+
+fn glob_to_regex(pattern: &str) -> Result<Regex> {
+    let mut re = String::from("^");
+    let mut rest = pattern;
+    while let Some(c) = rest.chars().next() {
+        if let Some(r) = rest.strip_prefix("**/") {
+            re.push_str("(?:.*/)?");
+            rest = r;
+            continue;
+        }
+        match c {
+            '*' => re.push_str("[^/]*"),
+            '?' => re.push_str("[^/]"),
+            _ => re.push_str(&regex::escape(&c.to_string())),
+        }
+        rest = &rest[c.len_utf8()..];
+    }
+    re.push('$');
+    Ok(Regex::new(&re)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_dir;
+
+    fn write_item(dir: &Path, id: &str) {
+        let input = format!(
+            r#"(
+                object_type: "item",
+                id: "{id}",
+                image: (path: "{id}.png"),
+                item: (
+                    item_kind: "object",
+                    group: "misc",
+                    basic_price: 10,
+                    w: 100,
+                    gen_weight: 1.0,
+                ),
+            )"#
+        );
+        fs::write(dir.join(format!("{id}.ron")), input).unwrap();
+        image::RgbImage::new(2, 2)
+            .save(dir.join(format!("{id}.png")))
+            .unwrap();
+    }
+
+    fn write_manifest(dir: &Path, atlas: bool) -> PathBuf {
+        let manifest = format!(
+            r#"(
+                name: "test",
+                version: "0.1.0",
+                include: ["*.ron"],
+                output: "out/test.pak",
+                atlas: {atlas},
+            )"#
+        );
+        let path = dir.join("manifest.ron");
+        fs::write(&path, manifest).unwrap();
+        path
+    }
+
+    #[test]
+    fn build_cache() {
+        let dir = test_dir("build-cache");
+        write_item(&dir, "apple");
+        write_item(&dir, "orange");
+        let manifest = write_manifest(&dir, false);
+        let pak = dir.join("out/test.pak");
+
+        assert_eq!(build(&manifest, false).unwrap(), 2);
+        let first = fs::read(&pak).unwrap();
+        assert!(dir.join("out/test.cache").exists());
+
+        // Unchanged objects are reused
+        assert_eq!(build(&manifest, false).unwrap(), 0);
+        assert_eq!(fs::read(&pak).unwrap(), first);
+
+        // Changing the referenced image recompiles the object
+        image::RgbImage::new(3, 3)
+            .save(dir.join("apple.png"))
+            .unwrap();
+        assert_eq!(build(&manifest, false).unwrap(), 1);
+        assert_eq!(build(&manifest, false).unwrap(), 0);
+
+        // Changing options invalidates the cache
+        assert_eq!(build(&manifest, true).unwrap(), 2);
+        assert_eq!(build(&manifest, true).unwrap(), 0);
+        let manifest = write_manifest(&dir, true);
+        assert_eq!(build(&manifest, true).unwrap(), 2);
+        assert_eq!(build(&manifest, true).unwrap(), 0);
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn build_error() {
+        let dir = test_dir("build-error");
+        write_item(&dir, "apple");
+        fs::write(dir.join("broken.ron"), "(").unwrap();
+        let manifest = write_manifest(&dir, false);
+
+        // Nothing is written if the first build fails
+        assert!(build(&manifest, false).is_err());
+        assert!(!dir.join("out/test.pak").exists());
+        assert!(!dir.join("out/test.cache").exists());
+
+        // The previous pak and cache are kept if a build fails
+        fs::remove_file(dir.join("broken.ron")).unwrap();
+        assert_eq!(build(&manifest, false).unwrap(), 1);
+        let pak = fs::read(dir.join("out/test.pak")).unwrap();
+        let cache = fs::read(dir.join("out/test.cache")).unwrap();
+        write_item(&dir, "orange");
+        fs::write(dir.join("broken.ron"), "(").unwrap();
+        assert!(build(&manifest, false).is_err());
+        assert_eq!(fs::read(dir.join("out/test.pak")).unwrap(), pak);
+        assert_eq!(fs::read(dir.join("out/test.cache")).unwrap(), cache);
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn glob() {
+        let re = glob_to_regex("items/*.ron").unwrap();
+        assert!(re.is_match("items/apple.ron"));
+        assert!(!re.is_match("items/food/apple.ron"));
+        assert!(!re.is_match("items/apple.png"));
+
+        let re = glob_to_regex("**/*.py").unwrap();
+        assert!(re.is_match("a.py"));
+        assert!(re.is_match("script/quest/a.py"));
+
+        let re = glob_to_regex("tile?.ron").unwrap();
+        assert!(re.is_match("tile1.ron"));
+        assert!(!re.is_match("tile10.ron"));
+    }
+}
@@ -3,6 +3,7 @@ use anyhow::*;
 use common::obj::{Object, ScriptObject};
 use once_cell::sync::Lazy;
 use regex::Regex;
+use rustpython_compiler::{CompileOpts, Mode};
 use std::fs::File;
 use std::io::{BufRead, BufReader, Read, Seek};
 use std::path::Path;
@@ -11,8 +12,29 @@ static FIRST_LINE: Lazy<Regex> = Lazy::new(|| Regex::new("# rusted-ruins-script"
 static ID_LINE: Lazy<Regex> =
     Lazy::new(|| Regex::new("# id = \"([a-zA-Z!][a-zA-Z0-9_.-]*)\"").unwrap());
 
+/// Compile the script to find syntax errors. Returns serialized bytecode if `bytecode` is true.
+pub fn compile_script(id: &str, script: &str, bytecode: bool) -> Result<Option<Vec<u8>>> {
+    let code =
+        rustpython_compiler::compile(script, Mode::Exec, id.to_owned(), CompileOpts::default())
+            .map_err(|e| {
+                anyhow!(
+                    "syntax error in script \"{}\" at line {}, column {}: {}",
+                    id,
+                    e.location.row(),
+                    e.location.column(),
+                    e.error
+                )
+            })?;
+
+    if bytecode {
+        Ok(Some(code.to_bytes()))
+    } else {
+        Ok(None)
+    }
+}
+
 /// Read python script file
-pub fn read_pyscript<P: AsRef<Path>>(path: P) -> Result<Object> {
+pub fn read_pyscript<P: AsRef<Path>>(path: P, bytecode: bool) -> Result<Object> {
     let path = path.as_ref();
     let mut f = BufReader::new(File::open(path)?);
     print_verbose(|| format!("Processing \"{path:?}\""));
@@ -48,5 +70,73 @@ pub fn read_pyscript<P: AsRef<Path>>(path: P) -> Result<Object> {
     let mut script = String::new();
     f.read_to_string(&mut script)?;
 
-    Ok(Object::Script(ScriptObject { id, script }))
+    let byte_code = compile_script(&id, &script, bytecode)?;
+
+    Ok(Object::Script(ScriptObject {
+        id,
+        script,
+        byte_code,
+    }))
+}
+
+/// Returns true if the script can be written as a python script file named "<id>.py",
+/// and read by `read_pyscript()` with the same id and content.
+pub fn is_pyscript_source(id: &str, script: &str) -> bool {
+    let mut lines = script.split_inclusive('\n');
+    let first_line = lines.next().unwrap_or_default();
+    if !FIRST_LINE.is_match(first_line) {
+        return false;
+    }
+    let second_line = lines.next().unwrap_or_default();
+    if let Some(caps) = ID_LINE.captures(second_line) {
+        caps.get(1).unwrap().as_str() == id
+    } else {
+        !id.contains(['/', '\\'])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_dir;
+
+    const SCRIPT: &str = "# rusted-ruins-script\n# id = \"test-script\"\nx = 1\n";
+
+    #[test]
+    fn syntax_error() {
+        let script = "# rusted-ruins-script\nx = 1\ny = = 2\n";
+        let e = compile_script("broken", script, false)
+            .unwrap_err()
+            .to_string();
+        assert!(e.starts_with("syntax error in script \"broken\" at line 3, column "));
+    }
+
+    #[test]
+    fn bytecode() {
+        assert!(compile_script("test-script", SCRIPT, false)
+            .unwrap()
+            .is_none());
+        assert!(compile_script("test-script", SCRIPT, true)
+            .unwrap()
+            .is_some());
+    }
+
+    #[test]
+    fn read_script_file() {
+        let dir = test_dir("pyscript");
+        let path = dir.join("a.py");
+        std::fs::write(&path, SCRIPT).unwrap();
+
+        for bytecode in [false, true] {
+            let obj = match read_pyscript(&path, bytecode).unwrap() {
+                Object::Script(obj) => obj,
+                _ => panic!("not a script object"),
+            };
+            assert_eq!(obj.id, "test-script");
+            assert_eq!(obj.script, SCRIPT);
+            assert_eq!(obj.byte_code.is_some(), bytecode);
+        }
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
 }
@@ -0,0 +1,33 @@
+//! Export JSON Schema of input files and rule files.
+//!
+//! Files are written to "<output>/input.schema.json" and "<output>/rules/<rule>.schema.json".
+//! Doc comments of the types are used as descriptions.
+
+use crate::input::Input;
+use crate::verbose::print_verbose;
+use anyhow::Result;
+use common::schema::root_schema_for;
+use schemars::schema::RootSchema;
+use std::fs;
+use std::path::Path;
+
+pub fn export_schema(output_dir: &Path) -> Result<()> {
+    let rules_dir = output_dir.join("rules");
+    fs::create_dir_all(&rules_dir)?;
+
+    write_schema(
+        &output_dir.join("input.schema.json"),
+        &root_schema_for::<Input>(),
+    )?;
+    for (name, schema) in rules::schema::rule_schemas() {
+        write_schema(&rules_dir.join(format!("{name}.schema.json")), &schema)?;
+    }
+    Ok(())
+}
+
+fn write_schema(path: &Path, schema: &RootSchema) -> Result<()> {
+    print_verbose(|| format!("writing \"{}\"", path.display()));
+    let file = fs::File::create(path)?;
+    serde_json::to_writer_pretty(file, schema)?;
+    Ok(())
+}
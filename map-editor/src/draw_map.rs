@@ -87,7 +87,7 @@ pub fn draw_map(
                 for item in &map.items[p] {
                     let item_idx: ItemIdx = gobj::id_to_idx(&item.id);
                     let item_obj = gobj::get_obj(item_idx);
-                    let pixbuf = &pbh.get(item_idx).image;
+                    let pixbuf = &pbh.get(item_idx).texture;
                     let (x, y, w, h) = item_obj.img_rect_nth(0);
                     image_copy(
                         cr,
@@ -113,7 +113,7 @@ fn draw_pieces(
     ix: i32,
     iy: i32,
 ) {
-    let image = &pbh.get(idx).image;
+    let image = &pbh.get(idx).texture;
     let tile_obj = gobj::get_obj(idx);
 
     // Top left piece
@@ -178,7 +178,7 @@ fn draw_wall_pieces(
     ix: i32,
     iy: i32,
 ) {
-    let image = &pbh.get(idx).image;
+    let image = &pbh.get(idx).texture;
     let wall_obj = gobj::get_obj(idx);
     let h = wall_obj.get_img().h as i32 - TILE_SIZE_I;
 
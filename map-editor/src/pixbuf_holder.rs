@@ -1,4 +1,5 @@
 use common::gobj;
+use common::hashmap::HashMap;
 use common::obj::Img;
 use common::objholder::*;
 use gdk_pixbuf::{prelude::PixbufLoaderExt, Pixbuf, PixbufLoader};
@@ -8,6 +9,8 @@ pub struct PixbufSet {
     pub image: Pixbuf,
     /// Clipped image used to icon
     pub icon: Pixbuf,
+    /// Source of rects given by the object. The atlas if the image is packed into it.
+    pub texture: Pixbuf,
 }
 
 macro_rules! impl_pixbuf_holder {
@@ -24,10 +27,11 @@ macro_rules! impl_pixbuf_holder {
                 let mut pbh = PixbufHolder {
                     $($mem: Vec::new()),*
                 };
+                let mut atlases = HashMap::default();
 
                 $(
                     for ref o in &objholder.$mem {
-                        let pixbuf = load_png(&o.img);
+                        let pixbuf = load_img(&o.img, &mut atlases);
                         pbh.$mem.push(pixbuf);
                     }
                 )*
@@ -56,12 +60,30 @@ impl_pixbuf_holder! {
     {item, ItemIdx}
 }
 
-fn load_png(img: &Img) -> PixbufSet {
-    const ERR_MSG: &str = "Error occured while loading image";
-    let loader = PixbufLoader::with_type("png").expect(ERR_MSG);
-    loader.write(&img.data).expect(ERR_MSG);
-    loader.close().expect(ERR_MSG);
-    let pixbuf = loader.pixbuf().expect(ERR_MSG);
+fn load_img(img: &Img, atlases: &mut HashMap<String, Pixbuf>) -> PixbufSet {
+    let (texture, pixbuf) = if let Some(atlas) = &img.atlas {
+        let texture = atlases
+            .entry(atlas.id.clone())
+            .or_insert_with(|| {
+                let data = &gobj::get_objholder()
+                    .atlas
+                    .get(&atlas.id)
+                    .unwrap_or_else(|| panic!("atlas \"{}\" is not found", atlas.id))
+                    .data;
+                load_png(data)
+            })
+            .clone();
+        let pixbuf = texture.new_subpixbuf(
+            atlas.x as i32,
+            atlas.y as i32,
+            (img.w * img.grid_nx) as i32,
+            (img.h * img.grid_ny) as i32,
+        );
+        (texture, pixbuf)
+    } else {
+        let pixbuf = load_png(&img.data);
+        (pixbuf.clone(), pixbuf)
+    };
 
     let pixbuf_icon = if img.grid_nx == 1 && img.grid_ny == 1 {
         pixbuf.clone()
@@ -72,5 +94,14 @@ fn load_png(img: &Img) -> PixbufSet {
     PixbufSet {
         image: pixbuf,
         icon: pixbuf_icon,
+        texture,
     }
 }
+
+fn load_png(data: &[u8]) -> Pixbuf {
+    const ERR_MSG: &str = "Error occured while loading image";
+    let loader = PixbufLoader::with_type("png").expect(ERR_MSG);
+    loader.write(data).expect(ERR_MSG);
+    loader.close().expect(ERR_MSG);
+    loader.pixbuf().expect(ERR_MSG)
+}
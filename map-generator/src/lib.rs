@@ -12,6 +12,7 @@ extern crate tile_geom as geom;
 
 use arrayvec::ArrayVec;
 use geom::*;
+use schemars::JsonSchema;
 use serde_derive::{Deserialize, Serialize};
 
 pub mod binary;
@@ -65,7 +66,7 @@ impl GeneratedMap {
     }
 }
 
-#[derive(Clone, PartialEq, Debug, Serialize, Deserialize)]
+#[derive(Clone, PartialEq, Debug, Serialize, Deserialize, JsonSchema)]
 pub enum MapGenParam {
     Flat {
         w: u32,
@@ -1,14 +1,24 @@
 use super::Rule;
+use crate::formula::PowerFormula;
+use crate::patch::{self, MapPatch};
+use anyhow::{Context, Result};
 use common::gamedata::*;
+use schemars::JsonSchema;
 use std::collections::{hash_map::Iter, HashMap};
+use std::path::Path;
 
-#[derive(Serialize, Deserialize, Default, Debug)]
+#[derive(Serialize, Deserialize, Default, Debug, JsonSchema)]
 #[serde(transparent)]
-pub struct Abilities(HashMap<AbilityId, Ability>);
+pub struct Abilities {
+    abilities: HashMap<AbilityId, Ability>,
+    /// Parsed formulas of `PowerCalcMethod::Custom`
+    #[serde(skip)]
+    formulas: HashMap<String, PowerFormula>,
+}
 
 impl Abilities {
     pub fn iter(&self) -> Iter<'_, AbilityId, Ability> {
-        self.0.iter()
+        self.abilities.iter()
     }
 }
 
@@ -16,14 +26,37 @@ impl Rule for Abilities {
     const NAME: &'static str = "abilities";
 
     fn append(&mut self, other: Self) {
-        for (k, v) in other.0.into_iter() {
-            self.0.insert(k, v);
+        for (k, v) in other.abilities.into_iter() {
+            self.abilities.insert(k, v);
+        }
+    }
+
+    fn patch(&mut self, path: &Path) -> Result<()> {
+        patch::read::<MapPatch<AbilityId, Ability>>(path)?.apply(&mut self.abilities);
+        Ok(())
+    }
+
+    fn on_loaded(&mut self) -> Result<()> {
+        self.formulas.clear();
+        for (id, ability) in &self.abilities {
+            if let PowerCalcMethod::Custom(s) = &ability.power_calc {
+                let formula = s
+                    .parse()
+                    .with_context(|| format!("invalid formula \"{}\" in ability \"{}\"", s, id))?;
+                self.formulas.insert(s.clone(), formula);
+            }
         }
+        Ok(())
     }
 }
 
 impl Abilities {
     pub fn get(&self, id: &AbilityId) -> Option<&Ability> {
-        self.0.get(id)
+        self.abilities.get(id)
+    }
+
+    /// Get the parsed formula of `PowerCalcMethod::Custom`
+    pub fn formula(&self, s: &str) -> Option<&PowerFormula> {
+        self.formulas.get(s)
     }
 }
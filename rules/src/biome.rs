@@ -1,11 +1,15 @@
 use super::Rule;
+use crate::patch::{self, MapPatch};
+use anyhow::Result;
 use common::gobj::ObjIdxAsId;
 use common::objholder::*;
+use schemars::JsonSchema;
 use serde_with::serde_as;
 use std::collections::HashMap;
+use std::path::Path;
 
 /// Rules for wilderness map generation
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
 pub struct Biomes {
     pub biomes: HashMap<String, BiomeDetail>,
     pub sub_biomes: HashMap<String, SubBiomeDetail>,
@@ -22,20 +26,40 @@ impl Rule for Biomes {
             self.sub_biomes.insert(k, v);
         }
     }
+
+    fn patch(&mut self, path: &Path) -> Result<()> {
+        let patch: BiomesPatch = patch::read(path)?;
+        patch.biomes.apply(&mut self.biomes);
+        patch.sub_biomes.apply(&mut self.sub_biomes);
+        Ok(())
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct BiomesPatch {
+    #[serde(default)]
+    biomes: MapPatch<String, BiomeDetail>,
+    #[serde(default)]
+    sub_biomes: MapPatch<String, SubBiomeDetail>,
 }
 
 #[serde_as]
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
 pub struct BiomeDetail {
     #[serde_as(as = "ObjIdxAsId")]
+    #[schemars(with = "String")]
     pub tile: TileIdx,
     #[serde_as(as = "ObjIdxAsId")]
+    #[schemars(with = "String")]
     pub wall: WallIdx,
     #[serde_as(as = "Vec<(ObjIdxAsId, _)>")]
+    #[schemars(with = "Vec<(String, f32)>")]
     pub plants: Vec<(ItemIdx, f32)>,
     #[serde_as(as = "Vec<(ObjIdxAsId, _)>")]
+    #[schemars(with = "Vec<(String, f32)>")]
     pub items: Vec<(ItemIdx, f32)>,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
 pub struct SubBiomeDetail {}
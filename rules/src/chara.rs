@@ -1,7 +1,8 @@
 use super::Rule;
+use schemars::JsonSchema;
 
 /// Rules for character parameter calculation
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
 pub struct Chara {
     /// Default value of CharaParams::view_range.
     /// The actual value will be adjusted by character traits, and map attributes, etc.
@@ -56,7 +57,8 @@ pub struct Chara {
     pub damage_factor_stressed: f32,
     pub damage_factor_strained: f32,
     pub damage_factor_overloaded: f32,
-    /// Dafame factor for calculating poison damage
+    /// Damage factor for calculating poison damage.
+    /// Used by the built-in "poisoned" status effect.
     pub damage_factor_poisoned: f32,
 }
 
@@ -1,8 +1,13 @@
 use super::Rule;
+use crate::patch::{self, MapPatch};
+use crate::validate::ValidationReport;
+use anyhow::Result;
 use common::gamedata::CharaModifier;
+use schemars::JsonSchema;
 use std::collections::HashMap;
+use std::path::Path;
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
 #[serde(transparent)]
 pub struct CharaTraits(HashMap<String, CharaTrait>);
 
@@ -10,6 +15,19 @@ impl CharaTraits {
     pub fn get(&self, id: &str) -> &CharaTrait {
         &self.0[id]
     }
+
+    pub(crate) fn validate(&self, report: &mut ValidationReport) {
+        for (id, chara_trait) in &self.0 {
+            for conflict in &chara_trait.conflicts {
+                if !self.0.contains_key(conflict) {
+                    report.error(
+                        Self::NAME,
+                        format!("trait \"{id}\" conflicts with unknown trait \"{conflict}\""),
+                    );
+                }
+            }
+        }
+    }
 }
 
 impl Rule for CharaTraits {
@@ -20,10 +38,15 @@ impl Rule for CharaTraits {
             self.0.insert(k, v);
         }
     }
+
+    fn patch(&mut self, path: &Path) -> Result<()> {
+        patch::read::<MapPatch<String, CharaTrait>>(path)?.apply(&mut self.0);
+        Ok(())
+    }
 }
 
 /// Rules for character parameter calculation
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
 pub struct CharaTrait {
     #[serde(default)]
     pub cost: i32,
@@ -1,8 +1,9 @@
 use super::Rule;
 use common::gamedata::*;
+use schemars::JsonSchema;
 
 /// Rules for character generation
-#[derive(Serialize, Deserialize)]
+#[derive(Serialize, Deserialize, JsonSchema)]
 pub struct CharaGen {
     /// List of skills all character must have
     pub common_skills: Vec<SkillKind>,
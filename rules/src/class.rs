@@ -1,9 +1,13 @@
+use crate::patch::{self, MapPatch};
 use crate::Rule;
+use anyhow::Result;
 use common::basic::BonusLevel;
 use common::gamedata::*;
+use schemars::JsonSchema;
 use std::collections::HashMap;
+use std::path::Path;
 
-#[derive(Serialize, Deserialize)]
+#[derive(Serialize, Deserialize, JsonSchema)]
 #[serde(transparent)]
 pub struct Classes(HashMap<CharaClass, Class>);
 
@@ -15,6 +19,11 @@ impl Rule for Classes {
             self.0.insert(k, v);
         }
     }
+
+    fn patch(&mut self, path: &Path) -> Result<()> {
+        patch::read::<MapPatch<CharaClass, Class>>(path)?.apply(&mut self.0);
+        Ok(())
+    }
 }
 
 impl Classes {
@@ -26,7 +35,7 @@ impl Classes {
 }
 
 /// Rules for character generation
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
 pub struct Class {
     /// Attribute revisions by class
     pub attr: CharaAttrDiff,
@@ -1,7 +1,8 @@
 use super::Rule;
+use schemars::JsonSchema;
 
 /// Rules for calculation related to combat.
-#[derive(Serialize, Deserialize)]
+#[derive(Serialize, Deserialize, JsonSchema)]
 pub struct Combat {
     pub throw_range_factor: u32,
     pub throw_range_max: u32,
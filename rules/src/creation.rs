@@ -1,8 +1,9 @@
 use super::Rule;
 use common::gamedata::CreationRequiredTime;
+use schemars::JsonSchema;
 use std::collections::HashMap;
 
-#[derive(Serialize, Deserialize)]
+#[derive(Serialize, Deserialize, JsonSchema)]
 pub struct Creation {
     pub required_time: HashMap<CreationRequiredTime, u16>,
     pub recipe_learning_level_margin: u32,
@@ -1,12 +1,16 @@
+use crate::patch::{self, MapPatch};
 use crate::Rule;
+use anyhow::Result;
 use common::gamedata::*;
 use rusted_ruins_common::item_selector::ItemSelector;
+use schemars::JsonSchema;
 use std::collections::HashMap;
+use std::path::Path;
 
 /// Rules for map generation
 pub type DungeonGen = HashMap<DungeonKind, DungeonGenParams>;
 
-#[derive(Serialize, Deserialize)]
+#[derive(Serialize, Deserialize, JsonSchema)]
 pub struct DungeonGenParams {
     /// map symbol
     pub symbol: SiteSymbolKind,
@@ -42,4 +46,9 @@ impl Rule for DungeonGen {
             self.insert(k, v);
         }
     }
+
+    fn patch(&mut self, path: &Path) -> Result<()> {
+        patch::read::<MapPatch<DungeonKind, DungeonGenParams>>(path)?.apply(self);
+        Ok(())
+    }
 }
@@ -1,7 +1,8 @@
 use crate::Rule;
+use schemars::JsonSchema;
 
 /// Rules for effect processing
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
 pub struct Effect {
     pub item_drink_power_factor: f32,
     pub item_eat_power_factor: f32,
@@ -1,7 +1,8 @@
 use crate::Rule;
+use schemars::JsonSchema;
 
 /// Rules for exp calculation
-#[derive(Serialize, Deserialize)]
+#[derive(Serialize, Deserialize, JsonSchema)]
 pub struct Exp {
     /// Level difference (skill level - base level) for the first element of adjust_coeff
     pub begin_adjust_coeff: isize,
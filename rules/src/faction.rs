@@ -2,8 +2,9 @@ use common::gamedata::*;
 use std::collections::HashMap;
 
 use crate::Rule;
+use schemars::JsonSchema;
 
-#[derive(Serialize, Deserialize)]
+#[derive(Serialize, Deserialize, JsonSchema)]
 pub struct Faction {
     pub relation_friend: FactionRelation,
     pub relation_neutral: FactionRelation,
@@ -28,7 +29,7 @@ impl Faction {
 }
 
 /// Rules for character generation
-#[derive(Serialize, Deserialize)]
+#[derive(Serialize, Deserialize, JsonSchema)]
 pub struct FactionInfo {
     pub default_relation: FactionRelation,
     #[serde(default)]
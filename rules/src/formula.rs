@@ -0,0 +1,533 @@
+//! Arithmetic formulas for `PowerCalcMethod::Custom`.
+//!
+//! A formula consists of numbers, variables, dice like `2d6`, operators `+ - * / ^`,
+//! parentheses and functions `skill(Kind)`, `min(a, b)` and `max(a, b)`.
+//! `Custom("<power>; <hit>")` gives the formula of hit after `;`.
+//!
+//! Variables:
+//! - Character attributes: `str`, `vit`, `dex`, `int`, `wil`, `cha`, `spd`
+//! - Character level: `lv`
+//! - Base power of equipped weapons: `melee_power`, `ranged_power`
+
+use common::gamedata::SkillKind;
+use serde::de::IntoDeserializer;
+use serde::Deserialize;
+use std::fmt;
+use std::str::FromStr;
+
+/// Limit of nesting to prevent stack overflow
+const MAX_DEPTH: usize = 32;
+/// Limit of the number of tokens. Chains of binary operators are not limited by nesting
+const MAX_TOKENS: usize = 256;
+/// Limit of the number of dice in one dice term
+const MAX_DICE: u16 = 100;
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Variable {
+    Str,
+    Vit,
+    Dex,
+    Int,
+    Wil,
+    Cha,
+    Spd,
+    Lv,
+    MeleePower,
+    RangedPower,
+}
+
+impl Variable {
+    fn from_name(name: &str) -> Option<Variable> {
+        Some(match name {
+            "str" => Variable::Str,
+            "vit" => Variable::Vit,
+            "dex" => Variable::Dex,
+            "int" => Variable::Int,
+            "wil" => Variable::Wil,
+            "cha" => Variable::Cha,
+            "spd" => Variable::Spd,
+            "lv" => Variable::Lv,
+            "melee_power" => Variable::MeleePower,
+            "ranged_power" => Variable::RangedPower,
+            _ => return None,
+        })
+    }
+}
+
+/// Values of variables used in evaluation
+pub trait FormulaContext {
+    fn variable(&self, var: Variable) -> f32;
+    fn skill_level(&self, kind: SkillKind) -> f32;
+}
+
+#[derive(Debug)]
+pub struct FormulaError {
+    /// Byte position in the formula
+    pub pos: usize,
+    pub message: String,
+}
+
+impl fmt::Display for FormulaError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} at {}", self.message, self.pos)
+    }
+}
+
+impl std::error::Error for FormulaError {}
+
+fn error<T, S: Into<String>>(pos: usize, message: S) -> Result<T, FormulaError> {
+    Err(FormulaError {
+        pos,
+        message: message.into(),
+    })
+}
+
+#[derive(Clone, Debug)]
+pub struct Formula(Expr);
+
+impl Formula {
+    /// Evaluate this formula. Returns 0 if the result is not finite.
+    pub fn eval<C: FormulaContext + ?Sized>(&self, context: &C) -> f32 {
+        let value = self.0.eval(context);
+        if value.is_finite() {
+            value
+        } else {
+            0.0
+        }
+    }
+}
+
+impl FromStr for Formula {
+    type Err = FormulaError;
+
+    fn from_str(s: &str) -> Result<Formula, FormulaError> {
+        let tokens = tokenize(s)?;
+        if let Some(&(pos, _)) = tokens.get(MAX_TOKENS) {
+            return error(pos, "too long formula");
+        }
+        let mut parser = Parser {
+            tokens,
+            pos: 0,
+            end: s.len(),
+            depth: 0,
+        };
+        let expr = parser.expr()?;
+        if let Some(&(pos, ref token)) = parser.tokens.get(parser.pos) {
+            return error(pos, format!("unexpected {}", token));
+        }
+        Ok(Formula(expr))
+    }
+}
+
+/// Formulas of power and hit given by `PowerCalcMethod::Custom`
+#[derive(Clone, Debug)]
+pub struct PowerFormula {
+    pub power: Formula,
+    /// If None, hit is 1.0 as `PowerCalcMethod::Fixed`
+    pub hit: Option<Formula>,
+}
+
+impl FromStr for PowerFormula {
+    type Err = FormulaError;
+
+    fn from_str(s: &str) -> Result<PowerFormula, FormulaError> {
+        let (power, hit) = if let Some((power, hit)) = s.split_once(';') {
+            let offset = power.len() + 1;
+            let hit = hit.parse().map_err(|mut e: FormulaError| {
+                e.pos += offset;
+                e
+            })?;
+            (power, Some(hit))
+        } else {
+            (s, None)
+        };
+        Ok(PowerFormula {
+            power: power.parse()?,
+            hit,
+        })
+    }
+}
+
+#[derive(Clone, Debug)]
+enum Expr {
+    Number(f32),
+    Variable(Variable),
+    Skill(SkillKind),
+    Dice(u16, u16),
+    Neg(Box<Expr>),
+    Binary(BinOp, Box<Expr>, Box<Expr>),
+    Min(Box<Expr>, Box<Expr>),
+    Max(Box<Expr>, Box<Expr>),
+}
+
+#[derive(Clone, Copy, Debug)]
+enum BinOp {
+    Add,
+    Sub,
+    Mul,
+    Div,
+    Pow,
+}
+
+impl Expr {
+    fn eval<C: FormulaContext + ?Sized>(&self, context: &C) -> f32 {
+        match self {
+            Expr::Number(value) => *value,
+            Expr::Variable(var) => context.variable(*var),
+            Expr::Skill(kind) => context.skill_level(*kind),
+            Expr::Dice(n, x) => rng::roll_dice(*n, *x) as f32,
+            Expr::Neg(a) => -a.eval(context),
+            Expr::Binary(op, a, b) => {
+                let a = a.eval(context);
+                let b = b.eval(context);
+                match op {
+                    BinOp::Add => a + b,
+                    BinOp::Sub => a - b,
+                    BinOp::Mul => a * b,
+                    BinOp::Div => {
+                        if b == 0.0 {
+                            0.0
+                        } else {
+                            a / b
+                        }
+                    }
+                    BinOp::Pow => a.powf(b),
+                }
+            }
+            Expr::Min(a, b) => a.eval(context).min(b.eval(context)),
+            Expr::Max(a, b) => a.eval(context).max(b.eval(context)),
+        }
+    }
+}
+
+#[derive(Clone, PartialEq, Debug)]
+enum Token {
+    Number(f32),
+    Dice(u16, u16),
+    Ident(String),
+    Symbol(char),
+}
+
+impl fmt::Display for Token {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Token::Number(value) => write!(f, "number {}", value),
+            Token::Dice(n, x) => write!(f, "dice {}d{}", n, x),
+            Token::Ident(ident) => write!(f, "\"{}\"", ident),
+            Token::Symbol(c) => write!(f, "'{}'", c),
+        }
+    }
+}
+
+fn tokenize(s: &str) -> Result<Vec<(usize, Token)>, FormulaError> {
+    let mut tokens = Vec::new();
+    let mut chars = s.char_indices().peekable();
+
+    while let Some(&(start, c)) = chars.peek() {
+        if c.is_whitespace() {
+            chars.next();
+        } else if c.is_ascii_digit() || c == '.' {
+            let mut end = start;
+            while let Some(&(i, c)) = chars.peek() {
+                if !(c.is_ascii_digit() || c == '.') {
+                    break;
+                }
+                end = i + 1;
+                chars.next();
+            }
+            let number = &s[start..end];
+
+            // Dice like "2d6"
+            let is_dice =
+                s[end..].starts_with('d') && s[end + 1..].starts_with(|c: char| c.is_ascii_digit());
+            if is_dice {
+                chars.next();
+                let mut dice_end = end + 1;
+                while let Some(&(i, c)) = chars.peek() {
+                    if !c.is_ascii_digit() {
+                        break;
+                    }
+                    dice_end = i + 1;
+                    chars.next();
+                }
+                let n: u16 = number
+                    .parse()
+                    .or_else(|_| error(start, "invalid number of dice"))?;
+                let x: u16 = s[end + 1..dice_end]
+                    .parse()
+                    .or_else(|_| error(start, "invalid faces of dice"))?;
+                if n == 0 || n > MAX_DICE || x == 0 {
+                    return error(start, format!("invalid dice {}d{}", n, x));
+                }
+                tokens.push((start, Token::Dice(n, x)));
+            } else {
+                let value = number
+                    .parse()
+                    .or_else(|_| error(start, format!("invalid number \"{}\"", number)))?;
+                tokens.push((start, Token::Number(value)));
+            }
+        } else if c.is_ascii_alphabetic() || c == '_' {
+            let mut end = start;
+            while let Some(&(i, c)) = chars.peek() {
+                if !(c.is_ascii_alphanumeric() || c == '_') {
+                    break;
+                }
+                end = i + 1;
+                chars.next();
+            }
+            tokens.push((start, Token::Ident(s[start..end].to_owned())));
+        } else if "+-*/^(),".contains(c) {
+            chars.next();
+            tokens.push((start, Token::Symbol(c)));
+        } else {
+            return error(start, format!("unexpected character '{}'", c));
+        }
+    }
+
+    Ok(tokens)
+}
+
+struct Parser {
+    tokens: Vec<(usize, Token)>,
+    pos: usize,
+    /// Length of the source used for errors at the end
+    end: usize,
+    depth: usize,
+}
+
+impl Parser {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.pos).map(|(_, token)| token)
+    }
+
+    fn current_pos(&self) -> usize {
+        self.tokens
+            .get(self.pos)
+            .map(|(pos, _)| *pos)
+            .unwrap_or(self.end)
+    }
+
+    fn eat(&mut self, c: char) -> bool {
+        if self.peek() == Some(&Token::Symbol(c)) {
+            self.pos += 1;
+            true
+        } else {
+            false
+        }
+    }
+
+    fn expect(&mut self, c: char) -> Result<(), FormulaError> {
+        if self.eat(c) {
+            Ok(())
+        } else {
+            error(self.current_pos(), format!("expected '{}'", c))
+        }
+    }
+
+    fn expr(&mut self) -> Result<Expr, FormulaError> {
+        self.depth += 1;
+        if self.depth > MAX_DEPTH {
+            return error(self.current_pos(), "too deeply nested");
+        }
+
+        let mut expr = self.term()?;
+        loop {
+            let op = if self.eat('+') {
+                BinOp::Add
+            } else if self.eat('-') {
+                BinOp::Sub
+            } else {
+                break;
+            };
+            expr = Expr::Binary(op, Box::new(expr), Box::new(self.term()?));
+        }
+
+        self.depth -= 1;
+        Ok(expr)
+    }
+
+    fn term(&mut self) -> Result<Expr, FormulaError> {
+        let mut expr = self.unary()?;
+        loop {
+            let op = if self.eat('*') {
+                BinOp::Mul
+            } else if self.eat('/') {
+                BinOp::Div
+            } else {
+                break;
+            };
+            expr = Expr::Binary(op, Box::new(expr), Box::new(self.unary()?));
+        }
+        Ok(expr)
+    }
+
+    fn unary(&mut self) -> Result<Expr, FormulaError> {
+        if self.eat('-') {
+            self.depth += 1;
+            if self.depth > MAX_DEPTH {
+                return error(self.current_pos(), "too deeply nested");
+            }
+            let expr = Expr::Neg(Box::new(self.unary()?));
+            self.depth -= 1;
+            return Ok(expr);
+        }
+
+        let base = self.atom()?;
+        if self.eat('^') {
+            self.depth += 1;
+            if self.depth > MAX_DEPTH {
+                return error(self.current_pos(), "too deeply nested");
+            }
+            // Right associative
+            let exponent = self.unary()?;
+            self.depth -= 1;
+            return Ok(Expr::Binary(BinOp::Pow, Box::new(base), Box::new(exponent)));
+        }
+        Ok(base)
+    }
+
+    fn atom(&mut self) -> Result<Expr, FormulaError> {
+        let pos = self.current_pos();
+        let token = if let Some(token) = self.peek() {
+            token.clone()
+        } else {
+            return error(pos, "unexpected end of formula");
+        };
+        self.pos += 1;
+
+        match token {
+            Token::Number(value) => Ok(Expr::Number(value)),
+            Token::Dice(n, x) => Ok(Expr::Dice(n, x)),
+            Token::Symbol('(') => {
+                let expr = self.expr()?;
+                self.expect(')')?;
+                Ok(expr)
+            }
+            Token::Ident(ident) => {
+                if !self.eat('(') {
+                    return Variable::from_name(&ident)
+                        .map(Expr::Variable)
+                        .map_or_else(|| error(pos, format!("unknown variable \"{}\"", ident)), Ok);
+                }
+                let expr = match ident.as_str() {
+                    "skill" => {
+                        let arg_pos = self.current_pos();
+                        let kind = match self.peek() {
+                            Some(Token::Ident(kind)) => parse_skill_kind(kind),
+                            _ => None,
+                        };
+                        let kind = kind.map_or_else(|| error(arg_pos, "invalid skill kind"), Ok)?;
+                        self.pos += 1;
+                        Expr::Skill(kind)
+                    }
+                    "min" | "max" => {
+                        let a = Box::new(self.expr()?);
+                        self.expect(',')?;
+                        let b = Box::new(self.expr()?);
+                        if ident == "min" {
+                            Expr::Min(a, b)
+                        } else {
+                            Expr::Max(a, b)
+                        }
+                    }
+                    _ => return error(pos, format!("unknown function \"{}\"", ident)),
+                };
+                self.expect(')')?;
+                Ok(expr)
+            }
+            token => error(pos, format!("unexpected {}", token)),
+        }
+    }
+}
+
+/// Accepts both of the variant name like "BareHands" and the id like "bare_hands"
+fn parse_skill_kind(s: &str) -> Option<SkillKind> {
+    if s.starts_with('_') {
+        return None;
+    }
+    SkillKind::from_str(s).ok().or_else(|| {
+        let deserializer: serde::de::value::StrDeserializer<'_, serde::de::value::Error> =
+            s.into_deserializer();
+        SkillKind::deserialize(deserializer).ok()
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct Context;
+
+    impl FormulaContext for Context {
+        fn variable(&self, var: Variable) -> f32 {
+            match var {
+                Variable::Int => 10.0,
+                Variable::Lv => 3.0,
+                _ => 1.0,
+            }
+        }
+
+        fn skill_level(&self, kind: SkillKind) -> f32 {
+            if kind == SkillKind::Magic {
+                5.0
+            } else {
+                0.0
+            }
+        }
+    }
+
+    fn eval(s: &str) -> f32 {
+        s.parse::<Formula>().unwrap().eval(&Context)
+    }
+
+    #[test]
+    fn eval_formula() {
+        assert_eq!(eval("1 + 2 * 3"), 7.0);
+        assert_eq!(eval("(1 + 2) * 3"), 9.0);
+        assert_eq!(eval("-2 ^ 2"), -4.0);
+        assert_eq!(eval("2 ^ 3 ^ 2"), 512.0);
+        assert_eq!(eval("int * 2 + skill(Magic) - skill(magic)"), 20.0);
+        assert_eq!(eval("max(lv, 1.5) + min(lv, 1.5)"), 4.5);
+        assert_eq!(eval("1 / 0"), 0.0);
+        for _ in 0..10 {
+            let v = eval("(int*2 + skill(Magic))*1d6");
+            assert!((25.0..=150.0).contains(&v));
+        }
+    }
+
+    #[test]
+    fn invalid_formula() {
+        for s in [
+            "",
+            "1 +",
+            "(1",
+            "foo",
+            "skill(Foo)",
+            "skill(_DummyBasicSkill)",
+            "sqrt(2)",
+            "0d6",
+            "2d0",
+            "1 2",
+            "2dex",
+            "1 $ 2",
+        ] {
+            assert!(s.parse::<Formula>().is_err(), "{}", s);
+        }
+        assert!("(".repeat(100).parse::<Formula>().is_err());
+        assert!(format!("{}1", "-".repeat(100)).parse::<Formula>().is_err());
+        assert!(format!("{}1", "1^".repeat(100)).parse::<Formula>().is_err());
+        assert!(format!("{}1", "1^".repeat(10)).parse::<Formula>().is_ok());
+        assert!(format!("1{}", "+1".repeat(200000))
+            .parse::<Formula>()
+            .is_err());
+        assert!(format!("1{}", "+1".repeat(100)).parse::<Formula>().is_ok());
+
+        let e = "1; 2 +".parse::<PowerFormula>().unwrap_err();
+        assert_eq!(e.pos, 6);
+        assert!("int; dex + lv"
+            .parse::<PowerFormula>()
+            .unwrap()
+            .hit
+            .is_some());
+    }
+}
@@ -3,8 +3,9 @@ use std::collections::HashMap;
 use common::gamedata::*;
 
 use crate::Rule;
+use schemars::JsonSchema;
 
-#[derive(Serialize, Deserialize)]
+#[derive(Serialize, Deserialize, JsonSchema)]
 pub struct Item {
     pub quality_level_factor: u32,
     pub rotten_item: String,
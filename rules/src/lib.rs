@@ -11,6 +11,7 @@ extern crate serde_derive;
 extern crate log;
 extern crate rusted_ruins_common as common;
 extern crate rusted_ruins_map_generator as map_generator;
+extern crate rusted_ruins_rng as rng;
 extern crate tile_geom as geom;
 
 pub mod ability;
@@ -25,6 +26,7 @@ pub mod dungeon_gen;
 pub mod effect;
 pub mod exp;
 pub mod faction;
+pub mod formula;
 pub mod item;
 pub mod map_gen;
 pub mod material;
@@ -32,48 +34,78 @@ pub mod newgame;
 pub mod npc;
 pub mod npc_ai;
 pub mod params;
+pub mod patch;
 pub mod power;
 pub mod quest;
 pub mod race;
 pub mod recipe;
+pub mod removed_obj;
+pub mod schema;
+pub mod status_effect;
 pub mod town;
+pub mod validate;
 pub mod world;
 
 use anyhow::{anyhow, Context, Result};
+use common::reloadable::Reloadable;
 use once_cell::sync::Lazy;
 use serde::de::DeserializeOwned;
+use serde::Serialize;
 use std::fs;
 use std::path::{Path, PathBuf};
 use std::sync::Mutex;
 
-trait Rule: DeserializeOwned {
+trait Rule: Serialize + DeserializeOwned {
     const NAME: &'static str;
 
     fn load<P: AsRef<Path>>(rule_dirs: &[P]) -> Result<Self> {
+        Self::load_optional(rule_dirs)?
+            .ok_or_else(|| anyhow!("rule file not found for \"{}\" rule", Self::NAME))
+    }
+
+    /// Load rule. Returns None if there is no rule file.
+    /// Patch files in each directory are applied after rule files in the directory.
+    fn load_optional<P: AsRef<Path>>(rule_dirs: &[P]) -> Result<Option<Self>> {
+        Self::load_onto(None, rule_dirs)
+    }
+
+    /// Load rule files and patch files on top of the built-in rule
+    fn load_with_built_in<P: AsRef<Path>>(built_in: Self, rule_dirs: &[P]) -> Result<Self> {
+        Ok(Self::load_onto(Some(built_in), rule_dirs)?.unwrap())
+    }
+
+    fn load_onto<P: AsRef<Path>>(mut rule: Option<Self>, rule_dirs: &[P]) -> Result<Option<Self>> {
         info!("loading rule \"{}\"", Self::NAME);
 
-        let mut rule: Option<Self> = None;
         for rule_dir in rule_dirs {
             let rule_dir = rule_dir.as_ref();
             let d = rule_dir.join(Self::NAME);
+            let mut rule_files = Vec::new();
+            let mut patch_files = Vec::new();
 
             if d.exists() && d.is_dir() {
                 for entry in d.read_dir()? {
-                    let rule_file = entry?.path();
-                    let r = Self::from_file(&rule_file)
-                        .with_context(|| format!("loading \"{}\"", rule_file.display()))?;
-                    if let Some(rule) = rule.as_mut() {
-                        rule.append(r);
+                    let path = entry?.path();
+                    if patch::is_patch_file(&path) {
+                        patch_files.push(path);
                     } else {
-                        rule = Some(r);
+                        rule_files.push(path);
                     }
                 }
+                patch_files.sort();
             } else {
                 let rule_file = rule_dir.join(format!("{}.ron", Self::NAME));
-                if !rule_file.exists() {
-                    continue;
+                if rule_file.exists() {
+                    rule_files.push(rule_file);
+                }
+                let patch_file =
+                    rule_dir.join(format!("{}{}", Self::NAME, patch::PATCH_FILE_SUFFIX));
+                if patch_file.exists() {
+                    patch_files.push(patch_file);
                 }
+            }
 
+            for rule_file in rule_files {
                 let r = Self::from_file(&rule_file)
                     .with_context(|| format!("loading \"{}\"", rule_file.display()))?;
                 if let Some(rule) = rule.as_mut() {
@@ -82,9 +114,22 @@ trait Rule: DeserializeOwned {
                     rule = Some(r);
                 }
             }
+
+            for patch_file in patch_files {
+                let rule = rule
+                    .as_mut()
+                    .ok_or_else(|| anyhow!("\"{}\" has no rule to patch", patch_file.display()))?;
+                rule.patch(&patch_file)
+                    .with_context(|| format!("applying \"{}\"", patch_file.display()))?;
+            }
+        }
+
+        if let Some(rule) = rule.as_mut() {
+            rule.on_loaded()
+                .with_context(|| format!("checking \"{}\" rule", Self::NAME))?;
         }
 
-        rule.ok_or_else(|| anyhow!("rule file not found for \"{}\" rule", Self::NAME))
+        Ok(rule)
     }
 
     fn from_file(path: &Path) -> Result<Self> {
@@ -96,6 +141,17 @@ trait Rule: DeserializeOwned {
     fn append(&mut self, other: Self) {
         *self = other;
     }
+
+    /// Apply a patch file. Fields in the patch override fields of this rule by default.
+    fn patch(&mut self, path: &Path) -> Result<()> {
+        *self = patch::patch_fields(self, path)?;
+        Ok(())
+    }
+
+    /// Called after all rule files and patch files are loaded.
+    fn on_loaded(&mut self) -> Result<()> {
+        Ok(())
+    }
 }
 
 /// Contain game rules
@@ -123,6 +179,8 @@ pub struct Rules {
     pub quest: quest::Quest,
     pub races: race::Races,
     pub recipes: recipe::Recipes,
+    pub removed_obj: removed_obj::RemovedObj,
+    pub status_effects: status_effect::StatusEffects,
     pub town: town::Town,
     pub world: world::World,
 }
@@ -135,10 +193,16 @@ impl Rules {
             dirs.push(addon_rule_dir);
         }
 
+        let chara = chara::Chara::load(&dirs)?;
+        let status_effects = status_effect::StatusEffects::load_with_built_in(
+            status_effect::StatusEffects::built_in(chara.damage_factor_poisoned),
+            &dirs,
+        )?;
+
         Ok(Rules {
             abilities: ability::Abilities::load(&dirs)?,
             biomes: biome::Biomes::load(&dirs)?,
-            chara: chara::Chara::load(&dirs)?,
+            chara,
             chara_gen: charagen::CharaGen::load(&dirs)?,
             chara_traits: chara_trait::CharaTraits::load(&dirs)?,
             classes: class::Classes::load(&dirs)?,
@@ -159,6 +223,8 @@ impl Rules {
             quest: quest::Quest::load(&dirs)?,
             races: race::Races::load(&dirs)?,
             recipes: recipe::Recipes::load(&dirs)?,
+            removed_obj: removed_obj::RemovedObj::load_optional(&dirs)?.unwrap_or_default(),
+            status_effects,
             town: town::Town::load(&dirs)?,
             world: world::World::load(&dirs)?,
         })
@@ -168,22 +234,11 @@ impl Rules {
 static RULES_DIR: Lazy<Mutex<Option<PathBuf>>> = Lazy::new(|| Mutex::new(None));
 static ADDON_RULES_DIR: Lazy<Mutex<Option<PathBuf>>> = Lazy::new(|| Mutex::new(None));
 /// Global state rules holder
-pub static RULES: Lazy<Rules> = Lazy::new(|| {
-    let rules = Rules::load_from_dir(
-        RULES_DIR.lock().unwrap().as_ref().unwrap(),
-        ADDON_RULES_DIR
-            .lock()
-            .unwrap()
-            .as_ref()
-            .map(|path| path.as_ref()),
-    );
-
-    match rules {
-        Ok(rules) => rules,
-        Err(e) => {
-            error!("rules initalization failed:\n{:?}", e);
-            std::process::exit(1);
-        }
+pub static RULES: Reloadable<Rules> = Reloadable::new(|| match load_rules() {
+    Ok(rules) => rules,
+    Err(e) => {
+        error!("rules initalization failed:\n{:?}", e);
+        std::process::exit(1);
     }
 });
 
@@ -195,5 +250,23 @@ pub fn init<P: AsRef<Path>>(app_dirs: P, addon_dir: Option<P>) {
         *ADDON_RULES_DIR.lock().unwrap() = Some(addon_dir.as_ref().into());
     }
 
-    Lazy::force(&RULES);
+    Reloadable::force(&RULES);
+}
+
+/// Load rules again from the directories given to `init()`.
+/// The current rules are kept if loading fails.
+pub fn reload() -> Result<()> {
+    RULES.replace(load_rules()?);
+    Ok(())
+}
+
+fn load_rules() -> Result<Rules> {
+    Rules::load_from_dir(
+        RULES_DIR.lock().unwrap().as_ref().unwrap(),
+        ADDON_RULES_DIR
+            .lock()
+            .unwrap()
+            .as_ref()
+            .map(|path| path.as_ref()),
+    )
 }
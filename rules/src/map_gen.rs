@@ -1,8 +1,12 @@
+use crate::patch::{self, MapPatch};
 use crate::Rule;
+use anyhow::Result;
 use common::hashmap::HashMap;
 use map_generator::MapGenParam;
+use schemars::JsonSchema;
+use std::path::Path;
 
-#[derive(Serialize, Deserialize)]
+#[derive(Serialize, Deserialize, JsonSchema)]
 pub struct MapGen {
     pub map_gen_params: HashMap<String, MapGenParam>,
 }
@@ -15,4 +19,17 @@ impl Rule for MapGen {
             self.map_gen_params.insert(k, v);
         }
     }
+
+    fn patch(&mut self, path: &Path) -> Result<()> {
+        let patch: MapGenPatch = patch::read(path)?;
+        patch.map_gen_params.apply(&mut self.map_gen_params);
+        Ok(())
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct MapGenPatch {
+    #[serde(default)]
+    map_gen_params: MapPatch<String, MapGenParam>,
 }
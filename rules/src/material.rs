@@ -1,9 +1,13 @@
+use crate::patch::{self, MapPatch};
 use crate::Rule;
+use anyhow::Result;
 use common::gamedata::*;
 use once_cell::sync::Lazy;
+use schemars::JsonSchema;
 use std::collections::HashMap;
+use std::path::Path;
 
-#[derive(Serialize, Deserialize)]
+#[derive(Serialize, Deserialize, JsonSchema)]
 #[serde(transparent)]
 pub struct Materials(HashMap<MaterialName, Material>);
 
@@ -15,6 +19,11 @@ impl Rule for Materials {
             self.0.insert(k, v);
         }
     }
+
+    fn patch(&mut self, path: &Path) -> Result<()> {
+        patch::read::<MapPatch<MaterialName, Material>>(path)?.apply(&mut self.0);
+        Ok(())
+    }
 }
 
 impl Materials {
@@ -45,7 +54,7 @@ impl Materials {
 }
 
 /// Rules for character generation
-#[derive(Serialize, Deserialize, Default)]
+#[derive(Serialize, Deserialize, Default, JsonSchema)]
 pub struct Material {
     /// Group of this material
     pub group: String,
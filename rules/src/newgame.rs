@@ -3,12 +3,13 @@ use common::gamedata::*;
 use common::gobj::ObjIdxAsId;
 use common::objholder::ItemIdx;
 use geom::Coords;
+use schemars::JsonSchema;
 use serde_with::serde_as;
 use std::collections::HashMap;
 
 /// Rules for starting new game
 #[serde_as]
-#[derive(Serialize, Deserialize)]
+#[derive(Serialize, Deserialize, JsonSchema)]
 pub struct NewGame {
     /// The choices of character class
     pub class_choices: Vec<CharaClass>,
@@ -16,12 +17,14 @@ pub struct NewGame {
     pub trait_choices: Vec<String>,
     pub trait_initial_point: i32,
     pub start_region: String,
+    #[schemars(with = "(i32, i32)")]
     pub start_pos: Coords,
     pub start_money: u32,
     pub chara_template_table: HashMap<CharaClass, String>,
     pub common_initial_skills: Vec<SkillKind>,
     pub common_initial_abilities: Vec<AbilityId>,
     #[serde_as(as = "Vec<(ObjIdxAsId, _)>")]
+    #[schemars(with = "Vec<(String, u32)>")]
     pub common_initial_items: Vec<(ItemIdx, u32)>,
     /// Initial game date (year)
     pub initial_date_year: u32,
@@ -1,7 +1,8 @@
 use crate::Rule;
+use schemars::JsonSchema;
 
 /// Various parameters for game playing
-#[derive(Serialize, Deserialize)]
+#[derive(Serialize, Deserialize, JsonSchema)]
 pub struct Npc {
     /// Duration of npc recovering after map switching
     pub map_switch_recover_minutes: u32,
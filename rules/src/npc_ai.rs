@@ -1,8 +1,14 @@
+use crate::patch::{self, MapPatch};
+use crate::validate::ValidationReport;
 use crate::Rule;
+use anyhow::Result;
 use common::gamedata::*;
-use std::collections::HashMap;
+use common::objholder::ObjectHolder;
+use schemars::JsonSchema;
+use std::collections::{HashMap, HashSet};
+use std::path::Path;
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
 #[serde(transparent)]
 pub struct NpcAIs(HashMap<NpcAiKind, NpcAi>);
 
@@ -14,6 +20,11 @@ impl Rule for NpcAIs {
             self.0.insert(k, v);
         }
     }
+
+    fn patch(&mut self, path: &Path) -> Result<()> {
+        patch::read::<MapPatch<NpcAiKind, NpcAi>>(path)?.apply(&mut self.0);
+        Ok(())
+    }
 }
 
 impl NpcAIs {
@@ -22,9 +33,39 @@ impl NpcAIs {
             .get(&kind)
             .unwrap_or_else(|| &self.0[&NpcAiKind::default()])
     }
+
+    pub(crate) fn validate(&self, objholder: &ObjectHolder, report: &mut ValidationReport) {
+        if !self.0.contains_key(&NpcAiKind::default()) {
+            report.error(Self::NAME, "default npc ai is not defined");
+        }
+
+        let mut used = HashSet::new();
+        for ct in &objholder.chara_template {
+            if !self.0.contains_key(&ct.default_ai_kind) {
+                report.warn(
+                    Self::NAME,
+                    format!(
+                        "unknown npc ai \"{}\" in chara template \"{}\"",
+                        ct.default_ai_kind.as_str(),
+                        ct.id
+                    ),
+                );
+            }
+            used.insert(ct.default_ai_kind);
+        }
+
+        for kind in self.0.keys() {
+            if !used.contains(kind) && *kind != NpcAiKind::default() {
+                report.warn(
+                    Self::NAME,
+                    format!("npc ai \"{}\" is not used", kind.as_str()),
+                );
+            }
+        }
+    }
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
 pub struct NpcAi {
     pub move_kind: MoveKind,
     pub pathfinding_step: u32,
@@ -48,14 +89,14 @@ pub struct NpcAi {
     pub search_turn: u32,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
 pub enum MoveKind {
     NoMove,
     Wander,
     Return,
 }
 
-#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug, Serialize, Deserialize)]
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug, Serialize, Deserialize, JsonSchema)]
 pub enum CombatActionKind {
     Skip,
     ApproachEnemy,
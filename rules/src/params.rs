@@ -1,9 +1,10 @@
 use crate::Rule;
 use common::basic::BonusLevel;
+use schemars::JsonSchema;
 use std::collections::HashMap;
 
 /// Various parameters for game playing
-#[derive(Serialize, Deserialize)]
+#[derive(Serialize, Deserialize, JsonSchema)]
 pub struct Params {
     /// Minutes per one turn on maps in sites
     pub minutes_per_turn_normal: f32,
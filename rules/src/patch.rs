@@ -0,0 +1,289 @@
+//! Patch files to modify rules loaded from other rule directories.
+//!
+//! Fields given in a patch file override fields of the loaded rule,
+//! and other fields are kept. Map-typed rules use `MapPatch` to insert and remove entries.
+//!
+//! Map-typed fields inside other rules (e.g. `Town::shop_kinds`) are overridden as a whole,
+//! so a patch must give all entries of such fields.
+
+use anyhow::{anyhow, Result};
+use serde::de::{self, DeserializeSeed, IntoDeserializer, MapAccess, Visitor};
+use serde::{Deserialize, Deserializer, Serialize};
+use std::collections::HashMap;
+use std::fmt;
+use std::fs;
+use std::hash::{BuildHasher, Hash};
+use std::path::Path;
+
+/// Patch files have this suffix instead of ".ron"
+pub const PATCH_FILE_SUFFIX: &str = ".patch.ron";
+
+pub fn is_patch_file(path: &Path) -> bool {
+    path.file_name()
+        .and_then(|name| name.to_str())
+        .map(|name| name.ends_with(PATCH_FILE_SUFFIX))
+        .unwrap_or(false)
+}
+
+/// Read a patch file
+pub(crate) fn read<T: de::DeserializeOwned>(path: &Path) -> Result<T> {
+    let file = fs::File::open(path)?;
+    Ok(ron::de::from_reader(file)?)
+}
+
+/// Override fields of `base` by the fields given in the patch file
+pub(crate) fn patch_fields<T: Serialize + de::DeserializeOwned>(
+    base: &T,
+    path: &Path,
+) -> Result<T> {
+    patch_fields_str(base, &fs::read_to_string(path)?)
+}
+
+fn patch_fields_str<T: Serialize + de::DeserializeOwned>(base: &T, s: &str) -> Result<T> {
+    let base = match serde_json::to_value(base)? {
+        serde_json::Value::Object(base) => base,
+        _ => return Err(anyhow!("this rule cannot be patched by fields")),
+    };
+
+    let mut de = ron::Deserializer::from_str(s)?;
+    let patched = T::deserialize(FieldPatch {
+        patch: &mut de,
+        base,
+    })
+    .map_err(|e| de.span_error(e))?;
+    de.end().map_err(|e| de.span_error(e))?;
+    Ok(patched)
+}
+
+/// Patch for map-typed rules
+#[derive(Deserialize)]
+#[serde(
+    bound(deserialize = "K: Deserialize<'de> + Eq + Hash, V: Deserialize<'de>"),
+    deny_unknown_fields
+)]
+pub struct MapPatch<K, V> {
+    /// Keys of entries to remove
+    #[serde(default)]
+    pub remove: Vec<K>,
+    /// Entries to insert. Existing entries are replaced.
+    #[serde(default)]
+    pub insert: HashMap<K, V>,
+}
+
+impl<K, V> Default for MapPatch<K, V> {
+    fn default() -> Self {
+        MapPatch {
+            remove: Vec::new(),
+            insert: HashMap::new(),
+        }
+    }
+}
+
+impl<K: Eq + Hash, V> MapPatch<K, V> {
+    pub fn apply<S: BuildHasher>(self, map: &mut HashMap<K, V, S>) {
+        for k in &self.remove {
+            map.remove(k);
+        }
+        map.extend(self.insert);
+    }
+}
+
+/// Deserializer to read a struct from the patch,
+/// and fields not in the patch are read from the serialized base value.
+struct FieldPatch<'a, 'de> {
+    patch: &'a mut ron::Deserializer<'de>,
+    base: serde_json::Map<String, serde_json::Value>,
+}
+
+impl<'a, 'de> Deserializer<'de> for FieldPatch<'a, 'de> {
+    type Error = ron::Error;
+
+    fn deserialize_struct<V: Visitor<'de>>(
+        self,
+        name: &'static str,
+        fields: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, Self::Error> {
+        self.patch.deserialize_struct(
+            name,
+            fields,
+            FieldPatchVisitor {
+                visitor,
+                base: self.base,
+                fields,
+            },
+        )
+    }
+
+    fn deserialize_any<V: Visitor<'de>>(self, _visitor: V) -> Result<V::Value, Self::Error> {
+        Err(de::Error::custom("this rule cannot be patched by fields"))
+    }
+
+    serde::forward_to_deserialize_any! {
+        bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string
+        bytes byte_buf option unit unit_struct newtype_struct seq tuple
+        tuple_struct map enum identifier ignored_any
+    }
+}
+
+struct FieldPatchVisitor<V> {
+    visitor: V,
+    base: serde_json::Map<String, serde_json::Value>,
+    fields: &'static [&'static str],
+}
+
+impl<'de, V: Visitor<'de>> Visitor<'de> for FieldPatchVisitor<V> {
+    type Value = V::Value;
+
+    fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.visitor.expecting(f)
+    }
+
+    fn visit_map<A: MapAccess<'de>>(self, map: A) -> Result<V::Value, A::Error> {
+        self.visitor.visit_map(FieldPatchAccess {
+            patch: Some(map),
+            base: self.base.into_iter(),
+            base_value: None,
+            patched: Vec::new(),
+            fields: self.fields,
+        })
+    }
+}
+
+struct FieldPatchAccess<A> {
+    /// None after all fields in the patch are read
+    patch: Option<A>,
+    base: serde_json::map::IntoIter,
+    base_value: Option<serde_json::Value>,
+    patched: Vec<String>,
+    fields: &'static [&'static str],
+}
+
+impl<'de, A: MapAccess<'de>> MapAccess<'de> for FieldPatchAccess<A> {
+    type Error = A::Error;
+
+    fn next_key_seed<K: DeserializeSeed<'de>>(
+        &mut self,
+        seed: K,
+    ) -> Result<Option<K::Value>, A::Error> {
+        if let Some(patch) = self.patch.as_mut() {
+            if let Some(FieldName(field)) = patch.next_key()? {
+                let field = *self
+                    .fields
+                    .iter()
+                    .find(|f| **f == field)
+                    .ok_or_else(|| de::Error::unknown_field(&field, self.fields))?;
+                if self.patched.iter().any(|patched| patched == field) {
+                    return Err(de::Error::duplicate_field(field));
+                }
+                self.patched.push(field.to_owned());
+                return seed.deserialize(field.into_deserializer()).map(Some);
+            }
+            self.patch = None;
+        }
+
+        for (field, value) in self.base.by_ref() {
+            if self.patched.contains(&field) {
+                continue;
+            }
+            self.base_value = Some(value);
+            return seed.deserialize(field.into_deserializer()).map(Some);
+        }
+        Ok(None)
+    }
+
+    fn next_value_seed<S: DeserializeSeed<'de>>(&mut self, seed: S) -> Result<S::Value, A::Error> {
+        if let Some(patch) = self.patch.as_mut() {
+            patch.next_value_seed(seed)
+        } else {
+            let value = self
+                .base_value
+                .take()
+                .ok_or_else(|| de::Error::custom("value is missing"))?;
+            seed.deserialize(value).map_err(de::Error::custom)
+        }
+    }
+}
+
+/// Field name read as an identifier
+struct FieldName(String);
+
+impl<'de> Deserialize<'de> for FieldName {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        struct FieldNameVisitor;
+
+        impl<'de> Visitor<'de> for FieldNameVisitor {
+            type Value = FieldName;
+
+            fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                f.write_str("field name")
+            }
+
+            fn visit_str<E: de::Error>(self, v: &str) -> Result<FieldName, E> {
+                Ok(FieldName(v.to_owned()))
+            }
+        }
+
+        deserializer.deserialize_identifier(FieldNameVisitor)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    enum Kind {
+        A,
+        B(u32),
+    }
+
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    struct Sample {
+        factor: f32,
+        kind: Kind,
+        list: Vec<Kind>,
+        #[serde(default)]
+        count: u32,
+    }
+
+    fn sample() -> Sample {
+        Sample {
+            factor: 1.5,
+            kind: Kind::B(3),
+            list: vec![Kind::A, Kind::B(1)],
+            count: 2,
+        }
+    }
+
+    #[test]
+    fn field_patch() {
+        let patched = patch_fields_str(&sample(), "(factor: 2.0, kind: A)").unwrap();
+        assert_eq!(
+            patched,
+            Sample {
+                factor: 2.0,
+                kind: Kind::A,
+                ..sample()
+            }
+        );
+        assert_eq!(patch_fields_str(&sample(), "()").unwrap(), sample());
+        assert!(patch_fields_str(&sample(), "(factr: 2.0)").is_err());
+        assert!(patch_fields_str(&sample(), "(count: 1, count: 2)").is_err());
+    }
+
+    #[test]
+    fn map_patch() {
+        let mut map: HashMap<String, u32> = [("a".to_owned(), 1), ("b".to_owned(), 2)]
+            .into_iter()
+            .collect();
+        let patch: MapPatch<String, u32> =
+            ron::from_str(r#"(remove: ["a", "c"], insert: { "b": 3, "d": 4 })"#).unwrap();
+        patch.apply(&mut map);
+        let mut entries: Vec<_> = map.into_iter().collect();
+        entries.sort();
+        assert_eq!(entries, vec![("b".to_owned(), 3), ("d".to_owned(), 4)]);
+
+        assert!(ron::from_str::<MapPatch<String, u32>>(r#"(remvoe: ["a"])"#).is_err());
+    }
+}
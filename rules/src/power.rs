@@ -1,12 +1,14 @@
 use super::Rule;
 use ordered_float::NotNan;
+use schemars::JsonSchema;
 
 /// Rules for calculation related to power/hit calclation.
-#[derive(Serialize, Deserialize)]
+#[derive(Serialize, Deserialize, JsonSchema)]
 pub struct Power {
     pub skill_base: f32,
     pub base_evasion_power: f32,
     pub base_defence: f32,
+    #[schemars(with = "f32")]
     pub bare_hand_hit: NotNan<f32>,
     pub bare_hand_power_base: f32,
     pub bare_hand_power_factor: f32,
@@ -1,7 +1,8 @@
 use crate::Rule;
+use schemars::JsonSchema;
 
 /// Rules for quest
-#[derive(Serialize, Deserialize)]
+#[derive(Serialize, Deserialize, JsonSchema)]
 pub struct Quest {
     pub duplicate_factor: f32,
     pub update_duration_days: u32,
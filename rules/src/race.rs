@@ -1,9 +1,13 @@
+use crate::patch::{self, MapPatch};
 use crate::Rule;
+use anyhow::Result;
 use common::gamedata::*;
+use schemars::JsonSchema;
 use smallvec::{smallvec, SmallVec};
 use std::collections::HashMap;
+use std::path::Path;
 
-#[derive(Serialize, Deserialize)]
+#[derive(Serialize, Deserialize, JsonSchema)]
 #[serde(transparent)]
 pub struct Races(HashMap<String, Race>);
 
@@ -54,10 +58,15 @@ impl Rule for Races {
             self.0.insert(k, v);
         }
     }
+
+    fn patch(&mut self, path: &Path) -> Result<()> {
+        patch::read::<MapPatch<String, Race>>(path)?.apply(&mut self.0);
+        Ok(())
+    }
 }
 
 /// Rules for character generation
-#[derive(Serialize, Deserialize)]
+#[derive(Serialize, Deserialize, JsonSchema)]
 pub struct Race {
     #[serde(default)]
     pub base_race: String,
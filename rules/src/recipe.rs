@@ -1,7 +1,8 @@
 use super::Rule;
 use common::gamedata::{CreationKind, Recipe};
+use schemars::JsonSchema;
 
-#[derive(Serialize, Deserialize)]
+#[derive(Serialize, Deserialize, JsonSchema)]
 pub struct Recipes {
     #[serde(default)]
     art_recipes: Vec<Recipe>,
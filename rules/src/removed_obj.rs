@@ -0,0 +1,18 @@
+use common::idx_conv::RemovedObjPolicy;
+
+use crate::Rule;
+use schemars::JsonSchema;
+
+/// Rules for objects in saves which are removed from the current paks.
+/// This rule is optional.
+#[derive(Default, Serialize, Deserialize, JsonSchema)]
+#[serde(transparent)]
+pub struct RemovedObj(pub RemovedObjPolicy);
+
+impl Rule for RemovedObj {
+    const NAME: &'static str = "removed_obj";
+
+    fn append(&mut self, other: Self) {
+        self.0.append(other.0);
+    }
+}
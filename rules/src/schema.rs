@@ -0,0 +1,46 @@
+//! JSON Schema of rule files.
+//!
+//! Doc comments of rule types are used as descriptions in the schema.
+
+use crate::*;
+use common::schema::root_schema_for;
+use schemars::schema::RootSchema;
+
+/// Get JSON Schemas of all rules with their names
+pub fn rule_schemas() -> Vec<(&'static str, RootSchema)> {
+    macro_rules! rule_schemas {
+        ($($rule:ty),*) => {
+            vec![$((<$rule as Rule>::NAME, root_schema_for::<$rule>())),*]
+        }
+    }
+
+    rule_schemas!(
+        ability::Abilities,
+        biome::Biomes,
+        chara::Chara,
+        charagen::CharaGen,
+        chara_trait::CharaTraits,
+        class::Classes,
+        combat::Combat,
+        creation::Creation,
+        dungeon_gen::DungeonGen,
+        exp::Exp,
+        effect::Effect,
+        faction::Faction,
+        map_gen::MapGen,
+        item::Item,
+        material::Materials,
+        newgame::NewGame,
+        npc::Npc,
+        npc_ai::NpcAIs,
+        params::Params,
+        power::Power,
+        quest::Quest,
+        race::Races,
+        recipe::Recipes,
+        removed_obj::RemovedObj,
+        status_effect::StatusEffects,
+        town::Town,
+        world::World
+    )
+}
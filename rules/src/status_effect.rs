@@ -0,0 +1,409 @@
+use super::Rule;
+use crate::ability::Abilities;
+use crate::patch::{self, MapPatch};
+use crate::validate::ValidationReport;
+use anyhow::Result;
+use common::gamedata::{CharaModifier, Effect, EffectKind, ItemObjAttr, UseEffect};
+use common::objholder::{ObjectHolder, ObjectIndex, UiImgIdx};
+use schemars::JsonSchema;
+use std::collections::HashMap;
+use std::path::Path;
+
+/// Status effects given to characters by `EffectKind::Status`
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+#[serde(transparent)]
+pub struct StatusEffects(HashMap<String, StatusEffect>);
+
+impl StatusEffects {
+    pub fn get(&self, id: &str) -> Option<&StatusEffect> {
+        self.0.get(id)
+    }
+
+    /// Built-in status effects. Rule files and patch files are applied on top of them.
+    /// `damage_factor_poisoned` is given by the chara rule.
+    pub fn built_in(damage_factor_poisoned: f32) -> Self {
+        let asleep = StatusEffect {
+            duration: StatusDuration::Power(1.0),
+            stacking: StackingPolicy::Refresh,
+            restrictions: vec![ActionRestriction::Act],
+            ..StatusEffect::default()
+        };
+        let poisoned = StatusEffect {
+            per_turn: TurnChange {
+                hp_damage_factor: damage_factor_poisoned,
+                ..TurnChange::default()
+            },
+            ..StatusEffect::default()
+        };
+        let scanned = StatusEffect {
+            reveal_status: true,
+            ..StatusEffect::default()
+        };
+        StatusEffects(
+            [
+                ("asleep".to_owned(), asleep),
+                ("poisoned".to_owned(), poisoned),
+                ("scanned".to_owned(), scanned),
+            ]
+            .into(),
+        )
+    }
+
+    pub(crate) fn validate(
+        &self,
+        abilities: &Abilities,
+        objholder: &ObjectHolder,
+        report: &mut ValidationReport,
+    ) {
+        for (id, status_effect) in &self.0 {
+            if let StatusDuration::Power(factor) = status_effect.duration {
+                if factor <= 0.0 {
+                    report.error(
+                        Self::NAME,
+                        format!("status effect \"{id}\" has non-positive duration factor"),
+                    );
+                }
+            }
+            if let StackingPolicy::Stack(0) = status_effect.stacking {
+                report.error(
+                    Self::NAME,
+                    format!("status effect \"{id}\" has zero max stacks"),
+                );
+            }
+            if !status_effect.icon.is_empty()
+                && UiImgIdx::search_idx(&status_effect.icon, objholder).is_none()
+            {
+                report.error(
+                    Self::NAME,
+                    format!(
+                        "status effect \"{id}\": unknown icon \"{}\"",
+                        status_effect.icon
+                    ),
+                );
+            }
+        }
+
+        let mut effects: Vec<(String, &Effect)> = abilities
+            .iter()
+            .map(|(id, ability)| (format!("ability \"{id}\""), &ability.effect))
+            .collect();
+        for item in &objholder.item {
+            for attr in &item.attrs {
+                let effect = match attr {
+                    ItemObjAttr::Medical { effect }
+                    | ItemObjAttr::Release { effect }
+                    | ItemObjAttr::Throw { effect }
+                    | ItemObjAttr::Use(UseEffect::Effect(effect)) => effect,
+                    _ => continue,
+                };
+                effects.push((format!("item \"{}\"", item.id), effect));
+            }
+        }
+
+        for (user, effect) in effects {
+            for kind in &effect.kind {
+                if let EffectKind::Status { status } = kind {
+                    if !self.0.contains_key(status) {
+                        report.error(
+                            Self::NAME,
+                            format!("unknown status effect \"{status}\" in {user}"),
+                        );
+                    }
+                }
+            }
+        }
+    }
+}
+
+impl Rule for StatusEffects {
+    const NAME: &'static str = "status_effects";
+
+    fn append(&mut self, other: Self) {
+        for (k, v) in other.0.into_iter() {
+            self.0.insert(k, v);
+        }
+    }
+
+    fn patch(&mut self, path: &Path) -> Result<()> {
+        patch::read::<MapPatch<String, StatusEffect>>(path)?.apply(&mut self.0);
+        Ok(())
+    }
+}
+
+/// Definition of a status effect. Its name is given by the text id "chara_status-{id}".
+#[derive(Debug, Default, Serialize, Deserialize, JsonSchema)]
+pub struct StatusEffect {
+    /// Duration of this status. Permanent by default.
+    #[serde(default)]
+    pub duration: StatusDuration,
+    /// Behavior when a character gets this status again
+    #[serde(default)]
+    pub stacking: StackingPolicy,
+    /// Changes of HP, MP and SP every turn. Multiplied by the number of stacks.
+    #[serde(default)]
+    pub per_turn: TurnChange,
+    /// Modifiers applied to the character for each stack
+    #[serde(default)]
+    pub modifiers: Vec<CharaModifier>,
+    /// Actions the character cannot do
+    #[serde(default)]
+    pub restrictions: Vec<ActionRestriction>,
+    /// Conditions to remove this status before it expires
+    #[serde(default)]
+    pub cure: Vec<CureCondition>,
+    /// UI image id displayed in the status indicator
+    #[serde(default)]
+    pub icon: String,
+    /// The player can open the status window of characters with this status
+    #[serde(default)]
+    pub reveal_status: bool,
+}
+
+impl StatusEffect {
+    /// Turns of this status caused by an effect of the given power
+    pub fn turns(&self, power: f32) -> Option<u16> {
+        match self.duration {
+            StatusDuration::Permanent => None,
+            StatusDuration::Turns(turns) => Some(turns),
+            StatusDuration::Power(factor) => {
+                Some((power * factor).clamp(1.0, u16::MAX as f32) as u16)
+            }
+        }
+    }
+
+    pub fn is_restricted(&self, restriction: ActionRestriction) -> bool {
+        self.restrictions.contains(&restriction)
+    }
+
+    /// Returns true if any cure condition of this status satisfies `f`
+    pub fn is_cured<F: FnMut(CureCondition) -> bool>(&self, f: F) -> bool {
+        self.cure.iter().copied().any(f)
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default, Serialize, Deserialize, JsonSchema)]
+pub enum StatusDuration {
+    /// Continues until cured
+    #[default]
+    Permanent,
+    /// Fixed number of turns
+    Turns(u16),
+    /// (turns) = (effect power) * (factor)
+    Power(f32),
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default, Serialize, Deserialize, JsonSchema)]
+pub enum StackingPolicy {
+    /// The new status is ignored
+    #[default]
+    Ignore,
+    /// Turns left is set to the longer one
+    Refresh,
+    /// Turns of the new status are added
+    Extend,
+    /// Stacks are increased up to the given number, and turns left is set to the longer one
+    Stack(u16),
+}
+
+impl StackingPolicy {
+    /// Update turns left and stacks of the current status when the same status is given again.
+    /// `None` turns mean permanent.
+    pub fn apply(self, turn_left: &mut Option<u16>, stack: &mut u16, turn_left_new: Option<u16>) {
+        // None means permanent, so it is longer than any turns
+        let longer = turn_left.zip(turn_left_new).map(|(a, b)| a.max(b));
+        match self {
+            StackingPolicy::Ignore => (),
+            StackingPolicy::Refresh => {
+                *turn_left = longer;
+            }
+            StackingPolicy::Extend => {
+                *turn_left = turn_left
+                    .zip(turn_left_new)
+                    .map(|(a, b)| a.saturating_add(b));
+            }
+            StackingPolicy::Stack(max) => {
+                *stack = std::cmp::min(*stack + 1, max);
+                *turn_left = longer;
+            }
+        }
+    }
+}
+
+/// Changes every turn. Negative values decrease the parameter.
+#[derive(Clone, Copy, Debug, Default, Serialize, Deserialize, JsonSchema)]
+#[serde(default)]
+pub struct TurnChange {
+    pub hp: f32,
+    /// (damage) = (max hp) * (hp_damage_factor) / (VIT)
+    pub hp_damage_factor: f32,
+    pub mp: f32,
+    pub sp: f32,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Serialize, Deserialize, JsonSchema)]
+pub enum ActionRestriction {
+    /// Turns are skipped
+    Act,
+    Move,
+    /// Melee and ranged attacks
+    Attack,
+    Ability,
+}
+
+#[derive(Clone, Copy, Debug, Serialize, Deserialize, JsonSchema)]
+pub enum CureCondition {
+    /// Removed when the character is damaged
+    Damaged,
+    /// Removed by this probability every turn
+    Chance(f32),
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn stacking() {
+        let apply = |policy: StackingPolicy, current: (Option<u16>, u16), new: Option<u16>| {
+            let (mut turn_left, mut stack) = current;
+            policy.apply(&mut turn_left, &mut stack, new);
+            (turn_left, stack)
+        };
+
+        assert_eq!(
+            apply(StackingPolicy::Ignore, (Some(3), 1), Some(5)),
+            (Some(3), 1)
+        );
+        assert_eq!(
+            apply(StackingPolicy::Refresh, (Some(3), 1), Some(5)),
+            (Some(5), 1)
+        );
+        assert_eq!(
+            apply(StackingPolicy::Refresh, (Some(5), 1), Some(3)),
+            (Some(5), 1)
+        );
+        assert_eq!(
+            apply(StackingPolicy::Extend, (Some(3), 1), Some(5)),
+            (Some(8), 1)
+        );
+        assert_eq!(
+            apply(StackingPolicy::Extend, (Some(u16::MAX), 1), Some(5)),
+            (Some(u16::MAX), 1)
+        );
+        assert_eq!(
+            apply(StackingPolicy::Stack(3), (Some(3), 1), Some(2)),
+            (Some(3), 2)
+        );
+        assert_eq!(
+            apply(StackingPolicy::Stack(3), (Some(3), 3), Some(5)),
+            (Some(5), 3)
+        );
+        // Permanent status is longer than any turns
+        assert_eq!(
+            apply(StackingPolicy::Refresh, (None, 1), Some(5)),
+            (None, 1)
+        );
+        assert_eq!(
+            apply(StackingPolicy::Refresh, (Some(3), 1), None),
+            (None, 1)
+        );
+        assert_eq!(apply(StackingPolicy::Extend, (Some(3), 1), None), (None, 1));
+    }
+
+    #[test]
+    fn cure() {
+        let status_effect = StatusEffect {
+            cure: vec![CureCondition::Damaged, CureCondition::Chance(0.5)],
+            ..StatusEffect::default()
+        };
+        assert!(status_effect.is_cured(|cond| matches!(cond, CureCondition::Damaged)));
+        assert!(status_effect.is_cured(|cond| matches!(cond, CureCondition::Chance(p) if p >= 0.5)));
+        assert!(!status_effect.is_cured(|cond| matches!(cond, CureCondition::Chance(p) if p > 0.5)));
+        assert!(!StatusEffect::default().is_cured(|_| true));
+    }
+
+    #[test]
+    fn restriction() {
+        let status_effect: StatusEffect =
+            ron::de::from_str("(restrictions: [Move, Attack])").unwrap();
+        assert!(status_effect.is_restricted(ActionRestriction::Move));
+        assert!(status_effect.is_restricted(ActionRestriction::Attack));
+        assert!(!status_effect.is_restricted(ActionRestriction::Act));
+        assert!(!status_effect.is_restricted(ActionRestriction::Ability));
+    }
+
+    #[test]
+    fn turns() {
+        let status_effect = |duration| StatusEffect {
+            duration,
+            ..StatusEffect::default()
+        };
+        assert_eq!(status_effect(StatusDuration::Permanent).turns(10.0), None);
+        assert_eq!(status_effect(StatusDuration::Turns(3)).turns(10.0), Some(3));
+        assert_eq!(
+            status_effect(StatusDuration::Power(0.5)).turns(10.0),
+            Some(5)
+        );
+        assert_eq!(
+            status_effect(StatusDuration::Power(0.5)).turns(0.0),
+            Some(1)
+        );
+    }
+
+    #[test]
+    fn built_in_status_effects() {
+        let status_effects = StatusEffects::built_in(0.1);
+        let asleep = status_effects.get("asleep").unwrap();
+        assert!(asleep.is_restricted(ActionRestriction::Act));
+        assert_eq!(asleep.turns(4.0), Some(4));
+        assert_eq!(
+            status_effects
+                .get("poisoned")
+                .unwrap()
+                .per_turn
+                .hp_damage_factor,
+            0.1
+        );
+        assert!(status_effects.get("scanned").unwrap().reveal_status);
+
+        let mut report = ValidationReport::default();
+        let abilities: Abilities = ron::de::from_str("{}").unwrap();
+        status_effects.validate(&abilities, &ObjectHolder::default(), &mut report);
+        assert!(report.is_empty(), "{report}");
+    }
+
+    #[test]
+    fn load_on_built_in() {
+        let dir = std::env::temp_dir().join(format!(
+            "rusted-ruins-rules-test-status-effects-{}",
+            std::process::id()
+        ));
+        let addon_dir = dir.join("addon");
+        let patch_dir = dir.join("patch");
+        std::fs::create_dir_all(&addon_dir).unwrap();
+        std::fs::create_dir_all(&patch_dir).unwrap();
+        std::fs::write(
+            addon_dir.join("status_effects.ron"),
+            "{ \"burning\": (per_turn: (hp: -2.0)) }",
+        )
+        .unwrap();
+        std::fs::write(
+            patch_dir.join("status_effects.patch.ron"),
+            "(remove: [\"scanned\"])",
+        )
+        .unwrap();
+
+        let status_effects =
+            StatusEffects::load_with_built_in(StatusEffects::built_in(0.1), &[&addon_dir]).unwrap();
+        assert!(status_effects.get("burning").is_some());
+        assert!(status_effects.get("poisoned").is_some());
+        assert!(status_effects.get("asleep").is_some());
+
+        let status_effects =
+            StatusEffects::load_with_built_in(StatusEffects::built_in(0.1), &[&patch_dir]).unwrap();
+        assert!(status_effects.get("scanned").is_none());
+        assert!(status_effects.get("poisoned").is_some());
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
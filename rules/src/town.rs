@@ -1,9 +1,10 @@
 use crate::Rule;
 use common::item_selector::ItemSelector;
+use schemars::JsonSchema;
 use std::collections::HashMap;
 
 /// Used for town simulation
-#[derive(Serialize, Deserialize)]
+#[derive(Serialize, Deserialize, JsonSchema)]
 pub struct Town {
     /// The minimum number of shop items
     pub min_shop_items: u32,
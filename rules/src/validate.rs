@@ -0,0 +1,526 @@
+//! Validation over the whole ruleset.
+//!
+//! Each rule file is loaded separately, so references between rules and objects
+//! are checked after all rules and objects are loaded.
+
+use crate::dungeon_gen::DungeonGen;
+use crate::map_gen::MapGen;
+use crate::town::Town;
+use crate::Rules;
+use common::gobj;
+use common::objholder::*;
+use std::collections::HashSet;
+use std::fmt;
+
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Debug)]
+pub enum Severity {
+    Error,
+    Warning,
+}
+
+#[derive(Debug)]
+pub struct Problem {
+    pub severity: Severity,
+    /// Name of the rule which has this problem
+    pub rule: &'static str,
+    pub message: String,
+}
+
+/// All problems found in the ruleset
+#[derive(Debug, Default)]
+pub struct ValidationReport {
+    pub problems: Vec<Problem>,
+}
+
+impl ValidationReport {
+    pub(crate) fn error<S: Into<String>>(&mut self, rule: &'static str, message: S) {
+        self.push(Severity::Error, rule, message.into());
+    }
+
+    pub(crate) fn warn<S: Into<String>>(&mut self, rule: &'static str, message: S) {
+        self.push(Severity::Warning, rule, message.into());
+    }
+
+    fn push(&mut self, severity: Severity, rule: &'static str, message: String) {
+        self.problems.push(Problem {
+            severity,
+            rule,
+            message,
+        });
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.problems.is_empty()
+    }
+
+    pub fn n_errors(&self) -> usize {
+        self.count(Severity::Error)
+    }
+
+    pub fn n_warnings(&self) -> usize {
+        self.count(Severity::Warning)
+    }
+
+    fn count(&self, severity: Severity) -> usize {
+        self.problems
+            .iter()
+            .filter(|problem| problem.severity == severity)
+            .count()
+    }
+
+    /// Returns true if the ruleset cannot be used
+    pub fn is_failed(&self, warnings_as_errors: bool) -> bool {
+        self.n_errors() > 0 || (warnings_as_errors && self.n_warnings() > 0)
+    }
+}
+
+impl fmt::Display for ValidationReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "rule validation: {} errors, {} warnings",
+            self.n_errors(),
+            self.n_warnings()
+        )?;
+        for problem in &self.problems {
+            let severity = match problem.severity {
+                Severity::Error => "error",
+                Severity::Warning => "warning",
+            };
+            write!(f, "\n  {} [{}] {}", severity, problem.rule, problem.message)?;
+        }
+        Ok(())
+    }
+}
+
+impl Rules {
+    /// Check references between rules and objects. Objects must be loaded before this.
+    pub fn validate(&self) -> ValidationReport {
+        let mut report = ValidationReport::default();
+        let objholder = gobj::get_objholder();
+
+        self.chara_traits.validate(&mut report);
+        self.npc_ai.validate(objholder, &mut report);
+        self.status_effects
+            .validate(&self.abilities, objholder, &mut report);
+        validate_dungeon_gen(&self.dungeon_gen, &self.map_gen, objholder, &mut report);
+        validate_town(&self.town, objholder, &mut report);
+
+        report.problems.sort_by(|a, b| {
+            (a.severity, a.rule, &a.message).cmp(&(b.severity, b.rule, &b.message))
+        });
+        report
+    }
+}
+
+fn validate_dungeon_gen(
+    dungeon_gen: &DungeonGen,
+    map_gen: &MapGen,
+    objholder: &ObjectHolder,
+    report: &mut ValidationReport,
+) {
+    const RULE: &str = "dungeon_gen";
+
+    for (kind, params) in dungeon_gen {
+        for (map_gen_id, _) in &params.map_gen {
+            if !map_gen.map_gen_params.contains_key(map_gen_id) {
+                report.error(RULE, format!("{kind:?}: unknown map_gen \"{map_gen_id}\""));
+            }
+        }
+
+        for [tile, wall] in &params.terrain {
+            if TileIdx::search_idx(tile, objholder).is_none() {
+                report.error(RULE, format!("{kind:?}: unknown tile \"{tile}\""));
+            }
+            if WallIdx::search_idx(wall, objholder).is_none() {
+                report.error(RULE, format!("{kind:?}: unknown wall \"{wall}\""));
+            }
+        }
+        for (wall, _) in &params.sub_walls {
+            if WallIdx::search_idx(wall, objholder).is_none() {
+                report.error(RULE, format!("{kind:?}: unknown sub wall \"{wall}\""));
+            }
+        }
+
+        for (item_selector, _) in &params.item_gen_weight {
+            if !objholder.item.iter().any(|item| item_selector.is(item)) {
+                report.warn(
+                    RULE,
+                    format!("{kind:?}: item selector \"{item_selector}\" matches no items"),
+                );
+            }
+        }
+    }
+}
+
+fn validate_town(town: &Town, objholder: &ObjectHolder, report: &mut ValidationReport) {
+    const RULE: &str = "town";
+
+    let mut used = HashSet::new();
+    for site_gen in &objholder.site_gen {
+        for shop in site_gen.shops.values() {
+            if shop.shop_kind.is_empty() {
+                continue;
+            }
+            if !town.shop_kinds.contains_key(&shop.shop_kind) {
+                report.error(
+                    RULE,
+                    format!(
+                        "unknown shop kind \"{}\" in site gen \"{}\"",
+                        shop.shop_kind, site_gen.id
+                    ),
+                );
+            }
+            used.insert(shop.shop_kind.as_str());
+        }
+    }
+
+    for (shop_kind, item_selector) in &town.shop_kinds {
+        if !used.contains(shop_kind.as_str()) {
+            report.warn(RULE, format!("shop kind \"{shop_kind}\" is not used"));
+        }
+        if !objholder.item.iter().any(|item| item_selector.is(item)) {
+            report.warn(
+                RULE,
+                format!(
+                    "shop kind \"{shop_kind}\": item selector \"{item_selector}\" matches no items"
+                ),
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ability::Abilities;
+    use crate::chara_trait::CharaTraits;
+    use crate::npc_ai::NpcAIs;
+    use crate::status_effect::StatusEffects;
+    use common::gamedata::{
+        CharaBaseAttr, CharaClass, Effect, EffectKind, FactionId, ItemFlags, ItemKind, ItemObjAttr,
+        MaterialName, QualityKind, SiteKind, SiteSymbolKind,
+    };
+    use common::obj::*;
+    use common::sitegen::{NpcGenId, ShopGenData};
+
+    fn from_ron<T: serde::de::DeserializeOwned>(s: &str) -> T {
+        ron::de::from_str(s).unwrap()
+    }
+
+    /// Assert that the report has only the given problems
+    fn assert_problems(report: &ValidationReport, expected: &[(Severity, &str)]) {
+        let mut problems: Vec<(Severity, &str)> = report
+            .problems
+            .iter()
+            .map(|problem| (problem.severity, problem.message.as_str()))
+            .collect();
+        problems.sort();
+        let mut expected = expected.to_vec();
+        expected.sort();
+        assert_eq!(problems, expected);
+    }
+
+    fn img() -> Img {
+        Img {
+            data: Vec::new(),
+            w: 1,
+            h: 1,
+            grid_nx: 1,
+            grid_ny: 1,
+            n_frame: 1,
+            n_pattern: 1,
+            n_anim_frame: 1,
+            duration: 0,
+            variation_rule: ImgVariationRule::None,
+            atlas: None,
+        }
+    }
+
+    fn item(id: &str, group: &str, attrs: Vec<ItemObjAttr>) -> Object {
+        Object::Item(ItemObject {
+            id: id.into(),
+            img: img(),
+            kind: ItemKind::Object,
+            group: group.into(),
+            default_flags: ItemFlags::empty(),
+            basic_price: 0,
+            w: 0,
+            quality_kind: QualityKind::None,
+            gen_weight: 0.0,
+            shop_weight: 0.0,
+            gen_level: 0,
+            attrs,
+            material_group: String::new(),
+            material: MaterialName::default(),
+        })
+    }
+
+    fn chara_template(id: &str, ai: &str) -> Object {
+        Object::CharaTemplate(CharaTemplateObject {
+            id: id.into(),
+            img: img(),
+            race: String::new(),
+            class: CharaClass::default(),
+            faction: FactionId::default(),
+            gen_weight: 0.0,
+            gen_level: 0,
+            default_ai_kind: from_ron(&format!("\"{ai}\"")),
+            base_attr: CharaBaseAttr {
+                base_hp: 0,
+                base_mp: 0,
+                str: 0,
+                vit: 0,
+                dex: 0,
+                int: 0,
+                wil: 0,
+                cha: 0,
+                spd: 0,
+                carry: 0,
+                travel_speed: 0,
+            },
+            skill_bonus: Default::default(),
+            abilities: Vec::new(),
+            equips: Vec::new(),
+            drop_items: Vec::new(),
+        })
+    }
+
+    fn site_gen(id: &str, shop_kinds: &[&str]) -> Object {
+        Object::SiteGen(SiteGenObject {
+            id: id.into(),
+            kind: SiteKind::Town,
+            site_symbol: SiteSymbolKind::from("town"),
+            default_faction_id: FactionId::default(),
+            map_template_id: Vec::new(),
+            npcs: Vec::new(),
+            shops: shop_kinds
+                .iter()
+                .enumerate()
+                .map(|(i, shop_kind)| {
+                    let shop = ShopGenData {
+                        shop_kind: shop_kind.to_string(),
+                        item_selector: Default::default(),
+                    };
+                    (NpcGenId::Site(i as u32), shop)
+                })
+                .collect(),
+            quests: Vec::new(),
+            delivery_chest: None,
+        })
+    }
+
+    fn tile(id: &str) -> Object {
+        Object::Tile(TileObject {
+            id: id.into(),
+            img: img(),
+            kind: TileKind::Ground,
+            symbol_color: (0, 0, 0),
+            fertility: 0,
+            build_skill: None,
+            materials: Vec::new(),
+        })
+    }
+
+    fn wall(id: &str) -> Object {
+        Object::Wall(WallObject {
+            id: id.into(),
+            hp: 1,
+            base_draw: false,
+            img: img(),
+            symbol_color: (0, 0, 0),
+            build_skill: None,
+            materials: Vec::new(),
+            mining_rewards: Vec::new(),
+        })
+    }
+
+    fn ui_img(id: &str) -> Object {
+        Object::UiImg(UiImgObject {
+            id: id.into(),
+            img: img(),
+            hot: (0, 0),
+        })
+    }
+
+    #[test]
+    fn unknown_trait_conflict() {
+        let traits: CharaTraits = from_ron(
+            r#"{
+                "strong": (conflicts: ["weak"], modifiers: []),
+                "fast": (conflicts: ["clumsy"], modifiers: []),
+                "weak": (conflicts: ["strong"], modifiers: []),
+            }"#,
+        );
+        let mut report = ValidationReport::default();
+        traits.validate(&mut report);
+        assert_problems(
+            &report,
+            &[(
+                Severity::Error,
+                "trait \"fast\" conflicts with unknown trait \"clumsy\"",
+            )],
+        );
+    }
+
+    #[test]
+    fn npc_ai() {
+        let objholder = ObjectHolder::from_objs(vec![
+            chara_template("guard", "guard"),
+            chara_template("slime", "unknown"),
+        ]);
+        let ai = "(move_kind: Wander, pathfinding_step: 1)";
+        let npc_ai: NpcAIs = from_ron(&format!("{{\"guard\": {ai}, \"unused\": {ai}}}"));
+        let mut report = ValidationReport::default();
+        npc_ai.validate(&objholder, &mut report);
+        assert_problems(
+            &report,
+            &[
+                (Severity::Error, "default npc ai is not defined"),
+                (
+                    Severity::Warning,
+                    "unknown npc ai \"unknown\" in chara template \"slime\"",
+                ),
+                (Severity::Warning, "npc ai \"unused\" is not used"),
+            ],
+        );
+    }
+
+    #[test]
+    fn status_effects() {
+        let effect = |status: &str| Effect {
+            kind: vec![EffectKind::Status {
+                status: status.into(),
+            }],
+            ..Effect::default()
+        };
+        let objholder = ObjectHolder::from_objs(vec![
+            ui_img("icon-poisoned"),
+            item(
+                "antidote",
+                "potion",
+                vec![ItemObjAttr::Medical {
+                    effect: effect("poisoned"),
+                }],
+            ),
+            item(
+                "sleeping-gas",
+                "throwing",
+                vec![ItemObjAttr::Throw {
+                    effect: effect("sleeping"),
+                }],
+            ),
+        ]);
+        let status_effects: StatusEffects = from_ron(
+            r#"{
+                "poisoned": (duration: Power(0.5), stacking: Stack(3), icon: "icon-poisoned"),
+                "asleep": (duration: Power(0.0), icon: "icon-asleep"),
+                "scanned": (stacking: Stack(0)),
+            }"#,
+        );
+        let abilities: Abilities = from_ron("{}");
+        let mut report = ValidationReport::default();
+        status_effects.validate(&abilities, &objholder, &mut report);
+        assert_problems(
+            &report,
+            &[
+                (
+                    Severity::Error,
+                    "status effect \"asleep\" has non-positive duration factor",
+                ),
+                (
+                    Severity::Error,
+                    "status effect \"asleep\": unknown icon \"icon-asleep\"",
+                ),
+                (
+                    Severity::Error,
+                    "status effect \"scanned\" has zero max stacks",
+                ),
+                (
+                    Severity::Error,
+                    "unknown status effect \"sleeping\" in item \"sleeping-gas\"",
+                ),
+            ],
+        );
+    }
+
+    #[test]
+    fn dungeon_gen() {
+        let objholder = ObjectHolder::from_objs(vec![
+            tile("floor"),
+            wall("rock"),
+            item("stone", "material", Vec::new()),
+        ]);
+        let map_gen: MapGen = from_ron("(map_gen_params: {})");
+        let dungeon_gen: DungeonGen = from_ron(
+            r#"{
+                "cave": (
+                    symbol: "cave",
+                    map_gen: [("lattice", 1.0)],
+                    npc_race_probability: {},
+                    default_faction_id: "monster",
+                    terrain: [("floor", "rock"), ("grass", "tree")],
+                    sub_walls: [("rock", 1.0), ("ore", 1.0)],
+                    item_gen_probability: 0.1,
+                    item_gen_weight: [("group/material", 1.0), ("group/food", 1.0)],
+                    floor_range: (1, 2),
+                    music: "",
+                    underground: true,
+                ),
+            }"#,
+        );
+        let mut report = ValidationReport::default();
+        validate_dungeon_gen(&dungeon_gen, &map_gen, &objholder, &mut report);
+
+        let kind = format!("{:?}", dungeon_gen.keys().next().unwrap());
+        let messages = [
+            (
+                Severity::Error,
+                format!("{kind}: unknown map_gen \"lattice\""),
+            ),
+            (Severity::Error, format!("{kind}: unknown tile \"grass\"")),
+            (Severity::Error, format!("{kind}: unknown wall \"tree\"")),
+            (Severity::Error, format!("{kind}: unknown sub wall \"ore\"")),
+            (
+                Severity::Warning,
+                format!("{kind}: item selector \"group/food\" matches no items"),
+            ),
+        ];
+        let expected: Vec<(Severity, &str)> = messages
+            .iter()
+            .map(|(severity, message)| (*severity, message.as_str()))
+            .collect();
+        assert_problems(&report, &expected);
+    }
+
+    #[test]
+    fn town() {
+        let objholder = ObjectHolder::from_objs(vec![
+            item("bread", "food", Vec::new()),
+            site_gen("village", &["food", "magic", ""]),
+        ]);
+        let town: Town = from_ron(
+            r#"(
+                min_shop_items: 1,
+                max_shop_items: 2,
+                shop_kinds: {"food": "group/food", "weapon": "kind/weapon"},
+            )"#,
+        );
+        let mut report = ValidationReport::default();
+        validate_town(&town, &objholder, &mut report);
+        assert_problems(
+            &report,
+            &[
+                (
+                    Severity::Error,
+                    "unknown shop kind \"magic\" in site gen \"village\"",
+                ),
+                (Severity::Warning, "shop kind \"weapon\" is not used"),
+                (
+                    Severity::Warning,
+                    "shop kind \"weapon\": item selector \"kind/weapon\" matches no items",
+                ),
+            ],
+        );
+    }
+}
@@ -1,8 +1,9 @@
 use crate::Rule;
 use common::gamedata::Duration;
+use schemars::JsonSchema;
 
 /// Rules for game world
-#[derive(Serialize, Deserialize)]
+#[derive(Serialize, Deserialize, JsonSchema)]
 pub struct World {
     /// Restart map path
     pub restart_path: String,
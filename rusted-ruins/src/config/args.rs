@@ -13,12 +13,18 @@ struct Args {
     /// Use fixed random seed
     #[clap(long)]
     fix_rand: bool,
+    /// Treat warnings of rule validation as errors
+    #[clap(long)]
+    strict_rules: bool,
 }
 
 pub fn modify_config_by_args(mut config: Config) -> Config {
     let args = Args::parse();
 
     config.fix_rand = args.fix_rand;
+    if args.strict_rules {
+        config.strict_rules = true;
+    }
 
     if let Some(lang) = args.lang {
         config.lang = lang;
@@ -272,11 +272,54 @@ pub struct Config {
     pub scale: i32,
     #[serde(default)]
     pub fix_rand: bool,
+    /// Treat warnings of rule validation as errors
+    #[serde(default)]
+    pub strict_rules: bool,
     pub enable_joystick: bool,
     pub sound_effect_volume: i32,
     pub music_volume: i32,
+    /// The number of backup generations kept for each save
+    #[serde(default = "default_save_backups")]
+    pub save_backups: u32,
+    /// The maximum number of site maps kept in memory. 0 means unlimited
+    #[serde(default = "default_max_loaded_maps")]
+    pub max_loaded_maps: usize,
+    /// Compression codec for map files in saves: "none", "gzip" or "zstd"
+    #[serde(default)]
+    pub map_compression: filebox::Codec,
+    #[serde(default)]
+    pub autosave: AutosaveConfig,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(default)]
+pub struct AutosaveConfig {
+    /// Autosave after moving to another map
+    pub on_map_switch: bool,
+    /// Autosave interval in game hours. 0 disables interval autosave
+    pub interval_hours: u32,
+    /// Autosave before going down to the next floor
+    pub before_descending: bool,
+}
+
+impl Default for AutosaveConfig {
+    fn default() -> Self {
+        AutosaveConfig {
+            on_map_switch: true,
+            interval_hours: 6,
+            before_descending: true,
+        }
+    }
 }
 
 fn default_scale() -> i32 {
     1
 }
+
+fn default_save_backups() -> u32 {
+    3
+}
+
+fn default_max_loaded_maps() -> usize {
+    32
+}
@@ -220,6 +220,9 @@ pub struct BuildObjDialogConfig {
 pub struct ChooseSaveFileDialogConfig {
     pub rect: CfgRect,
     pub list_size: u32,
+    pub list_w: u32,
+    pub thumbnail_rect: CfgRect,
+    pub info_rect: CfgRect,
 }
 
 #[derive(Debug, Deserialize)]
@@ -33,13 +33,17 @@ impl<'a, 'b, 't, 'sdl> Context<'a, 'b, 't, 'sdl> {
         self.canvas.set_viewport(rect);
     }
 
-    pub fn render_tex<I>(&mut self, idx: I, dest: Rect)
+    pub fn render_tex<I, O>(&mut self, idx: I, dest: Rect)
     where
         for<'th> self::texture::TextureHolder<'th>:
             common::objholder::Holder<I, ReturnType = Texture<'th>>,
+        I: common::objholder::ObjectIndex<ObjectType = O> + Copy,
+        O: common::obj::ImgObject + 'static,
     {
         let tex = self.sv.tex().get(idx);
-        try_sdl!(self.canvas.copy(tex, None, dest));
+        let obj = common::gobj::get_obj(idx);
+        let src: Rect = obj.img_rect_all().into();
+        try_sdl!(self.canvas.copy(tex, src, dest));
     }
 
     pub fn render_tex_n<I, O>(&mut self, idx: I, dest: Rect, n_image: u32)
@@ -4,8 +4,11 @@ use super::textrenderer::TextRenderer;
 use super::texture::TextureHolder;
 use crate::SdlContext;
 use common::gobj;
+use sdl2::image::ImageRWops;
 use sdl2::render::{Texture, TextureCreator};
+use sdl2::rwops::RWops;
 use sdl2::video::WindowContext;
+use std::path::{Path, PathBuf};
 
 /// Includes data that isn't used by Game
 /// Used for rendering, or music/sound playing
@@ -14,6 +17,8 @@ pub struct SdlValues<'sdl, 't> {
     pub texture_holder: TextureHolder<'t>,
     pub text_renderer: TextRenderer<'sdl>,
     pub tcp: TextCachePool<'t>,
+    /// Thumbnail of the last requested save
+    thumbnail: Option<(PathBuf, Option<Texture<'t>>)>,
 }
 
 impl<'sdl, 't> SdlValues<'sdl, 't> {
@@ -23,6 +28,7 @@ impl<'sdl, 't> SdlValues<'sdl, 't> {
             texture_holder: TextureHolder::new(gobj::get_objholder(), tc),
             text_renderer: TextRenderer::new(sdl_context),
             tcp: TextCachePool::new(),
+            thumbnail: None,
         }
     }
 
@@ -37,4 +43,38 @@ impl<'sdl, 't> SdlValues<'sdl, 't> {
     pub fn tt_one(&mut self, c: &mut TextCache) -> &Texture<'_> {
         &self.tcp.group(c, &self.text_renderer, self.tc)[0]
     }
+
+    /// Get the thumbnail of a save. It is loaded again only if the save is changed.
+    pub fn thumbnail(&mut self, save_dir: &Path) -> Option<&Texture<'t>> {
+        if self.thumbnail.as_ref().map(|(path, _)| path.as_path()) != Some(save_dir) {
+            let tex = load_thumbnail(self.tc, save_dir);
+            self.thumbnail = Some((save_dir.to_owned(), tex));
+        }
+        self.thumbnail.as_ref().and_then(|(_, tex)| tex.as_ref())
+    }
+}
+
+fn load_thumbnail<'t>(
+    tc: &'t TextureCreator<WindowContext>,
+    save_dir: &Path,
+) -> Option<Texture<'t>> {
+    let path = save_dir.join(common::saveload::thumbnail::THUMBNAIL_FILE);
+    let data = std::fs::read(path).ok()?;
+    let result = RWops::from_bytes(&data)
+        .and_then(|rwops| rwops.load_png())
+        .and_then(|surface| {
+            tc.create_texture_from_surface(surface)
+                .map_err(|e| e.to_string())
+        });
+    match result {
+        Ok(tex) => Some(tex),
+        Err(e) => {
+            warn!(
+                "cannot load thumbnail in {:?}: {}",
+                save_dir.to_string_lossy(),
+                e
+            );
+            None
+        }
+    }
 }
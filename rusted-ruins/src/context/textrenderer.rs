@@ -1,3 +1,4 @@
+use super::texture::load_img_surface;
 use crate::config::visual::FontConfig;
 use crate::config::{abs_path, FONT_CFG, UI_CFG};
 use crate::SdlContext;
@@ -5,10 +6,8 @@ use common::gobj;
 use common::objholder::{ItemIdx, UiImgIdx};
 use once_cell::sync::Lazy;
 use regex::Regex;
-use sdl2::image::ImageRWops;
 use sdl2::pixels::Color;
 use sdl2::rect::Rect;
-use sdl2::rwops::RWops;
 use sdl2::surface::Surface;
 use sdl2::ttf::*;
 use std::path::PathBuf;
@@ -180,14 +179,7 @@ impl<'sdl> TextRenderer<'sdl> {
                     &gobj::get_obj(idx).img
                 }
             };
-            let rwops = match RWops::from_bytes(&img.data) {
-                Ok(rwops) => rwops,
-                Err(e) => {
-                    warn!("{}", e);
-                    continue;
-                }
-            };
-            let surface = match rwops.load() {
+            let surface = match load_img_surface(img) {
                 Ok(surface) => surface,
                 Err(e) => {
                     warn!("{}", e);
@@ -1,17 +1,21 @@
 use common::gobj;
-use common::obj::ImgObject;
+use common::hashmap::HashMap;
+use common::obj::{Img, ImgObject};
 use common::objholder::*;
 use sdl2::image::ImageRWops;
 use sdl2::rect::Rect;
-use sdl2::render::{Texture, TextureCreator};
+use sdl2::render::{BlendMode, Texture, TextureCreator};
 use sdl2::rwops::RWops;
+use sdl2::surface::Surface;
 use sdl2::video::WindowContext;
 
 macro_rules! impl_texture_holder {
     ($({$mem:ident, $idx:ty}),*) => {
         // Owns all SDL texture
         pub struct TextureHolder<'a> {
-            $(pub $mem: Vec<Texture<'a>>),*
+            /// Textures shared by objects. Objects packed into the same atlas use the same texture.
+            textures: Vec<Texture<'a>>,
+            $(pub $mem: Vec<usize>),*
         }
 
         impl<'a> TextureHolder<'a> {
@@ -24,16 +28,31 @@ macro_rules! impl_texture_holder {
                 let tc = TextureCreatorW::new(texture_creator);
 
                 let mut th = TextureHolder {
+                    textures: Vec::new(),
                     $($mem: Vec::new()),*
                 };
+                // Atlas id to texture index
+                let mut atlases: HashMap<String, usize> = HashMap::default();
 
                 $(
                     for ref o in &objholder.$mem {
-                        let texture = match tc.create_texture_from_data(&o.img.data) {
-                            Ok(o) => o,
-                            Err(_) => { unimplemented!() },
+                        let i = if let Some(atlas) = &o.img.atlas {
+                            if let Some(&i) = atlases.get(&atlas.id) {
+                                i
+                            } else {
+                                let data = match objholder.atlas.get(&atlas.id) {
+                                    Some(atlas) => &atlas.data,
+                                    None => panic!("atlas \"{}\" for \"{}\" is not found", atlas.id, o.id),
+                                };
+                                th.push_texture(&tc, data, &atlas.id);
+                                atlases.insert(atlas.id.clone(), th.textures.len() - 1);
+                                th.textures.len() - 1
+                            }
+                        } else {
+                            th.push_texture(&tc, &o.img.data, &o.id);
+                            th.textures.len() - 1
                         };
-                        th.$mem.push(texture);
+                        th.$mem.push(i);
                     }
                 )*
 
@@ -45,7 +64,7 @@ macro_rules! impl_texture_holder {
             impl<'a> Holder<$idx> for TextureHolder<'a> {
                 type ReturnType = Texture<'a>;
                 fn get(&self, idx: $idx) -> &Texture<'a> {
-                    &self.$mem[idx.as_usize()]
+                    &self.textures[self.$mem[idx.as_usize()]]
                 }
             }
         )*
@@ -64,6 +83,43 @@ impl_texture_holder! {
     {wall, WallIdx}
 }
 
+impl<'a> TextureHolder<'a> {
+    fn push_texture(&mut self, tc: &TextureCreatorW<'a>, data: &[u8], id: &str) {
+        let texture = match tc.create_texture_from_data(data) {
+            Ok(o) => o,
+            Err(e) => panic!("cannot create texture for \"{}\": {}", id, e),
+        };
+        self.textures.push(texture);
+    }
+}
+
+/// Load the whole image of an object as a surface.
+/// The image is cropped from the atlas if it is packed.
+pub fn load_img_surface(img: &Img) -> Result<Surface<'static>, String> {
+    let atlas = if let Some(atlas) = &img.atlas {
+        atlas
+    } else {
+        return RWops::from_bytes(&img.data)?.load_png();
+    };
+
+    let data = &gobj::get_objholder()
+        .atlas
+        .get(&atlas.id)
+        .ok_or_else(|| format!("atlas \"{}\" is not found", atlas.id))?
+        .data;
+    let mut atlas_surface = RWops::from_bytes(data)?.load_png()?;
+    // Copy pixels including alpha without blending
+    atlas_surface.set_blend_mode(BlendMode::None)?;
+    let (w, h) = (img.w * img.grid_nx, img.h * img.grid_ny);
+    let mut surface = Surface::new(w, h, atlas_surface.pixel_format_enum())?;
+    atlas_surface.blit(
+        Rect::new(atlas.x as i32, atlas.y as i32, w, h),
+        &mut surface,
+        None,
+    )?;
+    Ok(surface)
+}
+
 // A thin wrapper for TextureCreator
 pub struct TextureCreatorW<'a>(&'a TextureCreator<WindowContext>);
 
@@ -1,11 +1,10 @@
 use std::cell::RefCell;
 
+use crate::context::texture::load_img_surface;
 use anyhow::{anyhow, Error};
 use common::hashmap::HashMap;
 use common::{gobj, obj::UiImgObject};
-use sdl2::image::ImageRWops;
 use sdl2::mouse::Cursor as SdlCursor;
-use sdl2::rwops::RWops;
 
 thread_local!(
     static CURSORS: RefCell<Option<HashMap<Cursor, SdlCursor>>> = RefCell::new(None);
@@ -60,8 +59,7 @@ impl Cursor {
         let obj: &UiImgObject =
             gobj::get_by_id_checked(self.img_id()).ok_or_else(|| anyhow!("object not found"))?;
 
-        let rwops = RWops::from_bytes(&obj.img.data).map_err(to_err)?;
-        let surface = rwops.load().map_err(to_err)?;
+        let surface = load_img_surface(&obj.img).map_err(to_err)?;
 
         SdlCursor::from_surface(surface, obj.hot.0.into(), obj.hot.1.into()).map_err(to_err)
     }
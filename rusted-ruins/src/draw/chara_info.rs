@@ -2,9 +2,8 @@ use super::mainwin::MainWinDrawer;
 use crate::config::UI_CFG;
 use crate::context::*;
 use crate::game::Game;
-use common::gobj;
+use common::gobj::{self, ObjCache};
 use common::objholder::UiImgIdx;
-use once_cell::sync::Lazy;
 use sdl2::rect::Rect;
 
 struct DrawInfo {
@@ -29,7 +28,7 @@ impl DrawInfo {
     }
 }
 
-static DRAW_INFO: Lazy<DrawInfo> = Lazy::new(DrawInfo::init);
+static DRAW_INFO: ObjCache<DrawInfo> = ObjCache::new(DrawInfo::init);
 
 impl MainWinDrawer {
     pub fn draw_chara_info(&self, context: &mut Context<'_, '_, '_, '_>, game: &Game) {
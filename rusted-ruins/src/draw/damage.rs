@@ -2,10 +2,9 @@ use super::mainwin::MainWinDrawer;
 use crate::config::UI_CFG;
 use crate::context::*;
 use crate::damage_popup::{DamagePopup, PopupKind};
-use common::gobj;
+use common::gobj::{self, ObjCache};
 use common::objholder::UiImgIdx;
 use geom::Coords;
-use once_cell::sync::Lazy;
 use sdl2::rect::Rect;
 
 struct DigitDrawInfo {
@@ -26,8 +25,8 @@ impl DigitDrawInfo {
     }
 }
 
-static DIGIT_DRAW_INFO: Lazy<DigitDrawInfo> = Lazy::new(DigitDrawInfo::init);
-static POPUP_MISS_IDX: Lazy<UiImgIdx> = Lazy::new(|| gobj::id_to_idx("!popup-miss"));
+static DIGIT_DRAW_INFO: ObjCache<DigitDrawInfo> = ObjCache::new(DigitDrawInfo::init);
+static POPUP_MISS_IDX: ObjCache<UiImgIdx> = ObjCache::new(|| gobj::id_to_idx("!popup-miss"));
 
 impl MainWinDrawer {
     pub fn draw_damage(&self, context: &mut Context<'_, '_, '_, '_>) {
@@ -313,7 +313,8 @@ impl MainWinDrawer {
             }
             overlay::FogPattern::Fog(idx) => {
                 // src rect is fixed at right-bottom corner of image
-                let src = Rect::new(TILE_SIZE_I, TILE_SIZE_I * 2, TILE_SIZE, TILE_SIZE);
+                let (x, y) = gobj::get_obj(idx).img.offset();
+                let src = Rect::new(x + TILE_SIZE_I, y + TILE_SIZE_I * 2, TILE_SIZE, TILE_SIZE);
                 let dest = Rect::new(
                     p.0 * TILE_SIZE_I + self.dx,
                     p.1 * TILE_SIZE_I + self.dy,
@@ -334,7 +335,8 @@ impl MainWinDrawer {
             return;
         };
         let texture = context.sv.tex().get(idx);
-        let src = Rect::new(0, 0, TILE_SIZE, TILE_SIZE);
+        let (x, y) = gobj::get_obj(idx).img.offset();
+        let src = Rect::new(x, y, TILE_SIZE, TILE_SIZE);
         let (nx, ny) = self.calc_tile_num();
 
         for iy in 0..ny {
@@ -1,13 +1,12 @@
 use super::mainwin::{MainWinDrawer, TargetModeDrawInfo};
 use crate::context::*;
 use crate::game::Game;
-use common::gobj;
+use common::gobj::{self, ObjCache};
 use common::objholder::UiImgIdx;
 use geom::*;
-use once_cell::sync::Lazy;
 
-static TILE_RANGE_HIGHLIBHT: Lazy<UiImgIdx> =
-    Lazy::new(|| gobj::id_to_idx("!tile-range-highlight"));
+static TILE_RANGE_HIGHLIBHT: ObjCache<UiImgIdx> =
+    ObjCache::new(|| gobj::id_to_idx("!tile-range-highlight"));
 
 impl MainWinDrawer {
     pub fn draw_target_mode(
@@ -4,6 +4,7 @@ use crate::game::power::{calc_hit, calc_power};
 use crate::game::{extrait::*, Target};
 use crate::text::ToText;
 use common::gamedata::*;
+use rules::status_effect::ActionRestriction;
 use rules::RULES;
 
 /// Return true if success.
@@ -60,6 +61,13 @@ pub fn usable(gd: &GameData, cid: CharaId, ability_id: &AbilityId, print_log: bo
     let chara = gd.chara.get(cid);
     let cost = cost(gd, cid, ability_id);
 
+    if chara.is_restricted(ActionRestriction::Ability) {
+        if print_log {
+            game_log!("action-restricted"; chara=chara);
+        }
+        return false;
+    }
+
     if chara.sp < cost.sp {
         if print_log {
             game_log!("ability-not-enough-sp"; chara=chara);
@@ -13,6 +13,7 @@ use super::target::Target;
 use super::{Game, InfoGetter};
 use common::gamedata::*;
 use geom::*;
+use rules::status_effect::ActionRestriction;
 use rules::RULES;
 
 pub fn try_move(game: &mut Game, cid: CharaId, dir: Direction) -> bool {
@@ -40,6 +41,9 @@ pub fn try_move(game: &mut Game, cid: CharaId, dir: Direction) -> bool {
 
         match relation {
             Relationship::Ally | Relationship::Friendly | Relationship::Neutral => {
+                if restricted(game, cid, ActionRestriction::Move) {
+                    return false;
+                }
                 let current_map = game.gd.get_current_map_mut();
                 if current_map.tile[dest_tile].chara != Some(CharaId::Player) {
                     current_map.move_chara(cid, dir);
@@ -49,10 +53,16 @@ pub fn try_move(game: &mut Game, cid: CharaId, dir: Direction) -> bool {
                 }
             }
             Relationship::Hostile => {
+                if restricted(game, cid, ActionRestriction::Attack) {
+                    return false;
+                }
                 melee_attack(game, cid, other_chara);
             }
         }
     } else {
+        if restricted(game, cid, ActionRestriction::Move) {
+            return false;
+        }
         game.gd.get_current_map_mut().move_chara(cid, dir);
         if cid == CharaId::Player {
             game.anim_queue.push_player_move(dir);
@@ -62,6 +72,18 @@ pub fn try_move(game: &mut Game, cid: CharaId, dir: Direction) -> bool {
     true
 }
 
+/// Returns true if the action is restricted by status effects
+fn restricted(game: &Game, cid: CharaId, restriction: ActionRestriction) -> bool {
+    let chara = game.gd.chara.get(cid);
+    if !chara.is_restricted(restriction) {
+        return false;
+    }
+    if cid == CharaId::Player {
+        game_log!("action-restricted"; chara=chara);
+    }
+    true
+}
+
 /// Melee attack
 pub fn melee_attack(game: &mut Game, cid: CharaId, target: CharaId) {
     let (effect, power_calc) = melee_attack_effect(&game.gd, cid);
@@ -86,6 +108,9 @@ pub fn shoot_target(game: &mut Game, cid: CharaId, target: CharaId) -> bool {
     if !game.gd.target_visible(cid, target) || cid == target {
         return false;
     }
+    if restricted(game, cid, ActionRestriction::Attack) {
+        return false;
+    }
 
     let (effect, power_calc) = if let Some(result) = ranged_attack_effect(&game.gd, cid) {
         result
@@ -0,0 +1,103 @@
+use super::Game;
+use crate::config::CONFIG;
+use common::gamedata::MapId;
+use common::saveload::autosave_dir;
+use common::saveload::snapshot::SaveSnapshot;
+use once_cell::sync::Lazy;
+use std::path::PathBuf;
+use std::sync::mpsc::{channel, Sender};
+use std::sync::Mutex;
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum AutosaveTrigger {
+    MapSwitch,
+    Interval,
+    Descending,
+}
+
+/// Sender to the worker thread which writes snapshots
+static WORKER: Lazy<Mutex<Sender<(SaveSnapshot, PathBuf)>>> = Lazy::new(|| {
+    let (sender, receiver) = channel::<(SaveSnapshot, PathBuf)>();
+    std::thread::Builder::new()
+        .name("autosave".into())
+        .spawn(move || {
+            for (snapshot, dir) in receiver {
+                let _lock = WRITING.lock().unwrap();
+                match snapshot.write(&dir) {
+                    Ok(_) => info!("Autosaved to {:?}", dir.to_string_lossy()),
+                    Err(e) => warn!("Failed to autosave to {:?}: {}", dir.to_string_lossy(), e),
+                }
+            }
+        })
+        .expect("failed to start autosave thread");
+    Mutex::new(sender)
+});
+
+/// Locked while the worker thread is writing
+static WRITING: Mutex<()> = Mutex::new(());
+
+/// Wait for the worker thread if it is writing.
+/// Saving must wait for it because unloaded maps are copied from the save directory.
+pub fn wait_for_autosave() -> std::sync::MutexGuard<'static, ()> {
+    WRITING.lock().unwrap()
+}
+
+impl Game {
+    /// Take a snapshot of the current game, and write it to the autosave slot in the background.
+    /// Returns true if autosave is started.
+    pub fn autosave(&mut self, trigger: AutosaveTrigger) -> bool {
+        let config = &CONFIG.autosave;
+        let enabled = match trigger {
+            AutosaveTrigger::MapSwitch => config.on_map_switch,
+            AutosaveTrigger::Interval => config.interval_hours > 0,
+            AutosaveTrigger::Descending => config.before_descending,
+        };
+        if !enabled {
+            return false;
+        }
+        let save_dir = if let Some(save_dir) = self.save_dir.clone() {
+            save_dir
+        } else {
+            return false;
+        };
+
+        self.update_save_info();
+        let snapshot = match self.gd.snapshot(&save_dir) {
+            Ok(snapshot) => snapshot,
+            Err(e) => {
+                warn!("Failed to take a snapshot for autosave: {}", e);
+                return false;
+            }
+        };
+        trace!("Autosave by {:?}", trigger);
+        self.last_autosave = Some(self.gd.time.current_time());
+
+        let dir = autosave_dir(&save_dir);
+        if WORKER.lock().unwrap().send((snapshot, dir)).is_err() {
+            warn!("Autosave thread is not running");
+            return false;
+        }
+        true
+    }
+
+    /// Autosave if the interval has passed since the last autosave
+    pub fn check_autosave_interval(&mut self) {
+        let interval = CONFIG.autosave.interval_hours as u64;
+        if interval == 0 {
+            return;
+        }
+        let now = self.gd.time.current_time();
+        let last = *self.last_autosave.get_or_insert(now);
+        if last <= now && now.duration_from(last).hours() >= interval {
+            self.autosave(AutosaveTrigger::Interval);
+        }
+    }
+}
+
+/// Going down to the next floor in the same site or not
+pub fn is_descending(old_mid: MapId, new_mid: MapId) -> bool {
+    !old_mid.is_region_map()
+        && !new_mid.is_region_map()
+        && old_mid.sid() == new_mid.sid()
+        && new_mid.floor() > old_mid.floor()
+}
@@ -5,6 +5,7 @@ use crate::text::ToText;
 use common::gamedata::*;
 use geom::MDistRangeIter;
 use rng::{get_rng, roll_dice, Rng};
+use rules::status_effect::{ActionRestriction, CureCondition, TurnChange};
 use rules::RULES;
 
 /// This function will be called before the character's turn
@@ -35,16 +36,25 @@ pub fn preturn(game: &mut Game, cid: CharaId) -> bool {
         s.expire(&mut game.gd, cid);
     }
 
-    // Process character status
     let chara = game.gd.chara.get_mut(cid);
-    let mut is_poisoned = false;
+    chara.cure_status(|cond| matches!(cond, CureCondition::Chance(p) if rng::gen_bool(p)));
+
+    // Process character status
+    let mut turn_change = TurnChange::default();
     let mut encumbrance_damage = 0.0;
     let mut progress_anim = None;
 
     for s in chara.status.iter() {
         match *s {
-            CharaStatus::Poisoned => {
-                is_poisoned = true;
+            CharaStatus::Effect { stack, .. } => {
+                if let Some(status_effect) = s.status_effect() {
+                    let n = stack as f32;
+                    let per_turn = &status_effect.per_turn;
+                    turn_change.hp += per_turn.hp * n;
+                    turn_change.hp_damage_factor += per_turn.hp_damage_factor * n;
+                    turn_change.mp += per_turn.mp * n;
+                    turn_change.sp += per_turn.sp * n;
+                }
             }
             CharaStatus::Burdened => {
                 encumbrance_damage = RULES.chara.damage_factor_burdened;
@@ -70,12 +80,7 @@ pub fn preturn(game: &mut Game, cid: CharaId) -> bool {
         }
     }
 
-    if is_poisoned {
-        let chara = game.gd.chara.get_mut(cid);
-        let damage =
-            chara.attr.max_hp as f32 * RULES.chara.damage_factor_poisoned / chara.attr.vit as f32;
-        do_damage(game, cid, damage as i32, CharaDamageKind::Poison, None);
-    }
+    process_turn_change(game, cid, &turn_change);
 
     if encumbrance_damage > 0.0 {
         let chara = game.gd.chara.get_mut(cid);
@@ -142,6 +147,34 @@ pub fn preturn(game: &mut Game, cid: CharaId) -> bool {
     can_act(game.gd.chara.get_mut(cid))
 }
 
+/// Change HP, MP and SP by status effects
+fn process_turn_change(game: &mut Game, cid: CharaId, turn_change: &TurnChange) {
+    let chara = game.gd.chara.get_mut(cid);
+
+    if turn_change.mp != 0.0 {
+        chara.add_mp(turn_change.mp as i32);
+        chara.mp = std::cmp::max(chara.mp, 0);
+    }
+
+    let sp_damage = if turn_change.sp != 0.0 {
+        chara.add_sp(turn_change.sp, cid)
+    } else {
+        None
+    };
+    if let Some(damage) = sp_damage {
+        do_damage(game, cid, damage, CharaDamageKind::Starve, None);
+    }
+
+    let chara = game.gd.chara.get_mut(cid);
+    let hp = turn_change.hp
+        - chara.attr.max_hp as f32 * turn_change.hp_damage_factor / chara.attr.vit as f32;
+    if hp >= 1.0 {
+        chara.heal(hp as i32);
+    } else if hp < 0.0 {
+        do_damage(game, cid, -hp as i32, CharaDamageKind::Status, None);
+    }
+}
+
 /// Judges this character can act or not
 fn can_act(chara: &Chara) -> bool {
     if chara.hp < 0 {
@@ -150,9 +183,14 @@ fn can_act(chara: &Chara) -> bool {
 
     for s in chara.status.iter() {
         match *s {
-            CharaStatus::Asleep { .. } => {
-                game_log!("asleep"; chara=chara);
-                return false;
+            CharaStatus::Effect { .. } => {
+                if s.status_effect()
+                    .map(|status_effect| status_effect.is_restricted(ActionRestriction::Act))
+                    .unwrap_or(false)
+                {
+                    game_log!("cannot-act"; chara=chara, status=s);
+                    return false;
+                }
             }
             CharaStatus::Work { .. } => {
                 return false;
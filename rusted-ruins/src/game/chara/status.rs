@@ -1,11 +1,15 @@
 //! Functions for character status operation
 
 use common::gamedata::*;
+use rules::status_effect::{ActionRestriction, CureCondition, StatusEffect};
+use rules::RULES;
 
 pub trait CharaStatusOperation {
     fn add_status(&mut self, new_status: CharaStatus);
     fn remove_sp_status(&mut self);
     fn remove_encumbrance_status(&mut self);
+    fn cure_status<F: FnMut(CureCondition) -> bool>(&mut self, f: F);
+    fn is_restricted(&self, restriction: ActionRestriction) -> bool;
 }
 
 impl CharaStatusOperation for Chara {
@@ -17,21 +21,28 @@ impl CharaStatusOperation for Chara {
                     return;
                 }
             }
-            CharaStatus::Asleep {
+            CharaStatus::Effect {
+                ref id,
                 turn_left: turn_left_new,
+                ..
             } => {
+                let stacking = RULES
+                    .status_effects
+                    .get(id)
+                    .map(|status_effect| status_effect.stacking)
+                    .unwrap_or_default();
+
                 for s in self.status.iter_mut() {
-                    if let CharaStatus::Asleep { ref mut turn_left } = *s {
-                        if turn_left_new > *turn_left {
-                            *turn_left = turn_left_new;
+                    if let CharaStatus::Effect {
+                        id: ref current_id,
+                        ref mut turn_left,
+                        ref mut stack,
+                    } = *s
+                    {
+                        if current_id != id {
+                            continue;
                         }
-                        return;
-                    }
-                }
-            }
-            CharaStatus::Poisoned => {
-                for s in self.status.iter_mut() {
-                    if *s == CharaStatus::Poisoned {
+                        stacking.apply(turn_left, stack, turn_left_new);
                         return;
                     }
                 }
@@ -50,12 +61,6 @@ impl CharaStatusOperation for Chara {
                     return;
                 }
             }
-            CharaStatus::Scanned => {
-                if self.status.iter().all(|s| *s != CharaStatus::Scanned) {
-                    self.status.push(new_status);
-                }
-                return;
-            }
             _ => (),
         }
         self.status.push(new_status);
@@ -70,9 +75,29 @@ impl CharaStatusOperation for Chara {
     fn remove_encumbrance_status(&mut self) {
         self.status.retain(|s| !s.about_encumbrance());
     }
+
+    /// Remove status effects that have a cure condition satisfying `f`
+    fn cure_status<F: FnMut(CureCondition) -> bool>(&mut self, mut f: F) {
+        self.status.retain(|s| {
+            if let Some(status_effect) = s.status_effect() {
+                !status_effect.is_cured(&mut f)
+            } else {
+                true
+            }
+        });
+    }
+
+    fn is_restricted(&self, restriction: ActionRestriction) -> bool {
+        self.status.iter().any(|s| {
+            s.status_effect()
+                .map(|status_effect| status_effect.is_restricted(restriction))
+                .unwrap_or(false)
+        })
+    }
 }
 
 pub trait CharaStatusExt {
+    fn status_effect(&self) -> Option<&'static StatusEffect>;
     fn about_sp(&self) -> bool;
     fn about_encumbrance(&self) -> bool;
     fn advance_turn(&mut self, n: u16);
@@ -81,6 +106,15 @@ pub trait CharaStatusExt {
 }
 
 impl CharaStatusExt for CharaStatus {
+    /// Get the definition in rules if this is a status effect
+    fn status_effect(&self) -> Option<&'static StatusEffect> {
+        if let CharaStatus::Effect { id, .. } = self {
+            RULES.status_effects.get(id)
+        } else {
+            None
+        }
+    }
+
     fn about_sp(&self) -> bool {
         matches!(
             self,
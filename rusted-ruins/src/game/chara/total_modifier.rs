@@ -51,10 +51,20 @@ pub fn add_chara_trait(tm: &mut CharaTotalModifier, t: &CharaTrait) {
     }
 }
 
-pub fn add_status(_tm: &mut CharaTotalModifier, _status: &CharaStatus) {
-    // match status {
-    //     _ => (),
-    // }
+pub fn add_status(tm: &mut CharaTotalModifier, status: &CharaStatus) {
+    if let CharaStatus::Effect { id, stack, .. } = status {
+        let status_effect = if let Some(status_effect) = RULES.status_effects.get(id) {
+            status_effect
+        } else {
+            return;
+        };
+
+        for _ in 0..*stack {
+            for modifier in &status_effect.modifiers {
+                add_modifier(tm, modifier);
+            }
+        }
+    }
 }
 
 pub fn add_class(tm: &mut CharaTotalModifier, class: &Class) {
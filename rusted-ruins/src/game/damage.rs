@@ -4,6 +4,7 @@ use crate::game::extrait::*;
 use crate::game::{ClosureTrigger, Game, InfoGetter};
 use common::gamedata::*;
 use common::gobj;
+use rules::status_effect::CureCondition;
 use rules::RULES;
 
 use super::item::gen::{choose_item_by_item_selector, gen_item_from_idx};
@@ -14,7 +15,7 @@ pub enum CharaDamageKind {
     RangedAttack,
     Explosion,
     Direct,
-    Poison,
+    Status,
     Starve,
     Encumbrance,
 }
@@ -48,6 +49,10 @@ pub fn do_damage(
     let chara_hp = chara.hp;
 
     if chara_hp > 0 {
+        if damage > 0 {
+            chara.cure_status(|cond| matches!(cond, CureCondition::Damaged));
+        }
+
         // Faction process
         if let (Some(origin), Some(origin_faction)) = (origin, origin_faction) {
             if !chara.ai.state.is_combat() {
@@ -77,8 +82,8 @@ pub fn do_damage(
             CharaDamageKind::Direct => {
                 game_log_i!("killed"; chara=chara);
             }
-            CharaDamageKind::Poison => {
-                game_log_i!("killed-by-poison-damage"; chara=chara);
+            CharaDamageKind::Status => {
+                game_log_i!("killed-by-status-damage"; chara=chara);
             }
             CharaDamageKind::Starve => {
                 game_log_i!("killed-by-starve-damage"; chara=chara);
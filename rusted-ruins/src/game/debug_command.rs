@@ -122,12 +122,48 @@ pub fn exec_debug_command(game: &mut Game, command: &str) {
             }
             debug!("learned all recipes");
         }
+        "reload" => {
+            reload(game);
+        }
         _ => {
             game_log!("debug-command-invalid");
         }
     }
 }
 
+/// Reload objects, rules and texts from files.
+/// Indices in the current game data are converted for the reloaded objects.
+fn reload(game: &mut Game) {
+    let save_dir = if let Some(save_dir) = game.save_dir.as_ref() {
+        save_dir.clone()
+    } else {
+        return;
+    };
+
+    if let Err(e) = game.gd.reload_objs(&save_dir) {
+        error!("reloading objects failed: {:?}", e);
+        game_log!("debug-command-failed"; command="reload");
+        return;
+    }
+    if let Err(e) = rules::reload() {
+        // Continue with the previous rules
+        error!("reloading rules failed:\n{:?}", e);
+        game_log!("debug-command-failed"; command="reload");
+    }
+    common::idx_conv::set_removed_obj_policy(RULES.removed_obj.0.clone());
+    let report = RULES.validate();
+    if !report.is_empty() {
+        warn!("{}", report);
+    }
+    crate::text::reload();
+    crate::init_data_fingerprints();
+
+    game.frequent_tex = crate::game::frequent_tex::FrequentTextures::new();
+    game.ui_request
+        .push_back(crate::game::UiRequest::ReloadTextures);
+    game_log!("debug-command-reload");
+}
+
 fn gen_chara(game: &mut Game, arg1: &str) {
     let idx = if let Some(idx) = gobj::id_to_idx_checked::<CharaTemplateIdx>(arg1) {
         idx
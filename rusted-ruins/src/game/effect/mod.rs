@@ -15,6 +15,7 @@ use common::gobj;
 use common::objholder::TileIdx;
 use geom::*;
 use ordered_float::NotNan;
+use rules::RULES;
 
 pub fn do_effect<T: Into<Target>>(
     game: &mut Game,
@@ -64,7 +65,7 @@ pub fn do_effect<T: Into<Target>>(
             }
             EffectKind::Status { status } => {
                 for cid in &cids {
-                    cause_status(game, *cid, power, *status);
+                    cause_status(game, *cid, power, status);
                 }
             }
             EffectKind::WallDamage => {
@@ -187,25 +188,22 @@ fn to_shape(effect: &Effect, target: Coords, _cause: Option<Coords>) -> Option<S
 }
 
 // Cause status effect to given chara.
-fn cause_status(game: &mut Game, cid: CharaId, power: f32, status: StatusEffect) {
-    let chara = game.gd.chara.get_mut(cid);
+fn cause_status(game: &mut Game, cid: CharaId, power: f32, status: &str) {
+    let status_effect = if let Some(status_effect) = RULES.status_effects.get(status) {
+        status_effect
+    } else {
+        warn!("unknown status effect \"{}\"", status);
+        return;
+    };
 
-    match status {
-        StatusEffect::Asleep => {
-            chara.add_status(CharaStatus::Asleep {
-                turn_left: power as u16,
-            });
-            game_log_i!("fall-asleep"; chara=chara);
-        }
-        StatusEffect::Poison => {
-            chara.add_status(CharaStatus::Poisoned);
-            game_log_i!("poisoned"; chara=chara);
-        }
-        StatusEffect::Scanned => {
-            chara.add_status(CharaStatus::Scanned);
-            game_log_i!("scanned"; chara=chara);
-        }
-    }
+    let chara = game.gd.chara.get_mut(cid);
+    chara.add_status(CharaStatus::Effect {
+        id: status.to_owned(),
+        turn_left: status_effect.turns(power),
+        stack: 1,
+    });
+    let log_id = format!("status_effect-{}", status);
+    game_log_i!(&log_id; chara=chara);
 }
 
 #[extend::ext(pub)]
@@ -117,16 +117,19 @@ pub fn switch_map(game: &mut Game, destination: Destination) {
     game.ui_request.push_back(super::UiRequest::StopCentering);
     game.clear_target();
 
-    let save_dir = game.save_dir.as_ref().unwrap();
+    let map_dir = game.save_dir.as_ref().unwrap().join("maps");
     let old_mid = game.gd.get_current_mapid();
     let new_mid = destination_to_mid(&game.gd, destination);
 
+    let autosaved = super::autosave::is_descending(old_mid, new_mid)
+        && game.autosave(super::autosave::AutosaveTrigger::Descending);
+
     if !game.gd.region.map_exist(new_mid) {
         assert!(!new_mid.is_region_map());
         info!("{:?} is not exist, so try to create new floor", new_mid);
         super::dungeon_gen::extend_site_floor(&mut game.gd, new_mid.sid());
     } else {
-        game.gd.region.preload_map(new_mid, save_dir.join("maps"));
+        game.gd.region.preload_map(new_mid, &map_dir);
     }
     let new_player_pos = destination_to_pos(&game.gd, destination);
 
@@ -166,10 +169,19 @@ pub fn switch_map(game: &mut Game, destination: Destination) {
         }
     }
 
+    // Release maps not used recently from memory
+    if let Err(e) = gd.unload_old_maps(&map_dir) {
+        warn!("Failed to unload maps: {}", e);
+    }
+
     crate::audio::play_sound("floor-change");
     crate::audio::play_music(&gd.get_current_map().music);
     update::update_map(game);
     super::view::update_view_map(game);
+
+    if !autosaved {
+        game.autosave(super::autosave::AutosaveTrigger::MapSwitch);
+    }
 }
 
 fn process_map_before_switch(gd: &mut GameData, mid: MapId) {
@@ -1,6 +1,7 @@
 mod action;
 mod anim_queue;
 mod animation;
+pub mod autosave;
 pub mod building;
 pub mod chara;
 pub mod command;
@@ -72,6 +73,8 @@ pub struct Game {
     /// Player's current target of shot and similer actions
     target_chara: Option<CharaId>,
     save_dir: Option<PathBuf>,
+    /// Game time at the last autosave
+    last_autosave: Option<Time>,
     pub view_map: view::ViewMap,
     pub frequent_tex: self::frequent_tex::FrequentTextures,
 }
@@ -94,6 +97,7 @@ impl Game {
             script_state: ScriptState::default(),
             target_chara: None,
             save_dir: Some(save_dir),
+            last_autosave: None,
             view_map: view::ViewMap::new(),
             frequent_tex: self::frequent_tex::FrequentTextures::new(),
         }
@@ -113,6 +117,7 @@ impl Game {
             se,
             target_chara: None,
             save_dir: None,
+            last_autosave: None,
             view_map: view::ViewMap::new(),
             frequent_tex: self::frequent_tex::FrequentTextures::new(),
         }
@@ -138,6 +143,7 @@ impl Game {
     pub fn update_before_player_turn(&mut self) {
         time::update_time(self);
         view::update_view_map(self);
+        self.check_autosave_interval();
     }
 
     pub fn finish_player_turn(&mut self) {
@@ -252,6 +258,8 @@ pub enum DialogOpenRequest {
 /// User interface request from game
 pub enum UiRequest {
     StopCentering,
+    /// Create textures and windows again for reloaded objects
+    ReloadTextures,
     StartTargeting {
         effect: Effect,
         callback: Box<dyn Fn(&mut DoPlayerAction<'_>, self::target::Target) + 'static>,
@@ -34,6 +34,10 @@ impl<'a> DoPlayerAction<'a> {
         &mut self.0.gd
     }
 
+    pub fn save_file(&mut self) {
+        self.0.save_file();
+    }
+
     pub fn shoot(&mut self, target: Coords) {
         let map = self.gd().get_current_map();
         if let Some(target_id) = map.get_chara(target) {
@@ -356,13 +360,12 @@ impl<'a> DoPlayerAction<'a> {
 
         match cid {
             Some(cid) if cid != CharaId::Player => {
-                let scanned = self
-                    .gd()
-                    .chara
-                    .get(cid)
-                    .status
-                    .iter()
-                    .any(|status| *status == CharaStatus::Scanned);
+                let scanned = self.gd().chara.get(cid).status.iter().any(|status| {
+                    status
+                        .status_effect()
+                        .map(|status_effect| status_effect.reveal_status)
+                        .unwrap_or(false)
+                });
 
                 if scanned {
                     self.0
@@ -3,14 +3,13 @@ use crate::game::effect::do_effect;
 use crate::game::extrait::*;
 use crate::game::{Animation, InfoGetter};
 use common::gamedata::*;
-use common::gobj;
+use common::gobj::{self, ObjCache};
 use common::objholder::AnimImgIdx;
 use geom::*;
-use once_cell::sync::Lazy;
 use rules::RULES;
 use CharaId::Player;
 
-static MINING_ANIM_IDX: Lazy<AnimImgIdx> = Lazy::new(|| gobj::id_to_idx("mining"));
+static MINING_ANIM_IDX: ObjCache<AnimImgIdx> = ObjCache::new(|| gobj::id_to_idx("mining"));
 
 impl<'a> DoPlayerAction<'a> {
     pub fn use_tool(&mut self, pos: Coords) {
@@ -1,5 +1,6 @@
 use crate::game::extrait::*;
 use common::gamedata::*;
+use rules::formula::{FormulaContext, PowerFormula, Variable};
 use rules::RULES;
 
 use super::damage::CharaDamageKind;
@@ -56,7 +57,13 @@ pub fn calc_power(gd: &GameData, cid: CharaId, method: &PowerCalcMethod) -> f32
             let int = chara.attr.int as f32;
             int * (skill_level + skill_base).powf(0.5) * RULES.power.medical_power_base
         }
-        PowerCalcMethod::Custom(..) => todo!(),
+        PowerCalcMethod::Custom(ref formula) => {
+            if let Some(formula) = custom_formula(formula) {
+                formula.power.eval(&CharaFormulaContext(gd.chara.get(cid)))
+            } else {
+                0.0
+            }
+        }
     }
 }
 
@@ -114,8 +121,58 @@ pub fn calc_hit(gd: &GameData, cid: CharaId, method: &PowerCalcMethod) -> f32 {
             let dex = chara.attr.dex as f32;
             dex + skill_level
         }
-        PowerCalcMethod::Custom(..) => todo!(),
+        PowerCalcMethod::Custom(ref formula) => {
+            if let Some(hit) = custom_formula(formula).and_then(|formula| formula.hit.as_ref()) {
+                hit.eval(&CharaFormulaContext(gd.chara.get(cid)))
+            } else {
+                1.0
+            }
+        }
+    }
+}
+
+fn custom_formula(formula: &str) -> Option<&'static PowerFormula> {
+    let power_formula = RULES.abilities.formula(formula);
+    if power_formula.is_none() {
+        warn!("formula \"{}\" is not loaded", formula);
+    }
+    power_formula
+}
+
+struct CharaFormulaContext<'a>(&'a Chara);
+
+impl FormulaContext for CharaFormulaContext<'_> {
+    fn variable(&self, var: Variable) -> f32 {
+        let chara = self.0;
+        match var {
+            Variable::Str => chara.attr.str as f32,
+            Variable::Vit => chara.attr.vit as f32,
+            Variable::Dex => chara.attr.dex as f32,
+            Variable::Int => chara.attr.int as f32,
+            Variable::Wil => chara.attr.wil as f32,
+            Variable::Cha => chara.attr.cha as f32,
+            Variable::Spd => chara.attr.spd as f32,
+            Variable::Lv => chara.lv as f32,
+            Variable::MeleePower => weapon_base_power(chara, EquipSlotKind::MeleeWeapon),
+            Variable::RangedPower => weapon_base_power(chara, EquipSlotKind::RangedWeapon),
+        }
     }
+
+    fn skill_level(&self, kind: SkillKind) -> f32 {
+        self.0.skill_level(kind) as f32
+    }
+}
+
+/// Base power of the equipped weapon. Returns 0 if not equipped.
+fn weapon_base_power(chara: &Chara, slot: EquipSlotKind) -> f32 {
+    chara
+        .equip
+        .item(slot, 0)
+        .and_then(
+            |item| find_attr!(item.obj(), ItemObjAttr::Weapon { base_power, .. } => base_power),
+        )
+        .map(|base_power| base_power.0.into_inner())
+        .unwrap_or(0.0)
 }
 
 impl From<AttackKind> for CharaDamageKind {
@@ -1,12 +1,13 @@
-use crate::config::USER_DIR;
+use crate::config::{CONFIG, USER_DIR};
 use crate::game::Game;
 use common::basic::{SAVE_DIR_NAME, SAVE_EXTENSION};
-use common::gamedata::GameData;
+use common::fingerprint;
+use common::gamedata::{CharaId, GameData, MapId, SaveInfo};
 use std::fs;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
 impl Game {
-    pub fn save_file(&self) {
+    pub fn save_file(&mut self) {
         let save_dir = get_save_dir();
 
         if !save_dir.exists() {
@@ -20,13 +21,33 @@ impl Game {
         }
 
         let path = self.gd.save_dir(save_dir);
+        self.update_save_info();
 
-        match self.gd.save(&path) {
+        let _lock = super::autosave::wait_for_autosave();
+        match self.gd.save(&path, CONFIG.save_backups) {
             Ok(_) => info!("Saved to {:?}", path.to_string_lossy()),
             Err(e) => warn!("Faild to saving to {:?}: {}", path.to_string_lossy(), e),
         }
     }
 
+    /// Update information displayed in the save list
+    pub fn update_save_info(&mut self) {
+        use crate::text::ToText;
+        let gd = &self.gd;
+        let location = match gd.get_current_mapid() {
+            MapId::SiteMap { sid, floor } => {
+                format!("{} ({})", gd.region.get_site(sid).to_text(), floor + 1)
+            }
+            MapId::RegionMap { rid } => gd.region.get(rid).to_text().into_owned(),
+        };
+        let info = SaveInfo {
+            player_level: gd.chara.get(CharaId::Player).lv,
+            location,
+            play_time: gd.play_time.seconds(),
+        };
+        self.gd.meta.set_info(info);
+    }
+
     pub fn clean_save_data(&self) {
         let save_dir = get_save_dir();
         let path = self.gd.save_dir(save_dir);
@@ -62,12 +83,129 @@ pub fn save_file_list() -> Result<Vec<PathBuf>, std::io::Error> {
 
         if extension.is_some() && extension.unwrap() == SAVE_EXTENSION {
             list.push(path);
+        } else if autosave_to_save_dir(&path).is_some() {
+            list.push(path);
+        } else if let Some(path) = backup_to_save_dir(&path) {
+            // The save directory may be lost by crash during saving, but it can be restored from backups
+            if !path.exists() && !list.contains(&path) {
+                list.push(path);
+            }
         }
     }
 
     Ok(list)
 }
 
+/// Get save directory path from backup directory path
+fn backup_to_save_dir(path: &Path) -> Option<PathBuf> {
+    let extension = path.extension()?.to_str()?;
+    if !extension.starts_with("bak") {
+        return None;
+    }
+    let save_dir = path.with_extension("");
+    if save_dir.extension()? == SAVE_EXTENSION {
+        Some(save_dir)
+    } else {
+        None
+    }
+}
+
+/// Get save directory path from autosave directory path
+pub fn autosave_to_save_dir(path: &Path) -> Option<PathBuf> {
+    if path.extension()? != "auto" {
+        return None;
+    }
+    let save_dir = path.with_extension("");
+    if save_dir.extension()? == SAVE_EXTENSION {
+        Some(save_dir)
+    } else {
+        None
+    }
+}
+
+/// Load game data from a path in save_file_list()
+pub fn load_save_file(path: &Path) -> Result<GameData, anyhow::Error> {
+    if let Some(save_dir) = autosave_to_save_dir(path) {
+        GameData::load_autosave(save_dir)
+    } else {
+        GameData::load_with_backups(path)
+    }
+}
+
+/// Create a message if paks or rules used in the save are different from the current ones
+pub fn data_mismatch_message(path: &Path) -> Option<String> {
+    let meta = common::saveload::read_metadata(path).ok()?;
+    // Saves created before recording data have no fingerprints
+    if meta.data_fingerprints().is_empty() {
+        return None;
+    }
+    let diff = fingerprint::compare(meta.data_fingerprints(), &fingerprint::current());
+    if diff.is_empty() {
+        return None;
+    }
+
+    let mut msg = crate::text::ui_txt("dialog-data_mismatch");
+    for (list, id) in [
+        (&diff.missing, "dialog-data_mismatch-missing"),
+        (&diff.added, "dialog-data_mismatch-added"),
+        (&diff.changed, "dialog-data_mismatch-changed"),
+    ] {
+        if list.is_empty() {
+            continue;
+        }
+        let names = list
+            .iter()
+            .map(|data| data.name.as_str())
+            .collect::<Vec<_>>()
+            .join(", ");
+        msg.push('\n');
+        msg.push_str(&ui_txt_format!(id; list = names));
+    }
+    msg.push('\n');
+    msg.push_str(&crate::text::ui_txt("dialog-data_mismatch-confirm"));
+    Some(msg)
+}
+
+/// Text of player level, location and play time recorded in the save
+pub fn save_info_text(path: &Path) -> String {
+    let meta = match common::saveload::read_metadata(path) {
+        Ok(meta) => meta,
+        Err(_) => return String::new(),
+    };
+    // Saves created before recording information have no info
+    let info = if let Some(info) = meta.info() {
+        info
+    } else {
+        return String::new();
+    };
+    let s = info.play_time;
+    format!(
+        "{}\n{}\n{}  {:02}:{:02}:{:02}",
+        ui_txt_format!("label_text-save_info-level"; level = info.player_level),
+        info.location,
+        crate::text::ui_txt("label_text-play_time"),
+        s / 3600,
+        (s / 60) % 60,
+        s % 60
+    )
+}
+
+/// Name of the save to be displayed
+pub fn save_file_name(path: &Path) -> String {
+    if let Some(save_dir) = autosave_to_save_dir(path) {
+        format!(
+            "{} ({})",
+            save_dir.file_stem().unwrap_or_default().to_string_lossy(),
+            crate::text::ui_txt("save-autosave")
+        )
+    } else {
+        path.file_stem()
+            .unwrap_or_default()
+            .to_string_lossy()
+            .into_owned()
+    }
+}
+
 /// Generate random id for FileBox
 pub fn gen_box_id(gd: &GameData) -> u64 {
     use rng::*;
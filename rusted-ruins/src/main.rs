@@ -45,6 +45,7 @@ fn main() {
     init_obj();
     // Must be after init_obj()
     init_rules();
+    init_data_fingerprints();
 
     let sdl_context = SdlContext::init();
     let mut screen = screen::Screen::new(&sdl_context.sdl_context);
@@ -90,6 +91,8 @@ fn init_obj() {
         d.push("paks");
     }
     common::gobj::init(data_dirs);
+    common::gamedata::set_max_loaded_maps(crate::config::CONFIG.max_loaded_maps);
+    filebox::set_codec(crate::config::CONFIG.map_compression);
 }
 
 fn init_rules() {
@@ -97,6 +100,36 @@ fn init_rules() {
         &*crate::config::ASSETS_DIR,
         crate::config::ADDON_DIR.as_ref(),
     );
+    common::idx_conv::set_removed_obj_policy(rules::RULES.removed_obj.0.clone());
+
+    let report = rules::RULES.validate();
+    if report.is_failed(crate::config::CONFIG.strict_rules) {
+        error!("{}", report);
+        std::process::exit(1);
+    } else if !report.is_empty() {
+        warn!("{}", report);
+    }
+}
+
+/// Record paks and rules to be written in saves
+fn init_data_fingerprints() {
+    let pak_dirs: Vec<_> = crate::config::get_data_dirs()
+        .into_iter()
+        .map(|d| d.join("paks"))
+        .collect();
+    let mut fingerprints = common::fingerprint::pak_fingerprints(&pak_dirs);
+
+    let mut rule_dirs = vec![crate::config::ASSETS_DIR.join("rules")];
+    if let Some(addon_dir) = crate::config::ADDON_DIR.as_ref() {
+        rule_dirs.push(addon_dir.join("rules"));
+    }
+    fingerprints.extend(
+        rule_dirs
+            .iter()
+            .filter_map(common::fingerprint::rules_fingerprint),
+    );
+
+    common::fingerprint::set_current(fingerprints);
 }
 
 /// Setup logger. It is not game logger. It is for debug and warning information.
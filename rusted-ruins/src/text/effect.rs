@@ -1,6 +1,6 @@
 use common::gamedata::{Effect, EffectKind};
 
-use super::{misc_txt, ToText};
+use super::{misc_txt, status_effect_txt, ToText};
 
 pub const UI_IMG_ID_ITEM_INFO: &str = "!icon-item-info";
 
@@ -42,7 +42,7 @@ impl EffectText {
             EffectKind::Direct { .. } => ("!", misc_txt_format!("effect_kind-direct"; power=power)),
             EffectKind::Status { status } => (
                 "!",
-                misc_txt_format!("effect_kind-status"; status=status, hit=hit),
+                misc_txt_format!("effect_kind-status"; status=status_effect_txt(status).as_str(), hit=hit),
             ),
             EffectKind::WallDamage => ("!", misc_txt("effect_kind-wall_damage")),
             EffectKind::CharaScan => ("!", misc_txt("effect_kind-chara_scan")),
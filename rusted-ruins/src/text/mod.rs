@@ -10,6 +10,7 @@ mod to_text;
 
 use crate::config;
 use common::basic;
+use common::reloadable::Reloadable;
 use fluent::concurrent::FluentBundle;
 use fluent::{FluentArgs, FluentResource};
 use once_cell::sync::Lazy;
@@ -22,26 +23,41 @@ pub use to_text::CharaTraitTextId;
 
 /// Initialize lazy static
 pub fn init() {
-    Lazy::force(&ABILITY_BUNDLE);
-    Lazy::force(&FLAVOR_BUNDLE);
-    Lazy::force(&LOG_BUNDLE);
-    Lazy::force(&MISC_BUNDLE);
-    Lazy::force(&OBJ_BUNDLE);
-    Lazy::force(&QUEST_BUNDLE);
-    Lazy::force(&READABLE_BUNDLE);
-    Lazy::force(&TALK_BUNDLE);
-    Lazy::force(&UI_BUNDLE);
+    Reloadable::force(&ABILITY_BUNDLE);
+    Reloadable::force(&FLAVOR_BUNDLE);
+    Reloadable::force(&LOG_BUNDLE);
+    Reloadable::force(&MISC_BUNDLE);
+    Reloadable::force(&OBJ_BUNDLE);
+    Reloadable::force(&QUEST_BUNDLE);
+    Reloadable::force(&READABLE_BUNDLE);
+    Reloadable::force(&TALK_BUNDLE);
+    Reloadable::force(&UI_BUNDLE);
 }
 
-static ABILITY_BUNDLE: Lazy<Bundle> = Lazy::new(|| Bundle::load(basic::ABILITY_TXT_DIR));
-static FLAVOR_BUNDLE: Lazy<Bundle> = Lazy::new(|| Bundle::load(basic::FLAVOR_TXT_DIR));
-static LOG_BUNDLE: Lazy<Bundle> = Lazy::new(|| Bundle::load(basic::LOG_TXT_DIR));
-static MISC_BUNDLE: Lazy<Bundle> = Lazy::new(|| Bundle::load(basic::MISC_TXT_DIR));
-static OBJ_BUNDLE: Lazy<Bundle> = Lazy::new(|| Bundle::load(basic::OBJ_TXT_DIR));
-static QUEST_BUNDLE: Lazy<Bundle> = Lazy::new(|| Bundle::load(basic::QUEST_TXT_DIR));
-static READABLE_BUNDLE: Lazy<Bundle> = Lazy::new(|| Bundle::load(basic::READABLE_TXT_DIR));
-static TALK_BUNDLE: Lazy<Bundle> = Lazy::new(|| Bundle::load(basic::TALK_TXT_DIR));
-static UI_BUNDLE: Lazy<Bundle> = Lazy::new(|| Bundle::load(basic::UI_TXT_DIR));
+/// Load text files again
+pub fn reload() {
+    ABILITY_BUNDLE.replace(Bundle::load(basic::ABILITY_TXT_DIR));
+    FLAVOR_BUNDLE.replace(Bundle::load(basic::FLAVOR_TXT_DIR));
+    LOG_BUNDLE.replace(Bundle::load(basic::LOG_TXT_DIR));
+    MISC_BUNDLE.replace(Bundle::load(basic::MISC_TXT_DIR));
+    OBJ_BUNDLE.replace(Bundle::load(basic::OBJ_TXT_DIR));
+    QUEST_BUNDLE.replace(Bundle::load(basic::QUEST_TXT_DIR));
+    READABLE_BUNDLE.replace(Bundle::load(basic::READABLE_TXT_DIR));
+    TALK_BUNDLE.replace(Bundle::load(basic::TALK_TXT_DIR));
+    UI_BUNDLE.replace(Bundle::load(basic::UI_TXT_DIR));
+}
+
+static ABILITY_BUNDLE: Reloadable<Bundle> =
+    Reloadable::new(|| Bundle::load(basic::ABILITY_TXT_DIR));
+static FLAVOR_BUNDLE: Reloadable<Bundle> = Reloadable::new(|| Bundle::load(basic::FLAVOR_TXT_DIR));
+static LOG_BUNDLE: Reloadable<Bundle> = Reloadable::new(|| Bundle::load(basic::LOG_TXT_DIR));
+static MISC_BUNDLE: Reloadable<Bundle> = Reloadable::new(|| Bundle::load(basic::MISC_TXT_DIR));
+static OBJ_BUNDLE: Reloadable<Bundle> = Reloadable::new(|| Bundle::load(basic::OBJ_TXT_DIR));
+static QUEST_BUNDLE: Reloadable<Bundle> = Reloadable::new(|| Bundle::load(basic::QUEST_TXT_DIR));
+static READABLE_BUNDLE: Reloadable<Bundle> =
+    Reloadable::new(|| Bundle::load(basic::READABLE_TXT_DIR));
+static TALK_BUNDLE: Reloadable<Bundle> = Reloadable::new(|| Bundle::load(basic::TALK_TXT_DIR));
+static UI_BUNDLE: Reloadable<Bundle> = Reloadable::new(|| Bundle::load(basic::UI_TXT_DIR));
 
 struct Bundle {
     first: FluentBundle<FluentResource>,
@@ -271,6 +287,11 @@ pub fn misc_txt_checked(id: &str, args: Option<&FluentArgs<'_>>) -> Option<Strin
     MISC_BUNDLE.format(id, args)
 }
 
+/// Name of the status effect defined in rules
+pub fn status_effect_txt(id: &str) -> String {
+    misc_txt(&format!("chara_status-{id}"))
+}
+
 /// This is helper trait for some data objects that need to be printed in game.
 /// Logging macros use this.
 pub trait ToText {
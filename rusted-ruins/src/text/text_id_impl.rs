@@ -3,24 +3,6 @@ use super::ToTextId;
 use common::basic::BonusLevel;
 use common::gamedata::*;
 
-impl ToTextId for CharaStatus {
-    fn to_textid(&self) -> &'static str {
-        match *self {
-            CharaStatus::Hungry => "chara_status-hungry",
-            CharaStatus::Weak => "chara_status-weak",
-            CharaStatus::Starving => "chara_status-starving",
-            CharaStatus::Burdened => "chara_status-burdened",
-            CharaStatus::Stressed => "chara_status-stressed",
-            CharaStatus::Strained => "chara_status-strained",
-            CharaStatus::Overloaded => "chara_status-overloaded",
-            CharaStatus::Scanned => "chara_status-scanned",
-            CharaStatus::Asleep { .. } => "chara_status-asleep",
-            CharaStatus::Poisoned => "chara_status-poisoned",
-            CharaStatus::Work { .. } => "chara_status-work",
-        }
-    }
-}
-
 impl ToTextId for BonusLevel {
     fn to_textid(&self) -> &'static str {
         match *self {
@@ -38,16 +20,6 @@ impl ToTextId for BonusLevel {
     }
 }
 
-impl ToTextId for StatusEffect {
-    fn to_textid(&self) -> &'static str {
-        match self {
-            StatusEffect::Asleep => "chara_status-asleep",
-            StatusEffect::Poison => "chara_status-poisoned",
-            StatusEffect::Scanned => "chara_status-scanned",
-        }
-    }
-}
-
 impl ToTextId for Element {
     fn to_textid(&self) -> &'static str {
         match *self {
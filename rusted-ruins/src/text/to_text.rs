@@ -121,6 +121,30 @@ impl ToText for CharaTrait {
     }
 }
 
+impl ToText for CharaStatus {
+    fn to_text(&self) -> Cow<'_, str> {
+        let id = match self {
+            CharaStatus::Hungry => "chara_status-hungry",
+            CharaStatus::Weak => "chara_status-weak",
+            CharaStatus::Starving => "chara_status-starving",
+            CharaStatus::Burdened => "chara_status-burdened",
+            CharaStatus::Stressed => "chara_status-stressed",
+            CharaStatus::Strained => "chara_status-strained",
+            CharaStatus::Overloaded => "chara_status-overloaded",
+            CharaStatus::Effect { id, stack, .. } => {
+                let text = text::status_effect_txt(id);
+                return if *stack > 1 {
+                    format!("{text} x{stack}").into()
+                } else {
+                    text.into()
+                };
+            }
+            CharaStatus::Work { .. } => "chara_status-work",
+        };
+        misc_txt(id).into()
+    }
+}
+
 impl ToText for CharaModifier {
     fn to_text(&self) -> Cow<'_, str> {
         match self {
@@ -78,7 +78,7 @@ impl DialogWindow for ExitWindow {
         }
 
         if self.save_button.process_command(&command).is_some() {
-            pa.game().save_file();
+            pa.save_file();
             DialogResult::Close
         } else if self.title_screen_button.process_command(&command).is_some() {
             DialogResult::Special(SpecialDialogResult::ReturnToStartScreen)
@@ -4,6 +4,7 @@ use super::widget::WidgetTrait;
 use crate::context::textrenderer::FontKind;
 use crate::game::command::MouseButton;
 use common::basic::*;
+use common::gobj::ObjCache;
 use common::objholder::*;
 use once_cell::sync::Lazy;
 use std::collections::HashMap;
@@ -243,7 +244,8 @@ impl Window for TabsNavigator {
         _game: &Game,
         _anim: Option<(&Animation, u32)>,
     ) {
-        static MAKE_DARK_IDX: Lazy<UiImgIdx> = Lazy::new(|| common::gobj::id_to_idx("!make-dark"));
+        static MAKE_DARK_IDX: ObjCache<UiImgIdx> =
+            ObjCache::new(|| common::gobj::id_to_idx("!make-dark"));
         crate::draw::border::draw_window_border(context, self.rect);
 
         use sdl2::pixels::Color;
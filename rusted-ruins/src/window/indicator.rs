@@ -2,6 +2,7 @@ use super::commonuse::*;
 use super::widget::*;
 use crate::config::SCREEN_CFG;
 use crate::context::textrenderer::FontKind;
+use crate::game::extrait::CharaStatusExt;
 use crate::game::InfoGetter;
 use crate::text::ToText;
 use common::gamedata::*;
@@ -230,6 +231,7 @@ impl Window for TimeInfo {
 
 pub struct StatusInfo {
     labels: Vec<LabelWidget>,
+    icons: Vec<ImageWidget>,
     status: Vec<CharaStatus>,
 }
 
@@ -237,6 +239,7 @@ impl StatusInfo {
     pub fn new() -> StatusInfo {
         StatusInfo {
             labels: Vec::new(),
+            icons: Vec::new(),
             status: Vec::new(),
         }
     }
@@ -249,12 +252,24 @@ impl StatusInfo {
             self.status.clone_from(&player_chara.status);
 
             self.labels.clear();
+            self.icons.clear();
             for (i, status) in self.status.iter().enumerate() {
-                let label = LabelWidget::bordered(
-                    Rect::new(rect.x, rect.y - rect.h * i as i32, 1, 1),
-                    &crate::text::to_txt(status),
-                    FontKind::T,
-                );
+                let y = rect.y - rect.h * i as i32;
+                let icon = status
+                    .status_effect()
+                    .filter(|status_effect| !status_effect.icon.is_empty())
+                    .and_then(|status_effect| gobj::id_to_idx_checked(&status_effect.icon));
+                let x = if let Some(icon) = icon {
+                    self.icons.push(ImageWidget::new(
+                        Rect::new(rect.x, y, rect.h as u32, rect.h as u32),
+                        ImageIdx::UiImg(icon),
+                    ));
+                    rect.x + rect.h
+                } else {
+                    rect.x
+                };
+                let label =
+                    LabelWidget::bordered(Rect::new(x, y, 1, 1), &status.to_text(), FontKind::T);
                 self.labels.push(label);
             }
         }
@@ -271,6 +286,9 @@ impl Window for StatusInfo {
         self.update(game);
 
         context.set_viewport(None);
+        for icon in self.icons.iter_mut() {
+            icon.draw(context);
+        }
         for label in self.labels.iter_mut() {
             label.draw(context);
         }
@@ -719,6 +719,17 @@ impl<'sdl, 't> WindowManager<'sdl, 't> {
                         windows.main_window.stop_centering_mode();
                     }
                 }
+                UiRequest::ReloadTextures => {
+                    self.sdl_values.texture_holder = crate::context::texture::TextureHolder::new(
+                        common::gobj::get_objholder(),
+                        self.sdl_values.tc,
+                    );
+                    crate::cursor::load();
+                    // Windows may have indices of the previous objects
+                    if self.mode.is_on_game() {
+                        self.mode = WindowManageMode::OnGame(GameWindows::new());
+                    }
+                }
                 UiRequest::StartTargeting { effect, callback } => {
                     if let WindowManageMode::OnGame(ref mut windows) = self.mode {
                         windows
@@ -2,16 +2,15 @@ use super::commonuse::*;
 use crate::config::SCREEN_CFG;
 use crate::config::UI_CFG;
 use crate::game::command::MouseButton;
-use common::gobj;
+use common::gobj::{self, ObjCache};
 use common::objholder::UiImgIdx;
-use once_cell::sync::Lazy;
 
 pub struct Sidebar {
     rect: Rect,
     mouseover: Option<u32>,
 }
 
-static ICON_IDX: Lazy<UiImgIdx> = Lazy::new(|| gobj::id_to_idx("sidebar-icon"));
+static ICON_IDX: ObjCache<UiImgIdx> = ObjCache::new(|| gobj::id_to_idx("sidebar-icon"));
 
 const ITEM_INVENTORY: u32 = 0;
 const ITEM_CHARAINFO: u32 = 1;
@@ -1,11 +1,10 @@
 use super::commonuse::*;
+use super::msg_dialog::MsgDialog;
 use super::widget::*;
 use super::SpecialDialogResult;
 use crate::config::{SCREEN_CFG, UI_CFG};
 use crate::text;
-use common::gamedata::GameData;
-use std::ffi::OsStr;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
 pub struct StartWindow {
     title_screen: ImageWidget,
@@ -95,29 +94,40 @@ pub struct ChooseSaveFileDialog {
     closer: DialogCloser,
     list: TextListWidget,
     save_files: Vec<PathBuf>,
+    info_texts: Vec<String>,
+    info_label: LabelWidget,
 }
 
 impl ChooseSaveFileDialog {
     pub fn new() -> ChooseSaveFileDialog {
+        let cfg = &UI_CFG.choose_save_file_dialog;
         let save_files =
             crate::game::saveload::save_file_list().expect("Error at reading save file directory");
 
         let file_name_list: Vec<String> = save_files
             .iter()
-            .map(|path| {
-                path.file_stem()
-                    .unwrap_or_else(|| OsStr::new(""))
-                    .to_string_lossy()
-                    .into_owned()
-            })
+            .map(|path| crate::game::saveload::save_file_name(path))
             .collect();
-        let rect = UI_CFG.choose_save_file_dialog.rect.into();
+        let info_texts: Vec<String> = save_files
+            .iter()
+            .map(|path| crate::game::saveload::save_info_text(path))
+            .collect();
+        let rect = cfg.rect.into();
+        let info_rect: Rect = cfg.info_rect.into();
+        let info_label = LabelWidget::wrapped(
+            info_rect,
+            info_texts.first().cloned().unwrap_or_default(),
+            FontKind::S,
+            info_rect.width(),
+        );
 
         ChooseSaveFileDialog {
             rect,
             closer: DialogCloser::new(rect),
-            list: TextListWidget::text_choices((0, 0, rect.width(), rect.height()), file_name_list),
+            list: TextListWidget::text_choices((0, 0, cfg.list_w, rect.height()), file_name_list),
             save_files,
+            info_texts,
+            info_label,
         }
     }
 }
@@ -132,6 +142,34 @@ impl Window for ChooseSaveFileDialog {
         self.closer.draw(context);
         draw_window_border(context, self.rect);
         self.list.draw(context);
+
+        let cfg = &UI_CFG.choose_save_file_dialog;
+        let line_x = cfg.list_w as i32;
+        let h = self.rect.height() as i32;
+        context.draw_line((line_x, 0), (line_x, h), UI_CFG.color.border_light);
+        context.draw_line((line_x + 1, 0), (line_x + 1, h), UI_CFG.color.border_dark);
+
+        let i = self.list.get_current_choice() as usize;
+        if let Some(path) = self.save_files.get(i) {
+            if let Some(tex) = context.sv.thumbnail(path) {
+                // Keep the aspect ratio in the thumbnail area
+                let area: Rect = cfg.thumbnail_rect.into();
+                let query = tex.query();
+                let scale = f32::min(
+                    area.width() as f32 / query.width as f32,
+                    area.height() as f32 / query.height as f32,
+                );
+                let mut dest = Rect::new(
+                    0,
+                    0,
+                    (query.width as f32 * scale) as u32,
+                    (query.height as f32 * scale) as u32,
+                );
+                dest.center_on(area.center());
+                try_sdl!(context.canvas.copy(tex, None, dest));
+            }
+        }
+        self.info_label.draw(context);
     }
 }
 
@@ -140,19 +178,27 @@ impl DialogWindow for ChooseSaveFileDialog {
         closer!(self, command, false);
         let command = command.relative_to(self.rect);
         if let Some(response) = self.list.process_command(&command) {
+            if let ListWidgetResponse::SelectionChanged(i) = response {
+                self.info_label
+                    .set_text(self.info_texts[i as usize].as_str());
+            }
             if let ListWidgetResponse::Select(i) = response {
                 // Any item is selected
-                match GameData::load(&self.save_files[i as usize]) {
-                    Ok(gd) => {
-                        return DialogResult::Special(SpecialDialogResult::NewGameStart(Box::new(
-                            gd,
-                        )));
-                    }
-                    Err(e) => {
-                        warn!("Failed to load a save file: {}", e);
-                        return DialogResult::Continue;
-                    }
+                let path = self.save_files[i as usize].clone();
+                if let Some(msg) = crate::game::saveload::data_mismatch_message(&path) {
+                    let dialog = MsgDialog::with_yesno(&msg, move |_, n| {
+                        if n == 0 {
+                            match load_save_file(&path) {
+                                DialogResult::Continue => DialogResult::Close,
+                                result => result,
+                            }
+                        } else {
+                            DialogResult::Close
+                        }
+                    });
+                    return DialogResult::OpenChildDialog(Box::new(dialog));
                 }
+                return load_save_file(&path);
             }
             return DialogResult::Continue;
         }
@@ -164,3 +210,13 @@ impl DialogWindow for ChooseSaveFileDialog {
 
     fn sound(&self, _: bool) {}
 }
+
+fn load_save_file(path: &Path) -> DialogResult {
+    match crate::game::saveload::load_save_file(path) {
+        Ok(gd) => DialogResult::Special(SpecialDialogResult::NewGameStart(Box::new(gd))),
+        Err(e) => {
+            warn!("Failed to load a save file: {}", e);
+            DialogResult::Continue
+        }
+    }
+}
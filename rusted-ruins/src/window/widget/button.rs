@@ -122,7 +122,7 @@ impl WidgetTrait for ButtonWidget {
             let x = self.rect.x + (self.rect.w - w as i32) / 2;
             let y = self.rect.y + (self.rect.h - h as i32) / 2;
             let dest = Rect::new(x, y, w, h);
-            try_sdl!(context.canvas.copy(tex, None, dest));
+            try_sdl!(context.canvas.copy(tex, orig, dest));
         }
     }
 }
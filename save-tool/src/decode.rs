@@ -0,0 +1,118 @@
+use crate::format::{self, Format};
+use crate::idtable::IdTable;
+use anyhow::{Context, Result};
+use common::basic::SAVE_FORMAT_VERSION;
+use common::gamedata::{BoxedMap, GameData, MetaData};
+use common::saveload::migration;
+use common::saveload::thumbnail::THUMBNAIL_FILE;
+use std::fs::{self, File};
+use std::io::BufReader;
+use std::path::Path;
+
+/// Decode all files in the save directory.
+/// Data written in older save format is migrated to the current layout.
+pub fn decode(save_dir: &Path, output_dir: &Path, format: Format) -> Result<()> {
+    let mut meta = read_metadata(save_dir)?;
+    let version = meta.format_version();
+    migration::check_version(version)?;
+
+    fs::create_dir_all(output_dir.join("maps"))?;
+
+    let idtable = read_idtable(save_dir)?;
+    format::write(&output_dir.join("idtable"), &idtable, format)?;
+
+    let file = BufReader::new(File::open(save_dir.join("gamedata")).context("opening gamedata")?);
+    let gamedata: GameData =
+        migration::read_gamedata(file, version).context("decoding gamedata")?;
+    format::write(&output_dir.join("gamedata"), &gamedata, format)?;
+
+    let map_dir = save_dir.join("maps");
+    migration::set_map_format_version(version);
+    for id in map_ids(&map_dir)? {
+        let mut map = BoxedMap::empty(id);
+        map.read(&map_dir, false)
+            .with_context(|| format!("decoding map {id:016x}"))?;
+        let path = output_dir.join("maps").join(format!("{id:016x}"));
+        format::write(&path, &*map, format)?;
+    }
+    migration::set_map_format_version(SAVE_FORMAT_VERSION);
+
+    // Decoded data always has the current layout
+    meta.set_format_version(SAVE_FORMAT_VERSION);
+    format::write(&output_dir.join("metadata"), &meta, format)?;
+
+    let thumbnail = save_dir.join(THUMBNAIL_FILE);
+    if thumbnail.exists() {
+        fs::copy(&thumbnail, output_dir.join(THUMBNAIL_FILE)).context("copying thumbnail")?;
+    }
+
+    Ok(())
+}
+
+/// Print summary of the save directory
+pub fn print_info(save_dir: &Path) -> Result<()> {
+    let meta = read_metadata(save_dir)?;
+    println!("save name      : {}", meta.save_name());
+    println!("format version : {}", meta.format_version());
+
+    let idtable = read_idtable(save_dir)?;
+    println!("id table hash  : {}", idtable.hash);
+    for section in &idtable.sections {
+        println!("  {:<24} {:>6} ids", section.name, section.ids.len());
+    }
+
+    let gamedata_size = fs::metadata(save_dir.join("gamedata"))?.len();
+    println!("gamedata size  : {gamedata_size}");
+
+    let map_dir = save_dir.join("maps");
+    let ids = map_ids(&map_dir)?;
+    let mut maps_size = 0;
+    for id in &ids {
+        maps_size += fs::metadata(map_dir.join(format!("{id:016x}")))?.len();
+    }
+    println!("maps           : {} files, {} bytes", ids.len(), maps_size);
+
+    Ok(())
+}
+
+/// Export the save directory to an archive file
+pub fn export(save_dir: &Path, archive: &Path) -> Result<()> {
+    let meta = read_metadata(save_dir)?;
+    let version = meta.format_version();
+    migration::check_version(version)?;
+
+    let file = BufReader::new(File::open(save_dir.join("gamedata")).context("opening gamedata")?);
+    let gamedata: GameData =
+        migration::read_gamedata(file, version).context("decoding gamedata")?;
+    gamedata.export_archive(save_dir, archive)
+}
+
+fn read_metadata(save_dir: &Path) -> Result<MetaData> {
+    let file = BufReader::new(File::open(save_dir.join("metadata")).context("opening metadata")?);
+    Ok(serde_json::from_reader(file).context("decoding metadata")?)
+}
+
+fn read_idtable(save_dir: &Path) -> Result<IdTable> {
+    let file = BufReader::new(File::open(save_dir.join("idtable")).context("opening idtable")?);
+    IdTable::read(file).context("decoding idtable")
+}
+
+/// Get ids of map files in the map directory
+fn map_ids(map_dir: &Path) -> Result<Vec<u64>> {
+    let mut ids = Vec::new();
+    for entry in fs::read_dir(map_dir).context("reading map directory")? {
+        let path = entry?.path();
+        if !path.is_file() {
+            continue;
+        }
+        let id = path
+            .file_name()
+            .and_then(|name| name.to_str())
+            .and_then(|name| u64::from_str_radix(name, 16).ok());
+        if let Some(id) = id {
+            ids.push(id);
+        }
+    }
+    ids.sort_unstable();
+    Ok(ids)
+}
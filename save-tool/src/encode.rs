@@ -0,0 +1,140 @@
+use crate::format::{self, Format};
+use crate::idtable::IdTable;
+use anyhow::{Context, Result};
+use common::basic::SAVE_FORMAT_VERSION;
+use common::gamedata::{BoxedMap, GameData, Map, MetaData};
+use common::saveload::{self, sync_file, thumbnail::THUMBNAIL_FILE};
+use common::utils::to_writer_with_mode;
+use std::fs::{self, File};
+use std::io::BufWriter;
+use std::path::Path;
+
+/// Encode decoded files to the save directory.
+/// Files are written to a staging directory at first, and the previous save is kept as a backup.
+pub fn encode(input_dir: &Path, save_dir: &Path) -> Result<()> {
+    let mut meta: MetaData = format::read(&input_dir.join("metadata")).context("metadata")?;
+    let idtable: IdTable = format::read(&input_dir.join("idtable")).context("idtable")?;
+    let gamedata: GameData = format::read(&input_dir.join("gamedata")).context("gamedata")?;
+
+    let staging_dir = saveload::staging_dir(save_dir);
+    if staging_dir.exists() {
+        fs::remove_dir_all(&staging_dir)?;
+    }
+    fs::create_dir_all(&staging_dir)?;
+
+    // Encoded data always has the current layout
+    meta.set_format_version(SAVE_FORMAT_VERSION);
+    if let Err(e) = write_save_files(input_dir, &staging_dir, &meta, &idtable, &gamedata) {
+        let _ = fs::remove_dir_all(&staging_dir);
+        return Err(e);
+    }
+
+    saveload::replace_save(&staging_dir, save_dir, 1)
+}
+
+fn write_save_files(
+    input_dir: &Path,
+    save_dir: &Path,
+    meta: &MetaData,
+    idtable: &IdTable,
+    gamedata: &GameData,
+) -> Result<()> {
+    let map_dir = save_dir.join("maps");
+    fs::create_dir_all(&map_dir)?;
+
+    let mut file = BufWriter::new(File::create(save_dir.join("metadata"))?);
+    serde_json::to_writer_pretty(&mut file, meta)?;
+    sync_file(file)?;
+
+    let mut file = BufWriter::new(File::create(save_dir.join("idtable"))?);
+    idtable.write(&mut file)?;
+    sync_file(file)?;
+
+    let mut file = BufWriter::new(File::create(save_dir.join("gamedata"))?);
+    to_writer_with_mode(&mut file, gamedata)?;
+    sync_file(file)?;
+
+    let thumbnail = input_dir.join(THUMBNAIL_FILE);
+    if thumbnail.exists() {
+        fs::copy(&thumbnail, save_dir.join(THUMBNAIL_FILE)).context("copying thumbnail")?;
+    }
+
+    for entry in fs::read_dir(input_dir.join("maps")).context("reading map directory")? {
+        let path = entry?.path();
+        if Format::from_path(&path).is_none() {
+            continue;
+        }
+        let id = path
+            .file_stem()
+            .and_then(|name| name.to_str())
+            .and_then(|name| u64::from_str_radix(name, 16).ok());
+        let id = if let Some(id) = id { id } else { continue };
+
+        let map: Map =
+            format::read(&path.with_extension("")).with_context(|| format!("map {id:016x}"))?;
+        BoxedMap::write_force(&BoxedMap::new(id, map), &map_dir)
+            .with_context(|| format!("writing map {id:016x}"))?;
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::idtable::Section;
+    use common::gamedata::{Chara, CharaId, Time};
+
+    #[test]
+    fn encode_and_decode() {
+        let dir = crate::test_dir("encode");
+        let input_dir = dir.join("input");
+        let save_dir = dir.join("a.rrsve");
+        fs::create_dir_all(input_dir.join("maps")).unwrap();
+
+        let mut meta = MetaData::default();
+        meta.set_save_name("a");
+        let idtable = IdTable {
+            hash: "0123456789abcdef".into(),
+            sections: vec![Section {
+                name: "item".into(),
+                ids: vec!["!".into()],
+            }],
+        };
+        let mut gd = GameData::empty();
+        let cid = CharaId::Global { id: 1 };
+        gd.add_chara(
+            cid,
+            Chara {
+                name: Some("test".into()),
+                ..Chara::default()
+            },
+        );
+        format::write(&input_dir.join("metadata"), &meta, Format::Json).unwrap();
+        format::write(&input_dir.join("idtable"), &idtable, Format::Ron).unwrap();
+        format::write(&input_dir.join("gamedata"), &gd, Format::Ron).unwrap();
+        let map = Map::new(2, 3, Time::default());
+        let map_path = input_dir.join("maps").join(format!("{:016x}", 5));
+        format::write(&map_path, &map, Format::Ron).unwrap();
+        fs::write(input_dir.join(THUMBNAIL_FILE), b"png").unwrap();
+
+        encode(&input_dir, &save_dir).unwrap();
+        // The previous save is kept as a backup
+        encode(&input_dir, &save_dir).unwrap();
+        assert!(save_dir.join("maps").join(format!("{:016x}", 5)).exists());
+        assert_eq!(saveload::backup_dirs(&save_dir).len(), 1);
+        assert!(!saveload::staging_dir(&save_dir).exists());
+
+        let output_dir = dir.join("output");
+        crate::decode::decode(&save_dir, &output_dir, Format::Ron).unwrap();
+        let decoded: GameData = format::read(&output_dir.join("gamedata")).unwrap();
+        assert_eq!(decoded.chara.get(cid).name.as_deref(), Some("test"));
+        let decoded: Map =
+            format::read(&output_dir.join("maps").join(format!("{:016x}", 5))).unwrap();
+        assert_eq!((decoded.w, decoded.h), (2, 3));
+        assert_eq!(fs::read(save_dir.join(THUMBNAIL_FILE)).unwrap(), b"png");
+        assert_eq!(fs::read(output_dir.join(THUMBNAIL_FILE)).unwrap(), b"png");
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
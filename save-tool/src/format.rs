@@ -0,0 +1,97 @@
+use anyhow::{bail, Context, Result};
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Human-readable output format
+#[derive(Clone, Copy, PartialEq, Eq, Debug, clap::ValueEnum)]
+pub enum Format {
+    Ron,
+    Json,
+}
+
+impl Format {
+    pub const ALL: [Format; 2] = [Format::Ron, Format::Json];
+
+    pub fn extension(self) -> &'static str {
+        match self {
+            Format::Ron => "ron",
+            Format::Json => "json",
+        }
+    }
+
+    pub fn from_path(path: &Path) -> Option<Format> {
+        let ext = path.extension()?;
+        Format::ALL.into_iter().find(|f| ext == f.extension())
+    }
+}
+
+/// Write a value to "<path>.<ext>"
+pub fn write<T: Serialize>(path: &Path, value: &T, format: Format) -> Result<()> {
+    let path = path.with_extension(format.extension());
+    let s = match format {
+        Format::Ron => ron::ser::to_string_pretty(value, ron::ser::PrettyConfig::default())?,
+        Format::Json => serde_json::to_string_pretty(value)
+            .context("JSON cannot represent maps keyed by composite ids, use RON instead")?,
+    };
+    fs::write(path, s)?;
+    Ok(())
+}
+
+/// Read a value from the file which has any supported extension
+pub fn read<T: DeserializeOwned>(path: &Path) -> Result<T> {
+    let path = find(path)?;
+    let s = fs::read_to_string(&path)?;
+    let value = match Format::from_path(&path) {
+        Some(Format::Ron) => ron::de::from_str(&s)?,
+        Some(Format::Json) => serde_json::from_str(&s)?,
+        None => unreachable!(),
+    };
+    Ok(value)
+}
+
+/// Find the file with supported extension for the given path without extension
+fn find(path: &Path) -> Result<PathBuf> {
+    for format in Format::ALL {
+        let path = path.with_extension(format.extension());
+        if path.exists() {
+            return Ok(path);
+        }
+    }
+    bail!("file not found: {}.{{ron,json}}", path.display());
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use common::gamedata::{Chara, CharaId, GameData};
+
+    fn gamedata() -> GameData {
+        let mut gd = GameData::empty();
+        let cid = CharaId::Global { id: 1 };
+        let chara = Chara {
+            name: Some("test".into()),
+            ..Chara::default()
+        };
+        gd.add_chara(cid, chara);
+        gd
+    }
+
+    #[test]
+    fn composite_map_keys() {
+        let dir = crate::test_dir("format");
+        let path = dir.join("gamedata");
+        let cid = CharaId::Global { id: 1 };
+
+        write(&path, &gamedata(), Format::Ron).unwrap();
+        let gd: GameData = read(&path).unwrap();
+        assert_eq!(gd.chara.get(cid).name.as_deref(), Some("test"));
+
+        let e = write(&dir.join("gamedata2"), &gamedata(), Format::Json).unwrap_err();
+        assert!(e.to_string().contains("use RON"));
+        assert!(read::<GameData>(&dir.join("gamedata2")).is_err());
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
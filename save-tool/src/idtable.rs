@@ -0,0 +1,78 @@
+use anyhow::{Context, Result};
+use common::basic::ID_TABLE_SECTION_TAG;
+use std::io::{BufRead, Write};
+
+/// Decoded id table. Each object index is the position in the section.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct IdTable {
+    /// Hash of ObjectHolder at saving
+    pub hash: String,
+    pub sections: Vec<Section>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct Section {
+    pub name: String,
+    pub ids: Vec<String>,
+}
+
+impl IdTable {
+    pub fn read<R: BufRead>(r: R) -> Result<IdTable> {
+        let mut lines = r.lines();
+        let hash = lines.next().context("empty id table")??.trim().to_owned();
+        let mut sections: Vec<Section> = Vec::new();
+
+        for line in lines {
+            let line = line?;
+            if line.starts_with(ID_TABLE_SECTION_TAG) {
+                sections.push(Section {
+                    name: line
+                        .trim_start_matches(ID_TABLE_SECTION_TAG)
+                        .trim_end()
+                        .to_owned(),
+                    ids: Vec::new(),
+                });
+            } else {
+                let section = sections
+                    .last_mut()
+                    .context("id table does not start with a section")?;
+                section.ids.push(line);
+            }
+        }
+
+        Ok(IdTable { hash, sections })
+    }
+
+    pub fn write<W: Write>(&self, mut w: W) -> Result<()> {
+        writeln!(w, "{}", self.hash)?;
+        for section in &self.sections {
+            writeln!(w, "{}{}", ID_TABLE_SECTION_TAG, section.name)?;
+            for id in &section.ids {
+                writeln!(w, "{id}")?;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn read_write() {
+        let src = format!(
+            "0123456789abcdef\n{tag}chara_template\n!\nfoo\n{tag}item\nbar\n",
+            tag = ID_TABLE_SECTION_TAG
+        );
+        let idtable = IdTable::read(src.as_bytes()).unwrap();
+        assert_eq!(idtable.sections.len(), 2);
+        assert_eq!(idtable.sections[0].ids, vec!["!", "foo"]);
+
+        let mut out = Vec::new();
+        idtable.write(&mut out).unwrap();
+        assert_eq!(String::from_utf8(out).unwrap(), src);
+
+        assert!(IdTable::read("hash\nfoo\n".as_bytes()).is_err());
+    }
+}
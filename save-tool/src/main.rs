@@ -0,0 +1,87 @@
+#![warn(
+    rust_2018_compatibility,
+    rust_2018_idioms,
+    future_incompatible,
+    nonstandard_style
+)]
+
+#[macro_use]
+extern crate serde_derive;
+extern crate rusted_ruins_common as common;
+
+mod decode;
+mod encode;
+mod format;
+mod idtable;
+
+use clap::Parser;
+use std::path::PathBuf;
+
+/// Inspect and edit save data of Rusted Ruins
+#[derive(Parser, Debug)]
+#[clap(author, version, about)]
+enum Args {
+    /// Decode a save directory to human-readable files
+    Decode {
+        save_dir: PathBuf,
+        output_dir: PathBuf,
+        /// Output format. JSON cannot represent maps keyed by composite ids in gamedata
+        #[clap(long, value_enum, default_value = "ron")]
+        format: format::Format,
+    },
+    /// Encode decoded files to a save directory
+    Encode {
+        input_dir: PathBuf,
+        save_dir: PathBuf,
+    },
+    /// Print summary of a save directory
+    Info { save_dir: PathBuf },
+    /// Export a save directory to a single archive file
+    Export { save_dir: PathBuf, archive: PathBuf },
+    /// Import an archive file to a new save directory in the given directory
+    Import {
+        archive: PathBuf,
+        save_root: PathBuf,
+    },
+}
+
+fn main() {
+    let args = Args::parse();
+
+    let result = match args {
+        Args::Decode {
+            save_dir,
+            output_dir,
+            format,
+        } => decode::decode(&save_dir, &output_dir, format),
+        Args::Encode {
+            input_dir,
+            save_dir,
+        } => encode::encode(&input_dir, &save_dir),
+        Args::Info { save_dir } => decode::print_info(&save_dir),
+        Args::Export { save_dir, archive } => decode::export(&save_dir, &archive),
+        Args::Import { archive, save_root } => {
+            common::saveload::archive::import_archive(&archive, &save_root).map(|save_dir| {
+                println!("imported to {}", save_dir.display());
+            })
+        }
+    };
+
+    if let Err(e) = result {
+        for e in e.chain() {
+            eprintln!("{e}");
+        }
+        std::process::exit(1);
+    }
+}
+
+/// Create an empty directory for tests
+#[cfg(test)]
+fn test_dir(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("rr-save-test-{}-{}", name, std::process::id()));
+    if dir.exists() {
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+    std::fs::create_dir_all(&dir).unwrap();
+    dir
+}
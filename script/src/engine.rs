@@ -160,11 +160,7 @@ fn call_script(
         .set_item("game", pygame.into_pyobject(vm), vm)
         .map_err(|e| Error::from_py(vm, e))?;
 
-    let script = vm.compile(
-        &script_obj.script,
-        vm::compiler::Mode::Exec,
-        start_script.id.clone(),
-    )?;
+    let script = load_code(vm, script_obj)?;
     vm.run_code_obj(script, scope)
         .map_err(|e| Error::from_py(vm, e))?;
 
@@ -172,6 +168,31 @@ fn call_script(
     Ok(())
 }
 
+/// Load bytecode in the script object, or compile the script if it has no bytecode
+fn load_code(
+    vm: &vm::VirtualMachine,
+    script_obj: &ScriptObject,
+) -> Result<vm::PyRef<vm::builtins::PyCode>, Error> {
+    if let Some(byte_code) = &script_obj.byte_code {
+        match vm::bytecode::CodeObject::from_bytes(byte_code) {
+            Ok(code) => return Ok(vm.ctx.new_code(code)),
+            Err(e) => {
+                log::warn!(
+                    "cannot load bytecode of script \"{}\", compiling it\n{}",
+                    script_obj.id,
+                    e
+                );
+            }
+        }
+    }
+
+    Ok(vm.compile(
+        &script_obj.script,
+        vm::compiler::Mode::Exec,
+        script_obj.id.clone(),
+    )?)
+}
+
 fn load_modules(vm: &vm::VirtualMachine) -> Result<(), Error> {
     let code_obj = vm.compile(
         r#"import rr"#,